@@ -8,16 +8,17 @@
 use std::io::{Read, Seek};
 
 use rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng};
+use serde::Serialize;
 use tracing::trace;
 
 use crate::ss2_common::{read_string_with_size, read_u32};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Song {
     sections: Vec<SongSection>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SongSection {
     name: String,
     wav_file: String,
@@ -43,7 +44,7 @@ impl SongSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SongSectionOption {
     pub schema: String,
     pub sub_options: Vec<SubOption>,
@@ -64,7 +65,7 @@ impl SongSectionOption {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SubOption {
     pub next_index: u32,
     pub probability: u32,
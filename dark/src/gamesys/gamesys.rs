@@ -1,7 +1,7 @@
 use core::fmt;
 use std::{collections::HashMap, io};
 
-use rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng};
+use rand::{thread_rng, Rng};
 use shipyard::{Component, Get, IntoIter, IntoWithId, View, World};
 use tracing::info;
 
@@ -28,19 +28,27 @@ impl Gamesys {
             return None;
         }
 
-        let sample = result[0];
-        let maybe_samples = self.sound_schema.id_to_samples.get(&sample);
-
-        maybe_samples?;
-
-        let samples = maybe_samples.unwrap();
+        self.sound_schema.get_random_sample_by_id(result[0])
+    }
 
-        let mut rng = thread_rng();
-        let weights = samples.iter().map(|s| s.frequency).collect::<Vec<u8>>();
-        let weight_index = WeightedIndex::new(weights).unwrap();
-        let idx = weight_index.sample(&mut rng);
+    ///
+    /// get_random_speech
+    ///
+    /// Pick a random sample for a voice (ie, 'vhybrid1') speaking a concept (ie, 'spotplayer')
+    pub fn get_random_speech(
+        &self,
+        voice: &str,
+        concept: &str,
+        query: &EnvSoundQuery,
+    ) -> Option<String> {
+        let schemas = self.speech_db.query(voice, concept, query);
+
+        if schemas.is_empty() {
+            return None;
+        }
 
-        Some(samples[idx].sample_name.to_owned())
+        let idx = thread_rng().gen_range(0..schemas.len());
+        self.sound_schema.get_random_sample_by_id(schemas[idx])
     }
 }
 
@@ -63,7 +71,7 @@ pub fn read<T: io::Read + io::Seek>(
     let sound_schema = SoundSchema::read(&table_of_contents, reader, &entity_info);
 
     let env_tag_map = EnvMap::read(&table_of_contents, reader);
-    let speech_db = SpeechDB::read(&table_of_contents, reader);
+    let speech_db = SpeechDB::read(&table_of_contents, reader, &entity_info);
 
     // Uncomment to output debug info for voices:
     // debug_print_voices(&sound_schema, &speech_db);
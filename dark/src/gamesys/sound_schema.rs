@@ -26,17 +26,12 @@ pub struct SoundSchema {
 impl SoundSchema {
     pub fn get_random_sample(&self, schema: &str) -> Option<String> {
         let maybe_samples = self.name_to_samples.get(&schema.to_ascii_lowercase());
+        maybe_samples.and_then(|samples| pick_weighted_sample(samples))
+    }
 
-        if let Some(samples) = maybe_samples {
-            let mut rng = thread_rng();
-            let weights = samples.iter().map(|s| s.frequency).collect::<Vec<u8>>();
-            let weight_index = WeightedIndex::new(weights).unwrap();
-            let idx = weight_index.sample(&mut rng);
-
-            Some(samples[idx].sample_name.to_owned())
-        } else {
-            None
-        }
+    pub fn get_random_sample_by_id(&self, schema_id: i32) -> Option<String> {
+        let maybe_samples = self.id_to_samples.get(&schema_id);
+        maybe_samples.and_then(|samples| pick_weighted_sample(samples))
     }
 
     pub fn read<T: io::Read + io::Seek>(
@@ -110,3 +105,16 @@ impl SoundSchema {
         }
     }
 }
+
+fn pick_weighted_sample(samples: &[SchemaSample]) -> Option<String> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut rng = thread_rng();
+    let weights = samples.iter().map(|s| s.frequency).collect::<Vec<u8>>();
+    let weight_index = WeightedIndex::new(weights).ok()?;
+    let idx = weight_index.sample(&mut rng);
+
+    Some(samples[idx].sample_name.to_owned())
+}
@@ -1,9 +1,13 @@
-use std::io;
+use std::{collections::HashMap, io};
+
+use shipyard::{IntoIter, View, World};
 
 use crate::{
+    properties::{PropSymName, PropVoiceIndex},
     ss2_chunk_file_reader::ChunkFileTableOfContents,
     ss2_common::{read_bytes, read_u32},
-    NameMap, TagDatabase,
+    ss2_entity_info::SystemShock2EntityInfo,
+    EnvSoundQuery, NameMap, TagDatabase,
 };
 
 #[derive(Debug, Clone)]
@@ -29,12 +33,39 @@ pub struct SpeechDB {
     pub tag_map: NameMap,
     pub value_map: NameMap,
     pub voices: Vec<Voice>,
+    voice_name_to_index: HashMap<String, usize>,
 }
 
 impl SpeechDB {
+    ///
+    /// query
+    ///
+    /// Find the schema ids that a voice (ie, 'vhybrid1') could say for a concept (ie, 'spotplayer'),
+    /// narrowed down by the tags in the query.
+    pub fn query(&self, voice_name: &str, concept: &str, query: &EnvSoundQuery) -> Vec<i32> {
+        let maybe_voice = self
+            .voice_name_to_index
+            .get(&voice_name.to_ascii_lowercase())
+            .and_then(|idx| self.voices.get(*idx));
+        let maybe_concept = self.concept_map.get_index(&concept.to_ascii_lowercase());
+
+        match (maybe_voice, maybe_concept) {
+            (Some(voice), Some(concept_idx)) => {
+                let tag_query = query.to_tag_query(&self.tag_map, &self.value_map);
+                voice
+                    .tag_maps
+                    .get(concept_idx as usize)
+                    .map(|tag_db| tag_db.query_match_all_or_default(&tag_query))
+                    .unwrap_or_default()
+            }
+            _ => vec![],
+        }
+    }
+
     pub fn read<T: io::Read + io::Seek>(
         table_of_contents: &ChunkFileTableOfContents,
         reader: &mut T,
+        gamesys_entity_info: &SystemShock2EntityInfo,
     ) -> SpeechDB {
         // Read SchSamp chunk
         let schema_chunk = table_of_contents.get_chunk("Speech_DB".to_owned()).unwrap();
@@ -57,16 +88,39 @@ impl SpeechDB {
         let num_concepts = concept_map.count();
 
         let mut voices = Vec::new();
-        for idx in 0..num_voices {
+        for _ in 0..num_voices {
             let voice = Voice::read(reader, num_concepts);
             voices.push(voice);
         }
 
+        // The voice archetypes (ie, 'vhybrid1') carry the index into the voice list,
+        // so initialize their props to build a sym name -> voice index map
+        let mut world = World::new();
+        for props in gamesys_entity_info.entity_to_properties.values() {
+            let entity = world.add_entity(());
+            for prop in props {
+                prop.initialize(&mut world, entity);
+            }
+        }
+
+        let mut voice_name_to_index = HashMap::new();
+        world.run(
+            |v_sym_name: View<PropSymName>, v_voice_index: View<PropVoiceIndex>| {
+                for (sym_name, voice_index) in (&v_sym_name, &v_voice_index).iter() {
+                    if voice_index.0 >= 0 && (voice_index.0 as u32) < num_voices {
+                        voice_name_to_index
+                            .insert(sym_name.0.to_ascii_lowercase(), voice_index.0 as usize);
+                    }
+                }
+            },
+        );
+
         SpeechDB {
             concept_map,
             tag_map,
             value_map,
             voices,
+            voice_name_to_index,
         }
     }
 }
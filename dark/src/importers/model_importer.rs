@@ -57,3 +57,9 @@ fn process_model(
 
 pub static MODELS_IMPORTER: Lazy<AssetImporter<SystemShockContentModel, Model, ()>> =
     Lazy::new(|| AssetImporter::define(load_model, process_model));
+
+// Importer for the parsed model data, without building scene objects - for tools that need
+// the raw vertices, materials, and skeleton
+pub static MODEL_DATA_IMPORTER: Lazy<
+    AssetImporter<SystemShockContentModel, SystemShockContentModel, ()>,
+> = Lazy::new(|| AssetImporter::define(load_model, |content, _assets, _config| content));
@@ -12,6 +12,22 @@ pub(crate) fn load_texture(
     reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
     _assets: &mut AssetCache,
     _config: &TextureOptions,
+) -> RawTextureData {
+    read_texture_data(name, reader)
+}
+
+fn load_raw_texture(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
+    _assets: &mut AssetCache,
+    _config: &(),
+) -> RawTextureData {
+    read_texture_data(name, reader)
+}
+
+fn read_texture_data(
+    name: String,
+    reader: &mut Box<dyn engine::assets::asset_paths::ReadableAndSeekable>,
 ) -> RawTextureData {
     let extension = Path::new(&name).extension().unwrap();
     let format =
@@ -32,3 +48,8 @@ pub(crate) fn process_texture(
 
 pub static TEXTURE_IMPORTER: Lazy<AssetImporter<RawTextureData, Texture, TextureOptions>> =
     Lazy::new(|| AssetImporter::define(load_texture, process_texture));
+
+// Importer for the decoded pixels, without creating a GPU texture - for tools that need to
+// read or export the image data
+pub static RAW_TEXTURE_IMPORTER: Lazy<AssetImporter<RawTextureData, RawTextureData, ()>> =
+    Lazy::new(|| AssetImporter::define(load_raw_texture, |data, _assets, _config| data));
@@ -23,6 +23,9 @@ use crate::SCALE_FACTOR;
 use super::CellPortal;
 use super::Plane;
 
+// Polygons with a normal at least this far up are walkable floors
+const FLOOR_MIN_NORMAL_Y: f32 = 0.7;
+
 #[derive(Debug)]
 pub struct Cell {
     pub idx: u32,
@@ -36,6 +39,9 @@ pub struct Cell {
     pub planes: Vec<Plane>,
     pub vertices: Vec<Vector3<f32>>,
     pub lights: Vec<LightInfo>,
+
+    // Animated lights that touch this cell - bit N of a polygon's animation flags refers to entry N
+    pub anim_light_indices: Vec<i16>,
 }
 
 impl Cell {
@@ -102,7 +108,7 @@ impl Cell {
             planes.push(plane);
         }
 
-        let lights = read_lights(
+        let (lights, anim_light_indices) = read_lights(
             packer,
             cell_idx,
             reader,
@@ -125,10 +131,58 @@ impl Cell {
             planes,
             vertices,
             lights,
+            anim_light_indices,
         };
         cell
     }
 
+    ///
+    /// floor_texture_below
+    ///
+    /// Find the closest upward-facing render polygon directly below the position,
+    /// returning the height of the polygon and its index into the texture list
+    pub fn floor_texture_below(&self, position: Vector3<f32>) -> Option<(f32, u16)> {
+        let mut best: Option<(f32, u16)> = None;
+
+        for (poly_idx, render_poly) in self.textured_polygons.iter().enumerate() {
+            let polygon = &self.polygons[poly_idx];
+            let plane = &self.planes[polygon.plane_id as usize];
+
+            // Only consider floors - cell planes face into the cell, so a floor faces up, and a
+            // ceiling faces down
+            if plane.normal.y < FLOOR_MIN_NORMAL_Y {
+                continue;
+            }
+
+            let vertices = self.polygon_indices[poly_idx]
+                .iter()
+                .map(|idx| self.vertices[*idx as usize] / SCALE_FACTOR)
+                .collect::<Vec<Vector3<f32>>>();
+
+            if !is_point_in_polygon_xz(&vertices, position) {
+                continue;
+            }
+
+            let height = -(plane.normal.x * position.x + plane.normal.z * position.z + plane.w)
+                / plane.normal.y;
+
+            if height > position.y {
+                continue;
+            }
+
+            let is_closer = match best {
+                Some((best_height, _)) => height > best_height,
+                None => true,
+            };
+
+            if is_closer {
+                best = Some((height, render_poly.texture_num));
+            }
+        }
+
+        best
+    }
+
     pub fn debug_render(&self) -> Vec<SceneObject> {
         let mut ret = Vec::new();
 
@@ -338,10 +392,11 @@ fn read_lights<T: io::Read>(
     num_lights: u8,
     num_lightmaps: u8,
     light_size: u8,
-) -> Vec<LightInfo> {
+) -> (Vec<LightInfo>, Vec<i16>) {
     // Read lights
+    let mut anim_light_indices = Vec::new();
     for _ in 0..num_lights {
-        let _ = reader.read_i16::<byteorder::LittleEndian>().unwrap();
+        anim_light_indices.push(reader.read_i16::<byteorder::LittleEndian>().unwrap());
     }
 
     let mut light_infos: Vec<LightInfo> = Vec::new();
@@ -352,36 +407,36 @@ fn read_lights<T: io::Read>(
 
     for i in 0..num_lightmaps {
         let li = light_infos.get_mut(i as usize).unwrap();
-        let lm_count = li.animation_flags.count_ones() + 1;
+
+        // The first lightmap is the static one, followed by one for each animated light
+        // set in the animation flags, in bit order
+        let animated_light_bits: Vec<u32> = (0..32)
+            .filter(|bit| li.animation_flags & (1u32 << *bit) != 0)
+            .collect();
 
         let lm_size = (light_size as u16) * li.lx * (li.ly as u16);
 
-        for idx in 0..lm_count {
+        let mut bytes = vec![0_u8; lm_size as usize];
+        reader.read_exact(&mut bytes).unwrap();
+        let static_lightmap = decode_lightmap(&bytes, li.lx, li.ly);
+        li.texture_pack_result = packer.pack(&static_lightmap);
+
+        for bit in animated_light_bits {
             let mut bytes = vec![0_u8; lm_size as usize];
             reader.read_exact(&mut bytes).unwrap();
 
-            if idx == 0 {
-                let img = image::ImageBuffer::from_fn(li.lx as u32, li.ly as u32, |x, y| {
-                    if x >= li.lx as u32 || y >= li.ly as u32 {
-                        image::Rgb([255, 255, 0])
-                    } else {
-                        let pos = ((y * (2 * li.lx as u32)) + x * 2) as usize;
-                        let b0 = bytes[pos] as u16;
-                        let b1 = bytes[pos + 1] as u16;
-
-                        // Two bits (u16) encoded to have R,G,B each taking 5 bits:
-                        let pix: u16 = (b1 << 8) + b0;
-                        let r = (pix & 0b0001_1111) << 3;
-                        let g = ((pix >> 5) & 0b0001_1111) << 3;
-                        let b = ((pix >> 10) & 0b0001_1111) << 3;
-
-                        image::Rgb([r as u8, g as u8, b as u8])
-                    }
+            if let Some(light_index) = anim_light_indices.get(bit as usize) {
+                li.animated_lightmaps.push(AnimatedLightmap {
+                    light_index: *light_index,
+                    lightmap: decode_lightmap(&bytes, li.lx, li.ly),
                 });
-
-                li.texture_pack_result = packer.pack(&img);
             }
         }
+
+        // Only hang on to the static lightmap if we need to recombine it with the animated ones
+        if !li.animated_lightmaps.is_empty() {
+            li.static_lightmap = Some(static_lightmap);
+        }
     }
 
     let light_count = reader.read_u32::<byteorder::LittleEndian>().unwrap();
@@ -389,7 +444,33 @@ fn read_lights<T: io::Read>(
         let _ = reader.read_u16::<byteorder::LittleEndian>().unwrap();
     }
 
-    light_infos
+    (light_infos, anim_light_indices)
+}
+
+fn decode_lightmap(bytes: &[u8], lx: u16, ly: u8) -> image::RgbImage {
+    image::ImageBuffer::from_fn(lx as u32, ly as u32, |x, y| {
+        let pos = ((y * (2 * lx as u32)) + x * 2) as usize;
+        let b0 = bytes[pos] as u16;
+        let b1 = bytes[pos + 1] as u16;
+
+        // Two bits (u16) encoded to have R,G,B each taking 5 bits:
+        let pix: u16 = (b1 << 8) + b0;
+        let r = (pix & 0b0001_1111) << 3;
+        let g = ((pix >> 5) & 0b0001_1111) << 3;
+        let b = ((pix >> 10) & 0b0001_1111) << 3;
+
+        image::Rgb([r as u8, g as u8, b as u8])
+    })
+}
+
+///
+/// AnimatedLightmap
+///
+/// The contribution of a single animated light to a polygon's lightmap, at full brightness
+#[derive(Debug)]
+pub struct AnimatedLightmap {
+    pub light_index: i16,
+    pub lightmap: image::RgbImage,
 }
 
 #[derive(Debug)]
@@ -404,6 +485,51 @@ pub struct LightInfo {
     pub dynamic_lightmap_pointer: u32,
     pub animation_flags: u32,
     pub texture_pack_result: TexturePackResult,
+
+    // Only populated for polygons lit by animated lights
+    pub static_lightmap: Option<image::RgbImage>,
+    pub animated_lightmaps: Vec<AnimatedLightmap>,
+}
+
+impl LightInfo {
+    ///
+    /// combine_lightmaps
+    ///
+    /// Combine the static lightmap with the animated lightmaps, each scaled by the intensity (0..1) of its light.
+    /// Returns None if the polygon isn't lit by any animated lights.
+    pub fn combine_lightmaps(&self, intensity: impl Fn(i16) -> f32) -> Option<image::RgbImage> {
+        let static_lightmap = self.static_lightmap.as_ref()?;
+
+        let layers: Vec<(&image::RgbImage, f32)> = self
+            .animated_lightmaps
+            .iter()
+            .map(|animated_lightmap| {
+                let scale = intensity(animated_lightmap.light_index).clamp(0.0, 1.0);
+                (&animated_lightmap.lightmap, scale)
+            })
+            .filter(|(_, scale)| *scale > 0.0)
+            .collect();
+
+        Some(image::ImageBuffer::from_fn(
+            static_lightmap.width(),
+            static_lightmap.height(),
+            |x, y| {
+                let mut pixel = static_lightmap
+                    .get_pixel(x, y)
+                    .0
+                    .map(|channel| channel as f32);
+
+                for (lightmap, scale) in &layers {
+                    let layer_pixel = lightmap.get_pixel(x, y).0;
+                    for (dest, src) in pixel.iter_mut().zip(layer_pixel.iter()) {
+                        *dest += *src as f32 * scale;
+                    }
+                }
+
+                image::Rgb(pixel.map(|channel| channel.min(255.0) as u8))
+            },
+        ))
+    }
 }
 
 fn read_light_info<T: io::Read>(debug_idx: u32, reader: &mut T) -> LightInfo {
@@ -429,5 +555,89 @@ fn read_light_info<T: io::Read>(debug_idx: u32, reader: &mut T) -> LightInfo {
         dynamic_lightmap_pointer,
         animation_flags,
         texture_pack_result: TexturePackResult::DEFAULT,
+        static_lightmap: None,
+        animated_lightmaps: Vec::new(),
+    }
+}
+
+fn is_point_in_polygon_xz(vertices: &[Vector3<f32>], point: Vector3<f32>) -> bool {
+    if vertices.len() < 3 {
+        return false;
+    }
+
+    // The polygons are convex, so the point is inside if it is on the same side of every edge
+    let mut sign = 0.0;
+    for i in 0..vertices.len() {
+        let a = vertices[i];
+        let b = vertices[(i + 1) % vertices.len()];
+        let cross = (b.x - a.x) * (point.z - a.z) - (b.z - a.z) * (point.x - a.x);
+
+        if cross.abs() < f32::EPSILON {
+            continue;
+        }
+
+        if sign == 0.0 {
+            sign = cross.signum();
+        } else if cross.signum() != sign {
+            return false;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_point_in_polygon_xz() {
+        // Given - a square, wound either way, and a degenerate sliver
+        let square = vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(2.0, 0.0, 0.0),
+            vec3(2.0, 0.0, 2.0),
+            vec3(0.0, 0.0, 2.0),
+        ];
+        let reversed = square.iter().rev().cloned().collect::<Vec<Vector3<f32>>>();
+        let sliver = vec![vec3(0.0, 0.0, 0.0), vec3(2.0, 0.0, 0.0)];
+
+        // Then - height doesn't matter, only x and z
+        assert!(is_point_in_polygon_xz(&square, vec3(1.0, 5.0, 1.0)));
+        assert!(is_point_in_polygon_xz(&reversed, vec3(1.0, -5.0, 1.0)));
+        assert!(!is_point_in_polygon_xz(&square, vec3(3.0, 0.0, 1.0)));
+        assert!(!is_point_in_polygon_xz(&reversed, vec3(1.0, 0.0, -0.5)));
+        assert!(!is_point_in_polygon_xz(&sliver, vec3(1.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_combine_lightmaps_scales_and_saturates() {
+        // Given - a static lightmap, and two animated lights over it
+        let mut light_info = read_light_info(0, &mut io::Cursor::new(vec![0u8; 20]));
+        light_info.static_lightmap = Some(image::RgbImage::from_pixel(
+            1,
+            1,
+            image::Rgb([100, 100, 100]),
+        ));
+        light_info.animated_lightmaps = vec![
+            AnimatedLightmap {
+                light_index: 1,
+                lightmap: image::RgbImage::from_pixel(1, 1, image::Rgb([200, 100, 0])),
+            },
+            AnimatedLightmap {
+                light_index: 2,
+                lightmap: image::RgbImage::from_pixel(1, 1, image::Rgb([0, 0, 50])),
+            },
+        ];
+
+        // When - the first light is at half intensity, and then both are out of range
+        let half = light_info
+            .combine_lightmaps(|light_index| if light_index == 1 { 0.5 } else { 0.0 })
+            .unwrap();
+        let full = light_info.combine_lightmaps(|_| 2.0).unwrap();
+
+        // Then - intensities are clamped to 0..1, and channels saturate at 255
+        assert_eq!(half.get_pixel(0, 0).0, [200, 150, 100]);
+        assert_eq!(full.get_pixel(0, 0).0, [255, 200, 150]);
     }
 }
@@ -95,6 +95,49 @@ impl SystemShock2Level {
 
         self.cells.get(idx as usize)
     }
+
+    ///
+    /// get_surface_material
+    ///
+    /// Get the material (ie, 'metal') of the floor texture below the position, if any
+    pub fn get_surface_material(&self, position: Vector3<f32>) -> Option<&str> {
+        // The floor may belong to the cell below, if the position is close to a cell boundary
+        let probe_positions = [position, position - vec3(0.0, MAX_FLOOR_DISTANCE, 0.0)];
+
+        let (height, texture_idx) = probe_positions
+            .iter()
+            .filter_map(|probe| self.get_cell_from_position(*probe))
+            .find_map(|cell| cell.floor_texture_below(position))?;
+
+        if position.y - height > MAX_FLOOR_DISTANCE {
+            return None;
+        }
+
+        self.textures
+            .0
+            .get(texture_idx as usize)
+            .and_then(|texture| texture.material.as_deref())
+    }
+
+    ///
+    /// lightmaps_by_animated_light
+    ///
+    /// Map each animated light to the (cell index, light info index) of every lightmap it contributes to
+    pub fn lightmaps_by_animated_light(&self) -> HashMap<i16, Vec<(usize, usize)>> {
+        let mut ret: HashMap<i16, Vec<(usize, usize)>> = HashMap::new();
+
+        for (cell_idx, cell) in self.cells.iter().enumerate() {
+            for (light_info_idx, light_info) in cell.lights.iter().enumerate() {
+                for animated_lightmap in &light_info.animated_lightmaps {
+                    ret.entry(animated_lightmap.light_index)
+                        .or_default()
+                        .push((cell_idx, light_info_idx));
+                }
+            }
+        }
+
+        ret
+    }
 }
 
 #[derive(Debug)]
@@ -105,6 +148,9 @@ pub struct TextureSize {
 
 const LIGHTMAP_SIZE: u32 = 4096;
 
+// How far below a position to look for the floor, when finding the surface material
+const MAX_FLOOR_DISTANCE: f32 = 8.0 / SCALE_FACTOR;
+
 type TextureDimensionProvider = fn(&SystemShock2Texture) -> TextureSize;
 
 pub struct UVCalculationInfo {
@@ -232,6 +278,31 @@ pub fn read<T: io::Read + io::Seek>(
     }
 }
 
+///
+/// read_entities
+///
+/// Read just the objects in the mission - the name map and entity info - without the level
+/// geometry, so tools can inspect a mission without a GL context
+pub fn read_entities<T: io::Read + io::Seek>(
+    reader: &mut T,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+    properties: &Vec<Box<dyn PropertyDefinition<T>>>,
+) -> (HashMap<i32, String>, SystemShock2EntityInfo) {
+    let table_of_contents = ss2_chunk_file_reader::read_table_of_contents(reader);
+
+    let (obj_map, _obj_texture_families) = read_obj_map(&table_of_contents, reader);
+    let entity_info = ss2_entity_info::new(
+        &table_of_contents,
+        links,
+        links_with_data,
+        properties,
+        reader,
+    );
+
+    (obj_map, entity_info)
+}
+
 fn read_obj_map<T: io::Read + io::Seek>(
     table_of_contents: &ChunkFileTableOfContents,
     reader: &mut T,
@@ -470,3 +541,220 @@ fn texture_dimensions(asset_cache: &mut AssetCache, tex_info: &SystemShock2Textu
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::properties::{Link, PropQuestBitName, PropScripts, PropSymName};
+    use dark_fixtures::*;
+    use engine::assets::asset_paths::AssetPath;
+    use shipyard::{Get, View, World};
+    use std::io::Cursor;
+
+    const ARCHETYPE_ID: i32 = -10;
+    const STIMULUS_ID: i32 = -20;
+    const TRAP_ID: i32 = 1;
+    const CRATE_ID: i32 = 2;
+
+    // Dark space (z up, unscaled) -> world space
+    fn to_world(x: f32, y: f32, z: f32) -> Vector3<f32> {
+        vec3(-x, z, y) / SCALE_FACTOR
+    }
+
+    fn gamesys_fixture() -> Vec<u8> {
+        let mut builder = dark_fixtures::gamesys();
+        builder
+            .add_chunk(
+                "P$SymName",
+                property_chunk(&[(ARCHETYPE_ID, string_property("TestArchetype"))]),
+            )
+            .add_chunk(
+                "L$arSrc",
+                link_chunk(&[LinkRecord::new(1, ARCHETYPE_ID, STIMULUS_ID)]),
+            );
+        builder.build()
+    }
+
+    // Two 16x16x8 cells side by side along x, joined by a portal - with a room for each cell,
+    // a trap in the first cell and a crate in the second
+    fn mission_fixture() -> Vec<u8> {
+        let mut textures = TextureListBuilder::new();
+        let texture = textures.add_texture(0, "null");
+
+        let mut world_rep = WorldRepBuilder::new();
+        world_rep.add_cell(
+            CuboidCell::new(vec3(0.0, 0.0, 0.0), vec3(16.0, 16.0, 8.0))
+                .with_texture(texture)
+                .with_portal(Face::East, 1),
+        );
+        world_rep.add_cell(
+            CuboidCell::new(vec3(16.0, 0.0, 0.0), vec3(32.0, 16.0, 8.0))
+                .with_texture(texture)
+                .with_portal(Face::West, 0),
+        );
+        world_rep.add_bsp_node(BspNode::Split {
+            cell: 0,
+            plane: Face::East.index() as u32,
+            front: Some(1),
+            back: Some(2),
+        });
+        world_rep.add_bsp_node(BspNode::Leaf { cell: 0 });
+        world_rep.add_bsp_node(BspNode::Leaf { cell: 1 });
+
+        let mut builder = dark_fixtures::mission(&world_rep, &textures);
+        builder
+            .add_chunk(
+                "ROOM_DB",
+                room_database(&[
+                    CuboidRoom::new(100, 0, vec3(0.0, 0.0, 0.0), vec3(16.0, 16.0, 8.0))
+                        .with_portal(1, Face::East, 1, 2),
+                    CuboidRoom::new(101, 1, vec3(16.0, 0.0, 0.0), vec3(32.0, 16.0, 8.0))
+                        .with_portal(2, Face::West, 0, 1),
+                ]),
+            )
+            .add_chunk(
+                "OBJ_MAP",
+                obj_map(&[(TRAP_ID, "trap"), (CRATE_ID, "crate")]),
+            )
+            .add_chunk(
+                "P$Position",
+                property_chunk(&[
+                    (TRAP_ID, position_property(vec3(8.0, 8.0, 1.0), 0)),
+                    (CRATE_ID, position_property(vec3(24.0, 8.0, 1.0), 1)),
+                ]),
+            )
+            .add_chunk(
+                "P$Scripts",
+                property_chunk(&[(TRAP_ID, scripts_property(&["TrapQBSet"], true))]),
+            )
+            .add_chunk(
+                "P$QBName",
+                property_chunk(&[(TRAP_ID, string_property("test_bit"))]),
+            )
+            .add_chunk(
+                "L$MetaProp",
+                link_chunk(&[
+                    LinkRecord::new(1, TRAP_ID, ARCHETYPE_ID),
+                    LinkRecord::new(2, CRATE_ID, ARCHETYPE_ID),
+                ]),
+            )
+            .add_chunk(
+                "L$SwitchLin",
+                link_chunk(&[LinkRecord::new(3, TRAP_ID, CRATE_ID)]),
+            )
+            .add_chunk(
+                "L$Contains",
+                link_chunk(&[LinkRecord::new(4, CRATE_ID, TRAP_ID)]),
+            )
+            .add_chunk("LD$Contains", link_data_chunk(4, &[(4, u32_property(7))]));
+        builder.build()
+    }
+
+    fn read_fixtures() -> (Gamesys, SystemShock2Level) {
+        let (properties, links, links_with_data) = crate::properties::get::<Cursor<Vec<u8>>>();
+        let gamesys = crate::gamesys::read(
+            &mut Cursor::new(gamesys_fixture()),
+            &links,
+            &links_with_data,
+            &properties,
+        );
+
+        // Only 'null' textures are used, so nothing is loaded from the asset cache
+        let mut asset_cache = AssetCache::new(".".to_owned(), AssetPath::folder(".".to_owned()));
+        let level = read(
+            &mut asset_cache,
+            &mut Cursor::new(mission_fixture()),
+            &gamesys,
+            &links,
+            &links_with_data,
+            &properties,
+        );
+        (gamesys, level)
+    }
+
+    #[test]
+    fn test_read_world_rep() {
+        let (_, level) = read_fixtures();
+
+        assert_eq!(level.cells.len(), 2);
+        assert_eq!(level.cells[0].portals.len(), 1);
+        assert_eq!(level.cells[0].portals[0].target_cell_idx, 1);
+        assert_eq!(level.cells[1].portals[0].target_cell_idx, 0);
+
+        // Five walls per cell, each a quad split into two triangles
+        assert_eq!(level.all_geometry.len(), 10);
+        assert!(level.all_geometry.iter().all(|geo| geo.verts.len() == 6));
+        assert_eq!(level.textures.0.len(), 1);
+
+        assert_eq!(
+            level.get_cell_idx_from_position(to_world(8.0, 8.0, 4.0)),
+            Some(0)
+        );
+        assert_eq!(
+            level.get_cell_idx_from_position(to_world(24.0, 8.0, 4.0)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_read_rooms() {
+        let (_, level) = read_fixtures();
+
+        let rooms = &level.room_database.rooms;
+        assert_eq!(rooms.len(), 2);
+        assert_eq!(rooms[0].portals[0].dest_room, 1);
+
+        let room = level
+            .room_database
+            .find_room_containing(to_world(24.0, 8.0, 4.0))
+            .unwrap();
+        assert_eq!(room.obj_id, 101);
+    }
+
+    #[test]
+    fn test_read_entities_with_inheritance_and_links() {
+        let (gamesys, level) = read_fixtures();
+        assert_eq!(level.obj_map[&TRAP_ID], "trap");
+
+        let entity_info = ss2_entity_info::merge_with_gamesys(&level.entity_info, &gamesys);
+        let hierarchy = ss2_entity_info::get_hierarchy(&entity_info);
+        assert_eq!(
+            ss2_entity_info::get_ancestors(hierarchy, &TRAP_ID),
+            vec![ARCHETYPE_ID]
+        );
+
+        let mut world = World::new();
+        let template_to_entity_id =
+            entity_info.initialize_world_with_entities(&mut world, HashMap::new(), |_| true);
+        let trap = template_to_entity_id[&TRAP_ID];
+        world.run(
+            |v_scripts: View<PropScripts>,
+             v_quest_bit_name: View<PropQuestBitName>,
+             v_sym_name: View<PropSymName>| {
+                assert_eq!(v_scripts.get(trap).unwrap().scripts, vec!["TrapQBSet"]);
+                assert_eq!(v_quest_bit_name.get(trap).unwrap().0, "test_bit");
+
+                // Inherited from the archetype in the gamesys
+                assert_eq!(v_sym_name.get(trap).unwrap().0, "TestArchetype");
+            },
+        );
+
+        let trap_links = &entity_info.template_to_links[&TRAP_ID].to_links;
+        assert_eq!(trap_links.len(), 1);
+        assert_eq!(trap_links[0].to_template_id, CRATE_ID);
+        assert_eq!(trap_links[0].link, Link::SwitchLink);
+
+        let crate_links = &entity_info.template_to_links[&CRATE_ID].to_links;
+        assert_eq!(crate_links[0].link, Link::Contains(7));
+    }
+
+    #[test]
+    fn test_read_stimulus_source_links() {
+        let (gamesys, _) = read_fixtures();
+
+        let archetype_links = &gamesys.entity_info.template_to_links[&ARCHETYPE_ID].to_links;
+        assert_eq!(archetype_links.len(), 1);
+        assert_eq!(archetype_links[0].to_template_id, STIMULUS_ID);
+        assert_eq!(archetype_links[0].link, Link::StimulusSource);
+    }
+}
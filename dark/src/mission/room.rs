@@ -19,6 +19,26 @@ pub struct Room {
 }
 
 impl Room {
+    ///
+    /// volume
+    ///
+    /// Volume of the bounding box of the room
+    pub fn volume(&self) -> f32 {
+        let size = self.bounding_box.max - self.bounding_box.min;
+        size.x.abs() * size.y.abs() * size.z.abs()
+    }
+
+    pub fn contains(&self, position: Vector3<f32>) -> bool {
+        let min = self.bounding_box.min;
+        let max = self.bounding_box.max;
+        position.x >= min.x
+            && position.x <= max.x
+            && position.y >= min.y
+            && position.y <= max.y
+            && position.z >= min.z
+            && position.z <= max.z
+    }
+
     // Read the ROOM_DB chunk to get a list of rooms
     pub fn read<T: io::Read + io::Seek>(reader: &mut T) -> Room {
         let obj_id = read_i32(reader);
@@ -3,6 +3,7 @@ use crate::{
     ss2_chunk_file_reader::ChunkFileTableOfContents,
 };
 use byteorder::ReadBytesExt;
+use cgmath::Vector3;
 
 
 use tracing::trace;
@@ -40,4 +41,16 @@ impl RoomDatabase {
 
         RoomDatabase { rooms }
     }
+
+    ///
+    /// find_room_containing
+    ///
+    /// Find the room containing the position. If the bounding boxes of multiple rooms contain it,
+    /// the smallest room is used.
+    pub fn find_room_containing(&self, position: Vector3<f32>) -> Option<&Room> {
+        self.rooms
+            .iter()
+            .filter(|room| room.contains(position))
+            .min_by(|a, b| a.volume().total_cmp(&b.volume()))
+    }
 }
@@ -10,14 +10,16 @@ use crate::{
     importers::TEXTURE_IMPORTER, properties::RenderType, util::load_multiple_textures_for_family,
 };
 
+///
+/// to_scene
+///
+/// Build the scene objects for the level geometry. The lightmap texture is passed in
+/// (from `lightmap_atlas.generate_textures`) so the caller can update it as lights change.
 pub fn to_scene(
     level: &crate::mission::SystemShock2Level,
     asset_cache: &mut AssetCache,
+    lightmap_texture: Rc<Texture>,
 ) -> Vec<SceneObject> {
-    let lightmap_textures = level.lightmap_atlas.generate_textures();
-    let tex = lightmap_textures.get(0).unwrap();
-    let lightmap_texture = tex.clone();
-
     let all_geometry = &level.all_geometry;
     let mut texture_to_vertices: HashMap<&u16, Vec<VertexPositionTextureLightmapAtlas>> =
         HashMap::new();
@@ -1,4 +1,6 @@
-use crate::properties::{AnimTexFlags, PropAnimTex, PropRenderType, RenderType};
+use crate::properties::{
+    AnimTexFlags, PropAnimTex, PropClassTag, PropMaterial, PropRenderType, RenderType,
+};
 use crate::ss2_chunk_file_reader::ChunkFileTableOfContents;
 use crate::ss2_entity_info::{self, SystemShock2EntityInfo};
 use crate::Gamesys;
@@ -24,6 +26,8 @@ pub struct SystemShock2Texture {
     pub texture_filename: String,
     pub render_type: RenderType,
     pub animation_info: Option<TextureAnimationInfo>,
+    // Surface material (ie, 'metal', 'carpet'), from the material tags on the texture archetype
+    pub material: Option<String>,
 }
 
 pub struct TextureList(pub Vec<SystemShock2Texture>);
@@ -49,7 +53,7 @@ impl TextureList {
 fn read_txlist_chunk<T: io::Read + io::Seek>(
     table_of_contents: &ChunkFileTableOfContents,
     reader: &mut T,
-    name_to_info: HashMap<String, (RenderType, Option<TextureAnimationInfo>)>,
+    name_to_info: HashMap<String, TextureArchetypeInfo>,
 ) -> TextureList {
     let txlist = table_of_contents
         .get_chunk("TXLIST".to_string())
@@ -84,13 +88,13 @@ fn read_txlist_chunk<T: io::Read + io::Seek>(
 
         let entity_name = format!("t_fam/{}/{}", family, name);
 
-        let (render_type, maybe_animation_info) = {
+        let (render_type, maybe_animation_info, maybe_material) = {
             if let Some(info) = name_to_info.get(&entity_name) {
                 info!("texture info for: {} is {:?}", entity_name, info);
-                (info.0.clone(), info.1.clone())
+                (info.0.clone(), info.1.clone(), info.2.clone())
             } else {
                 warn!("no texture info for: {}", entity_name);
-                (RenderType::Normal, None)
+                (RenderType::Normal, None, None)
             }
         };
 
@@ -99,16 +103,19 @@ fn read_txlist_chunk<T: io::Read + io::Seek>(
             texture_filename: name,
             render_type,
             animation_info: maybe_animation_info,
+            material: maybe_material,
         })
     }
     TextureList(textures)
 }
 
+type TextureArchetypeInfo = (RenderType, Option<TextureAnimationInfo>, Option<String>);
+
 fn read_texture_archetypes(
     obj_texture_families: Vec<(String, i32)>,
     entity_info: &SystemShock2EntityInfo,
     gamesys: &Gamesys,
-) -> HashMap<String, (RenderType, Option<TextureAnimationInfo>)> {
+) -> HashMap<String, TextureArchetypeInfo> {
     let mut world = World::new();
     let name_map_override = HashMap::new();
 
@@ -129,6 +136,7 @@ fn read_texture_archetypes(
     for (family_name, id) in &obj_texture_families {
         let v_render_type = world.borrow::<View<PropRenderType>>().unwrap();
         let v_anim_tex = world.borrow::<View<PropAnimTex>>().unwrap();
+        let v_material = world.borrow::<View<PropMaterial>>().unwrap();
 
         let maybe_entity_id = template_to_entity_id.get(id);
         if let Some(entity_id) = maybe_entity_id {
@@ -152,9 +160,14 @@ fn read_texture_archetypes(
                     None
                 }
             };
+            let maybe_material = v_material
+                .get(*entity_id)
+                .ok()
+                .and_then(|material| parse_material(&material.0));
+
             name_to_info.insert(
                 family_name.clone(),
-                (render_type, maybe_texture_animation_info),
+                (render_type, maybe_texture_animation_info, maybe_material),
             );
 
             // if let Ok(anim_tex) = maybe_anim_tex {
@@ -165,3 +178,35 @@ fn read_texture_archetypes(
     }
     name_to_info
 }
+
+///
+/// parse_material
+///
+/// Material tags are stored as tag / value pairs (ie, 'Material Metal'),
+/// so pull out the value for the material tag. Tags without one have no material.
+fn parse_material(material_tags: &str) -> Option<String> {
+    if material_tags.trim().is_empty() {
+        return None;
+    }
+
+    let class_tag = PropClassTag::from_string(material_tags.trim());
+    class_tag
+        .tag_values
+        .iter()
+        .find(|(tag, _)| tag == "material")
+        .map(|(_, value)| value.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_material_needs_a_material_tag() {
+        // Then
+        assert_eq!(parse_material("Material Metal"), Some("metal".to_owned()));
+        assert_eq!(parse_material("Texture Metal"), None);
+        assert_eq!(parse_material("Metal"), None);
+        assert_eq!(parse_material(""), None);
+    }
+}
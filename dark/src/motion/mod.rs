@@ -221,6 +221,31 @@ impl MotionDB {
     }
 }
 
+impl MotionDB {
+    ///
+    /// animation_names
+    ///
+    /// All of the animations available to an actor type (the same index used for `query`),
+    /// sorted by name.
+    pub fn animation_names(&self, actor_type: u32) -> Vec<String> {
+        let tag_database = match self.tag_databases.get(actor_type as usize) {
+            Some(tag_database) => tag_database,
+            None => return Vec::new(),
+        };
+
+        let mut names = tag_database
+            .all_values()
+            .iter()
+            .filter_map(|value| self.tag_value_to_animations.get(value))
+            .flatten()
+            .cloned()
+            .collect::<Vec<String>>();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
 fn load_name_map<T: io::Read + io::Seek>(
     reader: &mut T,
 ) -> (HashMap<String, u32>, HashMap<u32, String>) {
@@ -1,6 +1,7 @@
 mod accumulator;
 mod prop_ai;
 mod prop_ambient_hacked;
+mod prop_anim_light;
 mod prop_anim_tex;
 mod prop_bitmap_animation;
 mod prop_collision_type;
@@ -26,6 +27,7 @@ mod prop_tweq;
 use num_derive::{FromPrimitive, ToPrimitive};
 pub use prop_ai::*;
 pub use prop_ambient_hacked::*;
+pub use prop_anim_light::*;
 pub use prop_anim_tex::*;
 pub use prop_bitmap_animation::*;
 pub use prop_collision_type::*;
@@ -158,6 +160,14 @@ pub struct PropMaterial(pub String);
 #[derive(Debug, Component, Clone, Serialize, Deserialize)]
 pub struct PropSymName(pub String);
 
+// Name of the voice archetype (ie, 'vhybrid1') used when a creature speaks
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropSpeechVoice(pub String);
+
+// Index of a voice archetype into the voices of the speech database
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropVoiceIndex(pub i32);
+
 #[derive(Debug, Component, Clone, Serialize, Deserialize)]
 pub struct PropMotionActorTags {
     pub tags: Vec<String>,
@@ -280,6 +290,7 @@ pub enum Link {
     LandingPoint,
     Projectile(ProjectileOptions),
     Replicator,
+    StimulusSource,
     SwitchLink,
     MissSpang,
     TPathInit,
@@ -633,6 +644,7 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
         define_link("L$LandingPo", |_| Link::LandingPoint),
         define_link("L$Replicato", |_| Link::Replicator),
         define_link("L$SwitchLin", |_| Link::SwitchLink),
+        define_link("L$arSrc", |_| Link::StimulusSource),
         define_link("L$TPathInit", |_| Link::TPathInit),
         define_link("L$Miss Span", |_| Link::MissSpang),
         //define_link("L$TPath", |_| Link::TPath),
@@ -701,6 +713,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$AnimLight",
+            PropAnimLight::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$AnimTex",
             PropAnimTex::read,
@@ -1049,6 +1067,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$SpchVoice",
+            read_prop_string,
+            PropSpeechVoice,
+            accumulator::latest,
+        ),
         define_prop(
             "P$StartLoc",
             |reader, _len| read_i32(reader),
@@ -1067,6 +1091,12 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$VoiceIdx",
+            |reader, _len| read_i32(reader),
+            PropVoiceIndex,
+            accumulator::latest,
+        ),
         // Tweq props
         define_prop(
             "P$CfgTweqDe",
@@ -1086,6 +1116,36 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$CfgTweqRo",
+            TweqVectorConfig::read,
+            PropTweqRotateConfig,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$CfgTweqSc",
+            TweqVectorConfig::read,
+            PropTweqScaleConfig,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$CfgTweqJo",
+            PropTweqJointsConfig::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$CfgTweqLo",
+            PropTweqLockConfig::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$CfgTweqBl",
+            PropTweqBlinkConfig::read,
+            identity,
+            accumulator::latest,
+        ),
         define_prop(
             "P$SignalTyp",
             read_variable_length_string,
@@ -1116,6 +1176,30 @@ pub fn get<R: io::Read + io::Seek + 'static>() -> (
             identity,
             accumulator::latest,
         ),
+        define_prop(
+            "P$StTweqSca",
+            PropTweqScaleState::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$StTweqJoi",
+            PropTweqJointsState::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$StTweqLoc",
+            PropTweqLockState::read,
+            identity,
+            accumulator::latest,
+        ),
+        define_prop(
+            "P$StTweqBli",
+            PropTweqBlinkState::read,
+            identity,
+            accumulator::latest,
+        ),
         // Internal properties
         // These are not properties that are provided by shock2 game,
         // but are used internally for save/restore.
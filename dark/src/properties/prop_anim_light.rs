@@ -0,0 +1,103 @@
+use std::io;
+
+use cgmath::Vector3;
+use num_derive::FromPrimitive;
+use shipyard::Component;
+
+use crate::ss2_common::*;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum AnimLightMode {
+    Flip = 0,
+    Smooth = 1,
+    Random = 2,
+    Minimum = 3,
+    Maximum = 4,
+    Zero = 5,
+    SmoothBrighten = 6,
+    SmoothDim = 7,
+    RandomCoherent = 8,
+    Flicker = 9,
+}
+
+///
+/// PropAnimLight
+///
+/// A light that contributes an animated lightmap layer to the cells it touches. The light_index
+/// matches the entries in each cell's `anim_light_indices`.
+#[derive(Debug, Component, Clone, Serialize, Deserialize)]
+pub struct PropAnimLight {
+    pub base_brightness: f32,
+    pub offset: Vector3<f32>,
+
+    pub light_index: i16,
+    pub mode: AnimLightMode,
+    pub time_rise_in_milliseconds: i32,
+    pub time_fall_in_milliseconds: i32,
+    pub min_brightness: f32,
+    pub max_brightness: f32,
+
+    // Animation state - stored in the level, but updated at runtime
+    pub brightness: f32,
+    pub is_rising: bool,
+    pub countdown_in_milliseconds: i32,
+    pub is_inactive: bool,
+
+    // Runtime state - whether the light has been switched on or off by a script
+    pub is_on: bool,
+}
+
+impl PropAnimLight {
+    pub fn read<T: io::Read + io::Seek>(reader: &mut T, _len: u32) -> PropAnimLight {
+        let base_brightness = read_single(reader);
+        let offset = read_vec3(reader);
+
+        let light_index = read_i16(reader);
+        let _num_cells = read_i16(reader);
+        let _cell_index = read_i32(reader);
+
+        let mode_bits = read_i16(reader);
+        let mode = num_traits::FromPrimitive::from_i16(mode_bits).unwrap_or(AnimLightMode::Maximum);
+        let _pad = read_i16(reader);
+
+        let time_rise_in_milliseconds = read_i32(reader);
+        let time_fall_in_milliseconds = read_i32(reader);
+        let min_brightness = read_single(reader);
+        let max_brightness = read_single(reader);
+
+        let brightness = read_single(reader);
+        let is_rising = read_bool(reader);
+        let countdown_in_milliseconds = read_i32(reader);
+        let is_inactive = read_bool(reader);
+
+        PropAnimLight {
+            base_brightness,
+            offset,
+            light_index,
+            mode,
+            time_rise_in_milliseconds,
+            time_fall_in_milliseconds,
+            min_brightness,
+            max_brightness,
+            brightness,
+            is_rising,
+            countdown_in_milliseconds,
+            is_inactive,
+            is_on: true,
+        }
+    }
+
+    ///
+    /// intensity
+    ///
+    /// How much of the light's lightmap layer to apply, from 0 (none) to 1 (fully lit)
+    pub fn intensity(&self) -> f32 {
+        if !self.is_on || self.max_brightness <= 0.0 {
+            return 0.0;
+        }
+
+        (self.brightness / self.max_brightness).clamp(0.0, 1.0)
+    }
+}
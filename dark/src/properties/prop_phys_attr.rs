@@ -17,7 +17,7 @@ pub struct PropPhysAttr {
     pub cog: Vector3<f32>,  // ?
     pub rotation_axes: u32, // ?
     pub rest_axes: u32,     // ?
-    pub climbable: u32,     // ?
+    pub climbable: u32,     // Bitmask of the sides that can be climbed, like on a ladder
     pub edge_trigger: bool,
 }
 
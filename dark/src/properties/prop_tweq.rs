@@ -1,6 +1,6 @@
 use std::{io, time::Duration};
 
-use cgmath::Vector3;
+use cgmath::{Quaternion, Vector3};
 use num_derive::FromPrimitive;
 use shipyard::Component;
 
@@ -30,7 +30,7 @@ bitflags! {
     }
 }
 
-#[derive(FromPrimitive, Clone, Debug, Deserialize, Serialize)]
+#[derive(FromPrimitive, Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum TweqHalt {
     DESTROY_OBJECT = 0,
     REMOVE_PROP = 1,
@@ -39,12 +39,90 @@ pub enum TweqHalt {
     SLAY_OBJ = 4,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TweqType {
+    Scale,
+    Rotate,
+    Joints,
+    Models,
+    Delete,
+    Emitter,
+    Blink,
+    Lock,
+}
+
+// Number of joints a joints tweq can animate
+pub const TWEQ_MAX_JOINTS: usize = 6;
+
+///
+/// TweqLimits
+///
+/// Rate (units per second) and range for a single animated value of a tweq -
+/// an axis of a rotate or scale tweq, or a joint of a joints or lock tweq.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct TweqLimits {
+    pub rate: f32,
+    pub low: f32,
+    pub high: f32,
+}
+
+impl TweqLimits {
+    pub fn read<T: io::Read>(reader: &mut T) -> TweqLimits {
+        let rate = read_single(reader);
+        let low = read_single(reader);
+        let high = read_single(reader);
+        TweqLimits { rate, low, high }
+    }
+
+    pub fn zero() -> TweqLimits {
+        TweqLimits {
+            rate: 0.0,
+            low: 0.0,
+            high: 0.0,
+        }
+    }
+}
+
+///
+/// read_base_config
+///
+/// Reads the header shared by all the tweq configs: animation flags, halt action, and rate
+fn read_base_config<T: io::Read>(reader: &mut T) -> (TweqAnimationConfig, TweqHalt, Duration) {
+    let _unk = read_u8(reader);
+    let _curve = read_u8(reader);
+    let animation_config_bits = read_u8(reader);
+    let animation_config = TweqAnimationConfig::from_bits_truncate(animation_config_bits.into());
+    let halt_bits = read_u8(reader);
+    let halt: TweqHalt =
+        num_traits::FromPrimitive::from_u8(halt_bits).unwrap_or(TweqHalt::STOP_TWEQ);
+
+    let _misc = read_u16(reader);
+    let rate = read_u16(reader);
+
+    (animation_config, halt, Duration::from_millis(rate.into()))
+}
+
+///
+/// read_base_state
+///
+/// Reads the animation state flags shared by all the tweq states
+fn read_base_state<T: io::Read>(reader: &mut T) -> TweqAnimationState {
+    let animation_state_bits = read_u16(reader);
+    let _misc = read_u16(reader);
+    TweqAnimationState::from_bits_truncate(animation_state_bits.into())
+}
+
 #[derive(Debug, Component, Clone, Deserialize, Serialize)]
 pub struct PropTweqRotateState {
     pub animation_state: TweqAnimationState,
     pub axis1_animation_state: TweqAnimationState,
     pub axis2_animation_state: TweqAnimationState,
     pub axis3_animation_state: TweqAnimationState,
+
+    // Runtime state - current angle (in degrees) of each axis, relative to the
+    // orientation the object had when the tweq started
+    pub angles: Vector3<f32>,
+    pub base_rotation: Option<Quaternion<f32>>,
 }
 
 impl PropTweqRotateState {
@@ -70,6 +148,8 @@ impl PropTweqRotateState {
             axis1_animation_state,
             axis2_animation_state,
             axis3_animation_state,
+            angles: Vector3::new(0.0, 0.0, 0.0),
+            base_rotation: None,
         }
     }
 }
@@ -245,3 +325,239 @@ impl PropTweqDeleteConfig {
         }
     }
 }
+
+///
+/// TweqVectorConfig
+///
+/// Config shared by the rotate and scale tweqs - per-axis limits, in the original (x, y, z) axis order.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TweqVectorConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub halt: TweqHalt,
+
+    // 1-based axis that drives completion of the tweq, or 0 if any axis can complete it
+    pub primary_axis: u8,
+    pub limits: [TweqLimits; 3],
+}
+
+impl TweqVectorConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> TweqVectorConfig {
+        let (animation_config, halt, _rate) = read_base_config(reader);
+
+        let primary_axis = read_u8(reader);
+        let _pad = read_bytes(reader, 3);
+
+        let limits = [
+            TweqLimits::read(reader),
+            TweqLimits::read(reader),
+            TweqLimits::read(reader),
+        ];
+
+        TweqVectorConfig {
+            animation_config,
+            halt,
+            primary_axis,
+            limits,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqRotateConfig(pub TweqVectorConfig);
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqScaleConfig(pub TweqVectorConfig);
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqScaleState {
+    pub animation_state: TweqAnimationState,
+    pub axis_animation_states: [TweqAnimationState; 3],
+
+    // Runtime state - current scale, in the original (x, y, z) axis order.
+    // Initialized from the object's scale the first time the tweq runs.
+    pub scale: Option<Vector3<f32>>,
+}
+
+impl PropTweqScaleState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropTweqScaleState {
+        let animation_state = read_base_state(reader);
+        let axis_animation_states = [
+            read_base_state(reader),
+            read_base_state(reader),
+            read_base_state(reader),
+        ];
+
+        PropTweqScaleState {
+            animation_state,
+            axis_animation_states,
+            scale: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct TweqJointConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub limits: TweqLimits,
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqJointsConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub halt: TweqHalt,
+
+    // 1-based joint that drives completion of the tweq, or 0 if any joint can complete it
+    pub primary_joint: u8,
+    pub joints: Vec<TweqJointConfig>,
+}
+
+impl PropTweqJointsConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropTweqJointsConfig {
+        let (animation_config, halt, _rate) = read_base_config(reader);
+
+        let primary_joint = read_u8(reader);
+        let _pad = read_bytes(reader, 3);
+
+        let mut joints = Vec::new();
+        for _ in 0..TWEQ_MAX_JOINTS {
+            let joint_animation_config_bits = read_u8(reader);
+            let _curve = read_u8(reader);
+            let _pad = read_u16(reader);
+            let limits = TweqLimits::read(reader);
+
+            joints.push(TweqJointConfig {
+                animation_config: TweqAnimationConfig::from_bits_truncate(
+                    joint_animation_config_bits.into(),
+                ),
+                limits,
+            });
+        }
+
+        PropTweqJointsConfig {
+            animation_config,
+            halt,
+            primary_joint,
+            joints,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqJointsState {
+    pub animation_state: TweqAnimationState,
+    pub joint_animation_states: Vec<TweqAnimationState>,
+
+    // Runtime state - current value of each joint
+    pub values: Vec<f32>,
+}
+
+impl PropTweqJointsState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqJointsState {
+        let animation_state = read_base_state(reader);
+
+        let joint_count = ((len.saturating_sub(4) / 4) as usize).min(TWEQ_MAX_JOINTS);
+        let mut joint_animation_states = Vec::new();
+        for _ in 0..joint_count {
+            joint_animation_states.push(read_base_state(reader));
+        }
+        joint_animation_states.resize(TWEQ_MAX_JOINTS, TweqAnimationState::empty());
+
+        PropTweqJointsState {
+            animation_state,
+            joint_animation_states,
+            values: vec![0.0; TWEQ_MAX_JOINTS],
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqLockConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub halt: TweqHalt,
+
+    pub lock_joint: u8,
+    pub limits: TweqLimits,
+}
+
+impl PropTweqLockConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, len: u32) -> PropTweqLockConfig {
+        let (animation_config, halt, _rate) = read_base_config(reader);
+
+        // Some lock configs only have the base config
+        let (lock_joint, limits) = if len >= 24 {
+            let lock_joint = read_u8(reader);
+            let _pad = read_bytes(reader, 3);
+            (lock_joint, TweqLimits::read(reader))
+        } else {
+            (1, TweqLimits::zero())
+        };
+
+        PropTweqLockConfig {
+            animation_config,
+            halt,
+            lock_joint,
+            limits,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqLockState {
+    pub animation_state: TweqAnimationState,
+
+    // Runtime state - current value of the lock joint
+    pub value: f32,
+}
+
+impl PropTweqLockState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropTweqLockState {
+        let animation_state = read_base_state(reader);
+        // Lock target and stage follow, unused for now
+
+        PropTweqLockState {
+            animation_state,
+            value: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqBlinkConfig {
+    pub animation_config: TweqAnimationConfig,
+    pub halt: TweqHalt,
+
+    pub rate: Duration,
+}
+
+impl PropTweqBlinkConfig {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropTweqBlinkConfig {
+        let (animation_config, halt, rate) = read_base_config(reader);
+
+        PropTweqBlinkConfig {
+            animation_config,
+            halt,
+            rate,
+        }
+    }
+}
+
+#[derive(Debug, Component, Clone, Deserialize, Serialize)]
+pub struct PropTweqBlinkState {
+    pub animation_state: TweqAnimationState,
+    pub time_since_last_event: Duration,
+    pub is_lit: bool,
+}
+
+impl PropTweqBlinkState {
+    pub fn read<T: io::Seek + io::Read>(reader: &mut T, _len: u32) -> PropTweqBlinkState {
+        let animation_state = read_base_state(reader);
+        let _time = read_u16(reader); // misc state, is this used?
+        let _frame = read_u16(reader); // misc state, is this used?
+
+        PropTweqBlinkState {
+            animation_state,
+            time_since_last_event: Duration::from_secs(0),
+            is_lit: true,
+        }
+    }
+}
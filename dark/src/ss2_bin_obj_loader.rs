@@ -128,10 +128,8 @@ pub fn to_scene_objects(
         .into_iter()
         .collect::<Vec<(u16, Vec<VertexPositionTextureSkinned>)>>();
 
-    let mut bones = Vec::new();
-    build_skeleton_for_obj_mesh(&mesh, 0, None, &mut bones);
-    let is_skinned = bones.len() > 1;
-    let skeleton = Skeleton::create_from_bones(bones);
+    let skeleton = create_skeleton(mesh);
+    let is_skinned = skeleton.bone_count() > 1;
 
     let mut mesh_objects = vertices
         .into_iter()
@@ -379,6 +377,17 @@ fn get_bone_index_for_point(header: &SystemShock2ObjectMesh, usize: u16) -> u32
     0
 }
 
+///
+/// create_skeleton
+///
+/// Build a skeleton from the sub-object hierarchy - each sub-object is a bone, with the
+/// sub-object index as the joint id.
+pub fn create_skeleton(mesh: &SystemShock2ObjectMesh) -> Skeleton {
+    let mut bones = Vec::new();
+    build_skeleton_for_obj_mesh(mesh, 0, None, &mut bones);
+    Skeleton::create_from_bones(bones)
+}
+
 fn build_skeleton_for_obj_mesh(
     header: &SystemShock2ObjectMesh,
     sub_object_idx: i32,
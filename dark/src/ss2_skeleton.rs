@@ -31,6 +31,10 @@ impl Skeleton {
         self.bones.len()
     }
 
+    pub fn bones(&self) -> &Vec<Bone> {
+        &self.bones
+    }
+
     pub fn get_transforms(&self) -> [Matrix4<f32>; 40] {
         let mut transforms = [Matrix4::identity(); 40];
         for (joint_id, transform) in self.global_transforms.iter() {
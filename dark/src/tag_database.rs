@@ -131,6 +131,21 @@ impl TagDatabase {
         vec![]
     }
 
+    ///
+    /// query_match_all_or_default
+    ///
+    /// Like query_match_all, but falls back to the data at the root of the database
+    /// if none of the branches match. Speech databases keep the 'default' lines at the root.
+    pub fn query_match_all_or_default(&self, query: &TagQuery) -> Vec<i32> {
+        let output = self.query_match_all(query);
+
+        if output.is_empty() {
+            self.data.iter().map(|data| data.data).collect()
+        } else {
+            output
+        }
+    }
+
     pub fn query_one(&self, tag: u32) -> Vec<i32> {
         let mut ret = Vec::new();
         self.query_one_recursive(tag, &mut ret);
@@ -196,6 +211,29 @@ impl TagDatabase {
         }
     }
 
+    ///
+    /// all_values
+    ///
+    /// Every value stored in the database - at the root, and in every branch - sorted
+    /// and without duplicates.
+    pub fn all_values(&self) -> Vec<i32> {
+        let mut ret = Vec::new();
+        self.all_values_recursive(&mut ret);
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    fn all_values_recursive(&self, values: &mut Vec<i32>) {
+        for data in &self.data {
+            values.push(data.data)
+        }
+
+        for branch in self.branches.values() {
+            branch.all_values_recursive(values);
+        }
+    }
+
     fn collect_all_values(&self, values: &mut Vec<i32>) {
         for (_, branch) in &self.branches {
             branch.collect_all_values(values);
@@ -285,3 +323,47 @@ impl TagDatabaseKey {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(values: &[i32]) -> TagDatabase {
+        TagDatabase {
+            data: values
+                .iter()
+                .map(|data| TagDatabaseData {
+                    data: *data,
+                    weight: 1.0,
+                })
+                .collect(),
+            branches: HashMap::new(),
+        }
+    }
+
+    fn key(key_type: u32) -> TagDatabaseKey {
+        TagDatabaseKey {
+            key_type,
+            min: 0,
+            max: 0,
+            enum_values: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_all_values_includes_root_and_nested_branches() {
+        // Given
+        let mut nested = leaf(&[3, 1]);
+        nested.branches.insert(key(2), Rc::new(leaf(&[4])));
+
+        let mut root = leaf(&[0]);
+        root.branches.insert(key(0), Rc::new(nested));
+        root.branches.insert(key(1), Rc::new(leaf(&[1, 2])));
+
+        // When
+        let values = root.all_values();
+
+        // Then
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+}
@@ -0,0 +1,91 @@
+use std::io::Cursor;
+
+use crate::ChunkWriter;
+
+// Chunk names are stored in a 12 byte, null-padded field
+const CHUNK_NAME_SIZE: usize = 12;
+
+// inv_offset, zero, one, 256 bytes of padding, and the dead beef marker
+const FILE_HEADER_SIZE: usize = 4 + 4 + 4 + 256 + 4;
+
+const DEAD_BEEF: u32 = 0xEFBEADDE;
+
+///
+/// ChunkFileBuilder
+///
+/// Assemble chunks into a tagged file, laid out the way `dark::ss2_chunk_file_reader` expects:
+/// a file header pointing at the table of contents, then each chunk (a 24 byte chunk header
+/// followed by its data), then the table of contents itself.
+#[derive(Default)]
+pub struct ChunkFileBuilder {
+    chunks: Vec<(String, Vec<u8>)>,
+}
+
+impl ChunkFileBuilder {
+    pub fn new() -> ChunkFileBuilder {
+        ChunkFileBuilder { chunks: Vec::new() }
+    }
+
+    ///
+    /// add_chunk
+    ///
+    /// Add a chunk, replacing any existing chunk with the same name
+    pub fn add_chunk(&mut self, name: &str, data: Vec<u8>) -> &mut ChunkFileBuilder {
+        assert!(
+            name.len() <= CHUNK_NAME_SIZE,
+            "chunk name '{name}' is longer than {CHUNK_NAME_SIZE} bytes"
+        );
+
+        match self
+            .chunks
+            .iter_mut()
+            .find(|(existing, _)| existing == name)
+        {
+            Some((_, existing_data)) => *existing_data = data,
+            None => self.chunks.push((name.to_owned(), data)),
+        }
+        self
+    }
+
+    pub fn has_chunk(&self, name: &str) -> bool {
+        self.chunks.iter().any(|(existing, _)| existing == name)
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut body = ChunkWriter::new();
+        let mut table_of_contents = Vec::new();
+        for (name, data) in &self.chunks {
+            let offset = FILE_HEADER_SIZE + body.len();
+            table_of_contents.push((name, offset as u32, data.len() as u32));
+
+            // Chunk header - name, version high, version low, zero
+            body.write_string_with_size(name, CHUNK_NAME_SIZE)
+                .write_u32(0)
+                .write_u32(0)
+                .write_u32(0)
+                .write_bytes(data);
+        }
+
+        let inv_offset = FILE_HEADER_SIZE + body.len();
+        let mut file = ChunkWriter::new();
+        file.write_u32(inv_offset as u32)
+            .write_u32(0)
+            .write_u32(1)
+            .write_bytes(&[0; 256])
+            .write_u32(DEAD_BEEF)
+            .write_bytes(&body.into_bytes());
+
+        file.write_u32(table_of_contents.len() as u32);
+        for (name, offset, length) in table_of_contents {
+            file.write_string_with_size(name, CHUNK_NAME_SIZE)
+                .write_u32(offset)
+                .write_u32(length);
+        }
+
+        file.into_bytes()
+    }
+
+    pub fn build_reader(&self) -> Cursor<Vec<u8>> {
+        Cursor::new(self.build())
+    }
+}
@@ -0,0 +1,98 @@
+use byteorder::{LittleEndian, WriteBytesExt};
+use cgmath::Vector3;
+
+///
+/// ChunkWriter
+///
+/// Little-endian writer for chunk data - the counterpart of the `read_*` helpers in
+/// `dark::ss2_common`
+#[derive(Default)]
+pub struct ChunkWriter {
+    bytes: Vec<u8>,
+}
+
+impl ChunkWriter {
+    pub fn new() -> ChunkWriter {
+        ChunkWriter { bytes: Vec::new() }
+    }
+
+    pub fn write_u8(&mut self, value: u8) -> &mut ChunkWriter {
+        self.bytes.push(value);
+        self
+    }
+
+    pub fn write_u16(&mut self, value: u16) -> &mut ChunkWriter {
+        self.bytes.write_u16::<LittleEndian>(value).unwrap();
+        self
+    }
+
+    pub fn write_i16(&mut self, value: i16) -> &mut ChunkWriter {
+        self.bytes.write_i16::<LittleEndian>(value).unwrap();
+        self
+    }
+
+    pub fn write_u32(&mut self, value: u32) -> &mut ChunkWriter {
+        self.bytes.write_u32::<LittleEndian>(value).unwrap();
+        self
+    }
+
+    pub fn write_i32(&mut self, value: i32) -> &mut ChunkWriter {
+        self.bytes.write_i32::<LittleEndian>(value).unwrap();
+        self
+    }
+
+    pub fn write_single(&mut self, value: f32) -> &mut ChunkWriter {
+        self.bytes.write_f32::<LittleEndian>(value).unwrap();
+        self
+    }
+
+    ///
+    /// write_vec3
+    ///
+    /// Write a vector as-is, in Dark's coordinate space
+    pub fn write_vec3(&mut self, value: Vector3<f32>) -> &mut ChunkWriter {
+        self.write_single(value.x)
+            .write_single(value.y)
+            .write_single(value.z)
+    }
+
+    ///
+    /// write_plane
+    ///
+    /// Write a plane as a normal and distance, where points on the plane satisfy
+    /// `normal . point + distance = 0`
+    pub fn write_plane(&mut self, normal: Vector3<f32>, distance: f32) -> &mut ChunkWriter {
+        self.write_vec3(normal).write_single(distance)
+    }
+
+    ///
+    /// write_string_with_size
+    ///
+    /// Write a string into a fixed-size, null-padded field
+    pub fn write_string_with_size(&mut self, value: &str, size: usize) -> &mut ChunkWriter {
+        assert!(
+            value.len() <= size,
+            "'{value}' does not fit in a field of {size} bytes"
+        );
+        self.bytes.extend_from_slice(value.as_bytes());
+        self.bytes.resize(self.bytes.len() + size - value.len(), 0);
+        self
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) -> &mut ChunkWriter {
+        self.bytes.extend_from_slice(bytes);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
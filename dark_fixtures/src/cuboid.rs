@@ -0,0 +1,103 @@
+use cgmath::{vec3, InnerSpace, Vector3};
+
+///
+/// Face
+///
+/// A face of an axis-aligned box, in Dark's coordinate space (z is up)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Face {
+    West,   // -x
+    East,   // +x
+    South,  // -y
+    North,  // +y
+    Bottom, // -z
+    Top,    // +z
+}
+
+impl Face {
+    pub const ALL: [Face; 6] = [
+        Face::West,
+        Face::East,
+        Face::South,
+        Face::North,
+        Face::Bottom,
+        Face::Top,
+    ];
+
+    ///
+    /// index
+    ///
+    /// Index of the face in `Face::ALL` - which is also the index of its plane in a cuboid cell
+    pub fn index(self) -> usize {
+        Face::ALL.iter().position(|face| *face == self).unwrap()
+    }
+
+    ///
+    /// corner_indices
+    ///
+    /// The corners of the face, in winding order. Corners are indexed with bit 0 set for max x,
+    /// bit 1 for max y, and bit 2 for max z.
+    pub fn corner_indices(self) -> [u8; 4] {
+        match self {
+            Face::West => [0, 2, 6, 4],
+            Face::East => [1, 3, 7, 5],
+            Face::South => [0, 1, 5, 4],
+            Face::North => [2, 3, 7, 6],
+            Face::Bottom => [0, 1, 3, 2],
+            Face::Top => [4, 5, 7, 6],
+        }
+    }
+
+    ///
+    /// plane
+    ///
+    /// The plane of the face, with the normal facing into the box
+    pub fn plane(self, min: Vector3<f32>, max: Vector3<f32>) -> (Vector3<f32>, f32) {
+        match self {
+            Face::West => (vec3(1.0, 0.0, 0.0), -min.x),
+            Face::East => (vec3(-1.0, 0.0, 0.0), max.x),
+            Face::South => (vec3(0.0, 1.0, 0.0), -min.y),
+            Face::North => (vec3(0.0, -1.0, 0.0), max.y),
+            Face::Bottom => (vec3(0.0, 0.0, 1.0), -min.z),
+            Face::Top => (vec3(0.0, 0.0, -1.0), max.z),
+        }
+    }
+
+    ///
+    /// texture_axes
+    ///
+    /// Two axes lying in the plane of the face, for texture mapping
+    pub fn texture_axes(self) -> (Vector3<f32>, Vector3<f32>) {
+        match self {
+            Face::West | Face::East => (vec3(0.0, 1.0, 0.0), vec3(0.0, 0.0, 1.0)),
+            Face::South | Face::North => (vec3(1.0, 0.0, 0.0), vec3(0.0, 0.0, 1.0)),
+            Face::Bottom | Face::Top => (vec3(1.0, 0.0, 0.0), vec3(0.0, 1.0, 0.0)),
+        }
+    }
+
+    pub fn center(self, min: Vector3<f32>, max: Vector3<f32>) -> Vector3<f32> {
+        let corners = corners(min, max);
+        self.corner_indices()
+            .iter()
+            .map(|idx| corners[*idx as usize])
+            .sum::<Vector3<f32>>()
+            / 4.0
+    }
+}
+
+pub fn corners(min: Vector3<f32>, max: Vector3<f32>) -> [Vector3<f32>; 8] {
+    let pick = |bit: usize, min: f32, max: f32| if bit != 0 { max } else { min };
+    let mut ret = [min; 8];
+    for (idx, corner) in ret.iter_mut().enumerate() {
+        *corner = vec3(
+            pick(idx & 1, min.x, max.x),
+            pick(idx & 2, min.y, max.y),
+            pick(idx & 4, min.z, max.z),
+        );
+    }
+    ret
+}
+
+pub fn bounding_radius(min: Vector3<f32>, max: Vector3<f32>) -> f32 {
+    (max - min).magnitude() / 2.0
+}
@@ -0,0 +1,39 @@
+use crate::{ChunkFileBuilder, ChunkWriter};
+
+///
+/// gamesys
+///
+/// Start a .gam file with the chunks that are always read - an empty environmental sound
+/// database, sound schemas and speech database. Archetypes are added with the object helpers.
+pub fn gamesys() -> ChunkFileBuilder {
+    let mut builder = ChunkFileBuilder::new();
+    builder
+        .add_chunk("ENV_SOUND", empty_env_sound())
+        .add_chunk("SchSamp", Vec::new())
+        .add_chunk("Speech_DB", empty_speech_db());
+    builder
+}
+
+fn empty_env_sound() -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer
+        .write_u32(0) // required tags
+        .write_u32(0) // tag database data
+        .write_u32(0); // tag database keys
+    writer.into_bytes()
+}
+
+fn empty_speech_db() -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+
+    // Concept, tag and value name maps - bounds and size
+    for _ in 0..3 {
+        writer.write_i32(0).write_i32(0).write_u32(0);
+    }
+
+    writer
+        .write_u32(0) // priorities
+        .write_u32(0) // flags
+        .write_u32(0); // voices
+    writer.into_bytes()
+}
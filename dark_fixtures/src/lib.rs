@@ -0,0 +1,29 @@
+///
+/// dark_fixtures
+///
+/// Builders for small, synthetic tagged chunk files (.mis, .gam) - so the parsers in `dark`,
+/// and gameplay built on top of them, can be tested without the retail data.
+///
+/// Each builder produces the raw bytes for a single chunk, which are assembled into a file with
+/// `ChunkFileBuilder`. Positions, normals and plane distances are in Dark's coordinate space
+/// (z is up) and units - the same values that are in the retail files, before `dark` converts them.
+///
+mod chunk_file;
+mod chunk_writer;
+mod cuboid;
+mod gamesys;
+mod mission;
+mod objects;
+mod room_database;
+mod texture_list;
+mod world_rep;
+
+pub use chunk_file::*;
+pub use chunk_writer::*;
+pub use cuboid::*;
+pub use gamesys::*;
+pub use mission::*;
+pub use objects::*;
+pub use room_database::*;
+pub use texture_list::*;
+pub use world_rep::*;
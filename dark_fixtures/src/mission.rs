@@ -0,0 +1,41 @@
+use crate::{
+    obj_map, room_database, ChunkFileBuilder, ChunkWriter, TextureListBuilder, WorldRepBuilder,
+};
+
+///
+/// mission
+///
+/// Start a .mis file with the chunks that are always read - the world rep, textures, render and
+/// song params, and an empty OBJ_MAP and ROOM_DB. Objects (OBJ_MAP, properties and links) and
+/// rooms are added by the caller, replacing the empty chunks.
+pub fn mission(world_rep: &WorldRepBuilder, textures: &TextureListBuilder) -> ChunkFileBuilder {
+    let mut builder = ChunkFileBuilder::new();
+    builder
+        .add_chunk("WREXT", world_rep.build())
+        .add_chunk("TXLIST", textures.build())
+        .add_chunk("RENDPARAMS", render_params(0.0))
+        .add_chunk("SONGPARAMS", song_params(""))
+        .add_chunk("OBJ_MAP", obj_map(&[]))
+        .add_chunk("ROOM_DB", room_database(&[]));
+    builder
+}
+
+///
+/// render_params
+///
+/// The RENDPARAMS chunk - palette name and ambient light level
+pub fn render_params(ambient: f32) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer.write_string_with_size("", 16).write_single(ambient);
+    writer.into_bytes()
+}
+
+///
+/// song_params
+///
+/// The SONGPARAMS chunk - the song that plays in the mission
+pub fn song_params(song: &str) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer.write_string_with_size(song, 32);
+    writer.into_bytes()
+}
@@ -0,0 +1,144 @@
+use cgmath::Vector3;
+
+use crate::ChunkWriter;
+
+///
+/// obj_map
+///
+/// The OBJ_MAP chunk - the names of objects and archetypes, by id
+pub fn obj_map(names: &[(i32, &str)]) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    for (obj_id, name) in names {
+        // Sizes include the null terminator
+        writer
+            .write_i32(*obj_id)
+            .write_u32(name.len() as u32 + 1)
+            .write_string_with_size(name, name.len() + 1);
+    }
+    writer.into_bytes()
+}
+
+///
+/// property_chunk
+///
+/// A property chunk (ie, 'P$SymName') - the value of the property for each object, encoded with
+/// one of the property helpers below
+pub fn property_chunk(values: &[(i32, Vec<u8>)]) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    for (obj_id, value) in values {
+        writer
+            .write_i32(*obj_id)
+            .write_u32(value.len() as u32)
+            .write_bytes(value);
+    }
+    writer.into_bytes()
+}
+
+///
+/// string_property
+///
+/// Value for variable-length string properties, ie P$SymName or P$QBName
+pub fn string_property(value: &str) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer
+        .write_u32(0)
+        .write_string_with_size(value, value.len() + 1);
+    writer.into_bytes()
+}
+
+///
+/// scripts_property
+///
+/// Value for P$Scripts - up to four script names
+pub fn scripts_property(scripts: &[&str], inherits: bool) -> Vec<u8> {
+    assert!(scripts.len() <= 4, "P$Scripts holds at most four scripts");
+
+    let mut writer = ChunkWriter::new();
+    for idx in 0..4 {
+        writer.write_string_with_size(scripts.get(idx).unwrap_or(&""), 32);
+    }
+
+    // Stored as 'dont inherit'
+    writer.write_u32(if inherits { 0 } else { 1 });
+    writer.into_bytes()
+}
+
+///
+/// position_property
+///
+/// Value for P$Position, facing the default direction
+pub fn position_property(position: Vector3<f32>, cell: u16) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer
+        .write_vec3(position)
+        .write_u16(cell)
+        .write_i16(0)
+        .write_u16(0)
+        .write_u16(0)
+        .write_u16(0);
+    writer.into_bytes()
+}
+
+pub fn u32_property(value: u32) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer.write_u32(value);
+    writer.into_bytes()
+}
+
+///
+/// LinkRecord
+///
+/// A single link from one object to another - the name of the link chunk it is stored in
+/// (ie, 'L$SwitchLin') determines the kind of link
+#[derive(Clone, Debug)]
+pub struct LinkRecord {
+    pub id: i32,
+    pub src: i32,
+    pub dest: i32,
+    pub flavor: u16,
+}
+
+impl LinkRecord {
+    pub fn new(id: i32, src: i32, dest: i32) -> LinkRecord {
+        LinkRecord {
+            id,
+            src,
+            dest,
+            flavor: 0,
+        }
+    }
+}
+
+///
+/// link_chunk
+///
+/// A link chunk, ie 'L$SwitchLin', or 'L$MetaProp' for the archetype hierarchy
+pub fn link_chunk(links: &[LinkRecord]) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    for link in links {
+        writer
+            .write_i32(link.id)
+            .write_i32(link.src)
+            .write_i32(link.dest)
+            .write_u16(link.flavor);
+    }
+    writer.into_bytes()
+}
+
+///
+/// link_data_chunk
+///
+/// A link data chunk, ie 'LD$Contains' - fixed-size data for each link, by link id
+pub fn link_data_chunk(data_size: u32, data: &[(i32, Vec<u8>)]) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer.write_u32(data_size);
+    for (link_id, bytes) in data {
+        assert_eq!(
+            bytes.len(),
+            data_size as usize,
+            "link data for {link_id} is not {data_size} bytes"
+        );
+        writer.write_i32(*link_id).write_bytes(bytes);
+    }
+    writer.into_bytes()
+}
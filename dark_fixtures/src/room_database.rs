@@ -0,0 +1,102 @@
+use cgmath::{InnerSpace, Vector3};
+
+use crate::{ChunkWriter, Face};
+
+///
+/// CuboidRoom
+///
+/// An axis-aligned box room, with portals through its faces to neighboring rooms
+#[derive(Clone, Debug)]
+pub struct CuboidRoom {
+    pub obj_id: i32,
+    pub room_id: i16,
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+    pub portals: Vec<RoomPortalFixture>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoomPortalFixture {
+    pub id: i32,
+    pub face: Face,
+    pub dest_room: i32,
+    pub dest_portal: i32,
+}
+
+impl CuboidRoom {
+    pub fn new(obj_id: i32, room_id: i16, min: Vector3<f32>, max: Vector3<f32>) -> CuboidRoom {
+        CuboidRoom {
+            obj_id,
+            room_id,
+            min,
+            max,
+            portals: Vec::new(),
+        }
+    }
+
+    pub fn with_portal(
+        mut self,
+        id: i32,
+        face: Face,
+        dest_room: i32,
+        dest_portal: i32,
+    ) -> CuboidRoom {
+        self.portals.push(RoomPortalFixture {
+            id,
+            face,
+            dest_room,
+            dest_portal,
+        });
+        self
+    }
+
+    fn write(&self, writer: &mut ChunkWriter) {
+        writer
+            .write_i32(self.obj_id)
+            .write_i16(self.room_id)
+            .write_vec3((self.min + self.max) / 2.0);
+
+        for face in Face::ALL {
+            let (normal, distance) = face.plane(self.min, self.max);
+            writer.write_plane(normal, distance);
+        }
+
+        writer.write_u32(self.portals.len() as u32);
+        for (index, portal) in self.portals.iter().enumerate() {
+            let (normal, distance) = portal.face.plane(self.min, self.max);
+            writer
+                .write_i32(portal.id)
+                .write_u32(index as u32)
+                .write_plane(normal, distance)
+                .write_u32(0) // edges
+                .write_i32(self.room_id as i32)
+                .write_i32(portal.dest_room)
+                .write_vec3(portal.face.center(self.min, self.max))
+                .write_i32(portal.dest_portal);
+        }
+
+        // Distances between each pair of portals
+        for from in &self.portals {
+            for to in &self.portals {
+                let from_center = from.face.center(self.min, self.max);
+                let to_center = to.face.center(self.min, self.max);
+                writer.write_single((to_center - from_center).magnitude());
+            }
+        }
+
+        writer.write_u32(0); // object lists
+    }
+}
+
+///
+/// room_database
+///
+/// The ROOM_DB chunk
+pub fn room_database(rooms: &[CuboidRoom]) -> Vec<u8> {
+    let mut writer = ChunkWriter::new();
+    writer.write_u32(1).write_u32(rooms.len() as u32);
+    for room in rooms {
+        room.write(&mut writer);
+    }
+    writer.into_bytes()
+}
@@ -0,0 +1,61 @@
+use crate::ChunkWriter;
+
+///
+/// TextureListBuilder
+///
+/// The TXLIST chunk - the textures used by the world rep, grouped into families
+#[derive(Default)]
+pub struct TextureListBuilder {
+    families: Vec<String>,
+    textures: Vec<(u8, String)>,
+}
+
+impl TextureListBuilder {
+    pub fn new() -> TextureListBuilder {
+        TextureListBuilder {
+            families: Vec::new(),
+            textures: Vec::new(),
+        }
+    }
+
+    ///
+    /// add_family
+    ///
+    /// Add a texture family (ie, 'station'), returning its id. Ids start at 1 - textures
+    /// without a family use 0.
+    pub fn add_family(&mut self, name: &str) -> u8 {
+        self.families.push(name.to_owned());
+        self.families.len() as u8
+    }
+
+    ///
+    /// add_texture
+    ///
+    /// Add a texture, returning its index - which is what cells reference. Textures named 'null'
+    /// don't need an image to be loaded.
+    pub fn add_texture(&mut self, family: u8, name: &str) -> u16 {
+        self.textures.push((family, name.to_owned()));
+        (self.textures.len() - 1) as u16
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut body = ChunkWriter::new();
+        for family in &self.families {
+            body.write_string_with_size(family, 16);
+        }
+        for (family, name) in &self.textures {
+            body.write_u8(1)
+                .write_u8(*family)
+                .write_u16(0)
+                .write_string_with_size(name, 16);
+        }
+
+        let mut writer = ChunkWriter::new();
+        writer
+            .write_u32(12 + body.len() as u32)
+            .write_u32(self.textures.len() as u32)
+            .write_u32(self.families.len() as u32)
+            .write_bytes(&body.into_bytes());
+        writer.into_bytes()
+    }
+}
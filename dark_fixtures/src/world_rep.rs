@@ -0,0 +1,241 @@
+use cgmath::Vector3;
+
+use crate::{bounding_radius, corners, ChunkWriter, Face};
+
+// Marks a missing child in a BSP split node
+const NO_BSP_NODE: i32 = 0xFFFFFF;
+
+const BSP_FLAG_LEAF: u32 = 1;
+
+// Lightmaps are 16 bits per pixel (5-5-5 RGB) at this depth
+const LIGHTMAP_BIT_DEPTH: u32 = 0;
+const LIGHTMAP_PIXEL: u16 = 0x4210;
+
+///
+/// CuboidCell
+///
+/// An axis-aligned box cell. Each face is either a textured wall, or a portal to another cell.
+#[derive(Clone, Debug)]
+pub struct CuboidCell {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+    pub texture: u16,
+    pub portals: Vec<(Face, u16)>,
+}
+
+impl CuboidCell {
+    pub fn new(min: Vector3<f32>, max: Vector3<f32>) -> CuboidCell {
+        CuboidCell {
+            min,
+            max,
+            texture: 0,
+            portals: Vec::new(),
+        }
+    }
+
+    ///
+    /// with_texture
+    ///
+    /// Index into the TXLIST for the walls of the cell
+    pub fn with_texture(mut self, texture: u16) -> CuboidCell {
+        self.texture = texture;
+        self
+    }
+
+    pub fn with_portal(mut self, face: Face, target_cell: u16) -> CuboidCell {
+        self.portals.push((face, target_cell));
+        self
+    }
+
+    fn portal_target(&self, face: Face) -> Option<u16> {
+        self.portals
+            .iter()
+            .find(|(portal_face, _)| *portal_face == face)
+            .map(|(_, target_cell)| *target_cell)
+    }
+
+    fn write(&self, writer: &mut ChunkWriter) {
+        // Rendered polygons come first, followed by the portals
+        let (mut polygons, portals): (Vec<Face>, Vec<Face>) = Face::ALL
+            .into_iter()
+            .partition(|face| self.portal_target(*face).is_none());
+        let render_poly_count = polygons.len();
+        polygons.extend(portals.iter());
+
+        writer
+            .write_u8(8) // vertices
+            .write_u8(polygons.len() as u8)
+            .write_u8(render_poly_count as u8)
+            .write_u8(portals.len() as u8)
+            .write_u8(Face::ALL.len() as u8) // planes
+            .write_u8(1) // medium
+            .write_u8(0) // flags
+            .write_u32(0) // nxn
+            .write_u16(0) // poly map size
+            .write_u8(0) // animated lights
+            .write_u8(0) // flow group
+            .write_vec3((self.min + self.max) / 2.0)
+            .write_single(bounding_radius(self.min, self.max));
+
+        for corner in corners(self.min, self.max) {
+            writer.write_vec3(corner);
+        }
+
+        for face in &polygons {
+            writer
+                .write_u8(0) // flags
+                .write_u8(4) // vertex count
+                .write_u8(face.index() as u8)
+                .write_u8(0) // clut
+                .write_u16(self.portal_target(*face).unwrap_or(0))
+                .write_u8(0) // motion index
+                .write_u8(0);
+        }
+
+        for face in &polygons[..render_poly_count] {
+            let (axis_u, axis_v) = face.texture_axes();
+            writer
+                .write_vec3(axis_u)
+                .write_vec3(axis_v)
+                .write_single(0.0)
+                .write_single(0.0)
+                .write_u16(self.texture)
+                .write_u16(0) // origin vertex
+                .write_single(1.0)
+                .write_vec3(face.center(self.min, self.max));
+        }
+
+        writer.write_u32(polygons.len() as u32 * 4);
+        for face in &polygons {
+            writer.write_bytes(&face.corner_indices());
+        }
+
+        for face in Face::ALL {
+            let (normal, distance) = face.plane(self.min, self.max);
+            writer.write_plane(normal, distance);
+        }
+
+        // A single-pixel static lightmap for each rendered polygon, without animated lights
+        for _ in 0..render_poly_count {
+            writer
+                .write_i16(0) // u
+                .write_i16(0) // v
+                .write_u16(1) // width
+                .write_u8(1) // height
+                .write_u8(1)
+                .write_u32(0)
+                .write_u32(0)
+                .write_u32(0); // animation flags
+        }
+        for _ in 0..render_poly_count {
+            writer.write_u16(LIGHTMAP_PIXEL);
+        }
+        writer.write_u32(0); // lights
+    }
+}
+
+///
+/// BspNode
+///
+/// A node of the BSP tree used to find the cell containing a position. Splits reference one of
+/// the planes of their cell, and children by index into the node list - a position is in front
+/// of the plane if it's on the side the normal faces.
+#[derive(Clone, Debug)]
+pub enum BspNode {
+    Split {
+        cell: i32,
+        plane: u32,
+        front: Option<u32>,
+        back: Option<u32>,
+    },
+    Leaf {
+        cell: i32,
+    },
+}
+
+///
+/// WorldRepBuilder
+///
+/// The WREXT chunk - the cells, portals and BSP tree of a mission
+#[derive(Default)]
+pub struct WorldRepBuilder {
+    cells: Vec<CuboidCell>,
+    bsp_nodes: Vec<BspNode>,
+}
+
+impl WorldRepBuilder {
+    pub fn new() -> WorldRepBuilder {
+        WorldRepBuilder {
+            cells: Vec::new(),
+            bsp_nodes: Vec::new(),
+        }
+    }
+
+    pub fn add_cell(&mut self, cell: CuboidCell) -> u16 {
+        self.cells.push(cell);
+        (self.cells.len() - 1) as u16
+    }
+
+    ///
+    /// add_bsp_node
+    ///
+    /// Add a node to the BSP tree - the first node added is the root. Without any nodes, the tree
+    /// is a single leaf for the first cell.
+    pub fn add_bsp_node(&mut self, node: BspNode) -> u32 {
+        self.bsp_nodes.push(node);
+        (self.bsp_nodes.len() - 1) as u32
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut writer = ChunkWriter::new();
+        writer
+            .write_u32(0)
+            .write_u32(0)
+            .write_u32(0) // shadowed water
+            .write_u32(LIGHTMAP_BIT_DEPTH)
+            .write_u32(0)
+            .write_u32(0)
+            .write_u32(self.cells.len() as u32);
+
+        for cell in &self.cells {
+            cell.write(&mut writer);
+        }
+
+        let default_nodes = [BspNode::Leaf { cell: 0 }];
+        let bsp_nodes = if self.bsp_nodes.is_empty() {
+            &default_nodes[..]
+        } else {
+            &self.bsp_nodes[..]
+        };
+
+        writer.write_u32(0); // extra planes
+        writer.write_u32(bsp_nodes.len() as u32);
+        for (node_id, node) in bsp_nodes.iter().enumerate() {
+            let child = |child: &Option<u32>| child.map(|idx| idx as i32).unwrap_or(NO_BSP_NODE);
+            match node {
+                BspNode::Split {
+                    cell,
+                    plane,
+                    front,
+                    back,
+                } => writer
+                    .write_u32(node_id as u32)
+                    .write_i32(*cell)
+                    .write_u32(*plane)
+                    .write_i32(child(front))
+                    .write_i32(child(back)),
+                // Leaves store their cell in the 'front' slot
+                BspNode::Leaf { cell } => writer
+                    .write_u32((BSP_FLAG_LEAF << 24) | node_id as u32)
+                    .write_i32(*cell)
+                    .write_u32(0)
+                    .write_i32(*cell)
+                    .write_i32(NO_BSP_NODE),
+            };
+        }
+
+        writer.write_bytes(&vec![0; self.cells.len()]);
+        writer.write_u32(0).write_u32(0); // static and dynamic lights
+        writer.into_bytes()
+    }
+}
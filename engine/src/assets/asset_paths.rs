@@ -1,6 +1,6 @@
 use std::{
     cell::RefCell,
-    fs::File,
+    fs::{self, File},
     io::{self, BufReader},
     path::Path,
 };
@@ -13,6 +13,12 @@ pub trait ReadableAndSeekable: io::Read + io::Seek + Send + Sync + 'static {}
 
 impl<T> ReadableAndSeekable for T where T: io::Read + io::Seek + Send + Sync + 'static {}
 
+pub struct AssetListing {
+    // Description of the folder or archive the asset comes from
+    pub source: String,
+    pub asset_name: String,
+}
+
 pub trait AbstractAssetPath: Sync + Send {
     fn exists(&self, base_path: String, asset_name: String) -> bool;
 
@@ -21,6 +27,31 @@ pub trait AbstractAssetPath: Sync + Send {
         base_path: String,
         asset_name: String,
     ) -> Option<RefCell<Box<dyn ReadableAndSeekable>>>;
+
+    ///
+    /// describe
+    ///
+    /// A short description of where the assets come from, ie the folder or archive path
+    fn describe(&self) -> String;
+
+    ///
+    /// list_assets
+    ///
+    /// All the assets available from this path, in search order
+    fn list_assets(&self, base_path: String) -> Vec<AssetListing>;
+
+    ///
+    /// sources_of
+    ///
+    /// Every source that provides the asset, in search order - the first one is the one
+    /// that gets loaded, and the rest are shadowed by it.
+    fn sources_of(&self, base_path: String, asset_name: String) -> Vec<String> {
+        if self.exists(base_path, asset_name) {
+            vec![self.describe()]
+        } else {
+            vec![]
+        }
+    }
 }
 
 struct MultipleAssetPaths {
@@ -57,6 +88,30 @@ impl AbstractAssetPath for MultipleAssetPaths {
         }
         return None;
     }
+
+    fn describe(&self) -> String {
+        self.asset_paths
+            .iter()
+            .map(|asset_path| asset_path.describe())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn list_assets(&self, base_path: String) -> Vec<AssetListing> {
+        self.asset_paths
+            .iter()
+            .flat_map(|asset_path| asset_path.list_assets(base_path.to_owned()))
+            .collect()
+    }
+
+    fn sources_of(&self, base_path: String, asset_name: String) -> Vec<String> {
+        self.asset_paths
+            .iter()
+            .flat_map(|asset_path| {
+                asset_path.sources_of(base_path.to_owned(), asset_name.to_owned())
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -92,6 +147,34 @@ impl AbstractAssetPath for AssetPath {
         let reader = BufReader::new(file);
         Some(RefCell::new(Box::new(reader)))
     }
+
+    fn describe(&self) -> String {
+        self.folder_name.to_owned()
+    }
+
+    // Only the files directly in the folder - assets are looked up by name, not by sub-folder
+    fn list_assets(&self, base_path: String) -> Vec<AssetListing> {
+        let path = base_path + "/" + &self.folder_name;
+        let entries = match fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+
+        let mut asset_names = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        asset_names.sort();
+
+        asset_names
+            .into_iter()
+            .map(|asset_name| AssetListing {
+                source: self.describe(),
+                asset_name,
+            })
+            .collect()
+    }
 }
 
 impl AssetPath {
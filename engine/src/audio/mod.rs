@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::io::{BufReader, Cursor, Read};
 use std::rc::Rc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use cgmath::{vec3, Vector3};
 use rodio::buffer::SamplesBuffer;
@@ -17,6 +17,9 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::assets::asset_paths::ReadableAndSeekable;
 
+mod reverb;
+pub use reverb::*;
+
 #[cfg(target_os = "android")]
 const BASE_PATH: &str = "/mnt/sdcard/shock2quest";
 
@@ -27,6 +30,9 @@ static NEXT_HANDLE_ID: AtomicU64 = AtomicU64::new(0);
 
 const SOUND_SCALE_FACTOR: f32 = 5.0;
 
+// How long it takes to blend from one reverb setting to the next (ie, when moving between rooms)
+const REVERB_CROSSFADE_TIME: Duration = Duration::from_millis(750);
+
 #[derive(Clone, Debug)]
 pub struct AudioHandle {
     id: u64,
@@ -39,6 +45,39 @@ impl AudioHandle {
     }
 }
 
+///
+/// ReverbSettings
+///
+/// Parameters for the reverb applied to positional sounds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReverbSettings {
+    pub delay: Duration,
+    pub amplitude: f32,
+}
+
+impl ReverbSettings {
+    pub fn dry() -> ReverbSettings {
+        ReverbSettings {
+            delay: Duration::ZERO,
+            amplitude: 0.0,
+        }
+    }
+
+    pub fn is_dry(&self) -> bool {
+        self.delay.is_zero() || self.amplitude <= 0.01
+    }
+
+    pub fn lerp(&self, other: &ReverbSettings, amount: f32) -> ReverbSettings {
+        let amount = amount.clamp(0.0, 1.0);
+        let delay = self.delay.as_secs_f32()
+            + (other.delay.as_secs_f32() - self.delay.as_secs_f32()) * amount;
+        ReverbSettings {
+            delay: Duration::from_secs_f32(delay),
+            amplitude: self.amplitude + (other.amplitude - self.amplitude) * amount,
+        }
+    }
+}
+
 pub struct AudioChannel {
     name: String,
 }
@@ -88,6 +127,13 @@ impl SinkAdapter {
         }
     }
 
+    pub fn set_emitter_position(&self, position: [f32; 3]) {
+        match self {
+            SinkAdapter::StaticSink(_) => (),
+            SinkAdapter::PositionalSink(sink) => sink.set_emitter_position(position),
+        }
+    }
+
     pub fn empty(&self) -> bool {
         self.inner().empty()
     }
@@ -107,10 +153,14 @@ where
     sinks: Vec<Sink>,
     channel_to_last_handle: HashMap<String, u64>,
     handle_to_sink: HashMap<u64, SinkAdapter>,
+    // Reverb of the positional sounds that are playing, kept in step with the current reverb
+    handle_to_reverb: HashMap<u64, ReverbControl>,
     // Background music
     background_music: Option<Sink>,
     background_music_player: Option<Box<dyn BackgroundMusic<TCue>>>,
     next_music_cue: Option<TCue>,
+    // Cue used at every section boundary until it's cleared - ahead of the one-off cue
+    held_music_cue: Option<TCue>,
 
     // Environmental sounds
     environmental_sink: Option<(Sink, Rc<AudioClip>)>,
@@ -120,7 +170,12 @@ where
     last_right_ear_position: Vector3<f32>,
 
     // Ambient, positional sounds
-    ambient_sounds: HashMap<TAmbientKey, (SpatialSink, Rc<AudioClip>)>,
+    ambient_sounds: HashMap<TAmbientKey, (SpatialSink, Rc<AudioClip>, ReverbControl)>,
+
+    // Reverb for positional sounds, blending from -> to
+    reverb_from: ReverbSettings,
+    reverb_to: ReverbSettings,
+    reverb_transition_start: Instant,
 }
 
 impl<TAmbientKey, TCue> AudioContext<TAmbientKey, TCue>
@@ -136,10 +191,12 @@ where
             sinks: vec![],
             //spatial_sinks: vec![],
             handle_to_sink: HashMap::new(),
+            handle_to_reverb: HashMap::new(),
             channel_to_last_handle: HashMap::new(),
             background_music: None,
             background_music_player: None,
             next_music_cue: None,
+            held_music_cue: None,
 
             environmental_sink: None,
 
@@ -147,26 +204,62 @@ where
             last_right_ear_position: vec3(0.125, 0.0, 0.0),
 
             ambient_sounds: HashMap::new(),
+
+            reverb_from: ReverbSettings::dry(),
+            reverb_to: ReverbSettings::dry(),
+            reverb_transition_start: Instant::now(),
         }
     }
 
+    ///
+    /// set_reverb
+    ///
+    /// Set the reverb for positional sounds. The change is blended in over a short crossfade,
+    /// applied to sounds that are already playing, as well as ones played later.
+    pub fn set_reverb(&mut self, reverb: ReverbSettings) {
+        if self.reverb_to == reverb {
+            return;
+        }
+
+        self.reverb_from = self.current_reverb();
+        self.reverb_to = reverb;
+        self.reverb_transition_start = Instant::now();
+    }
+
+    pub fn current_reverb(&self) -> ReverbSettings {
+        let elapsed = self.reverb_transition_start.elapsed().as_secs_f32();
+        let amount = elapsed / REVERB_CROSSFADE_TIME.as_secs_f32();
+        self.reverb_from.lerp(&self.reverb_to, amount)
+    }
+
     pub fn set_background_music(
         &mut self,
         background_music_player: Box<dyn BackgroundMusic<TCue>>,
     ) -> () {
         self.background_music_player = Some(background_music_player);
         self.next_music_cue = None;
+        self.held_music_cue = None;
     }
 
     pub fn stop_background_music(&mut self) -> () {
         self.background_music_player = None;
         self.next_music_cue = None;
+        self.held_music_cue = None;
     }
 
     pub fn set_background_music_cue(&mut self, cue: TCue) -> () {
         self.next_music_cue = Some(cue)
     }
 
+    ///
+    /// hold_background_music_cue
+    ///
+    /// Keep using a cue at every section boundary, until it's cleared with None - for music that
+    /// should stay put for a while, like during combat
+    pub fn hold_background_music_cue(&mut self, cue: Option<TCue>) -> () {
+        self.held_music_cue = cue
+    }
+
     pub fn set_environmental_sound(&mut self, clip: Rc<AudioClip>) -> () {
         let sink = rodio::Sink::try_new(&self.handle).unwrap();
         clip.add_to_sink(&sink);
@@ -217,6 +310,14 @@ where
             sink.update_listener_position(left_ear_position, right_ear_position);
         }
 
+        let reverb = self.current_reverb();
+        let handle_to_sink = &self.handle_to_sink;
+        self.handle_to_reverb
+            .retain(|id, _| handle_to_sink.contains_key(id));
+        for (_, control) in &self.handle_to_reverb {
+            control.set(reverb);
+        }
+
         // Build hash map for new ambient sounds
         let mut current_sound_hash = HashMap::new();
         for (key, pos, clip) in &current_ambient_sounds {
@@ -224,11 +325,14 @@ where
         }
 
         let mut sounds_to_remove = HashSet::new();
+
         // First pass - check existing ambient sounds, update position, and see if they have completed
-        for (key, (sink, clip)) in &self.ambient_sounds {
+        for (key, (sink, clip, control)) in &mut self.ambient_sounds {
             if let Some(current_sound) = current_sound_hash.get(key) {
                 if sink.len() == 0 {
-                    clip.add_to_spatial_sink(&sink);
+                    *control = clip.add_to_spatial_sink_with_reverb(&sink, &reverb);
+                } else {
+                    control.set(reverb);
                 }
 
                 sink.set_emitter_position([
@@ -268,7 +372,14 @@ where
                 )
                 .unwrap();
 
-                self.ambient_sounds.insert(*key, (sink, clip.clone()));
+                self.ambient_sounds.insert(
+                    *key,
+                    (
+                        sink,
+                        clip.clone(),
+                        ReverbControl::new(ReverbSettings::dry()),
+                    ),
+                );
             }
         }
     }
@@ -281,11 +392,11 @@ where
         }
 
         if self.background_music.is_none() && self.background_music_player.is_some() {
-            let maybe_next = self
-                .background_music_player
-                .as_mut()
-                .unwrap()
-                .next_clip(self.next_music_cue.clone());
+            let maybe_next = self.background_music_player.as_mut().unwrap().next_clip(
+                self.held_music_cue
+                    .clone()
+                    .or_else(|| self.next_music_cue.clone()),
+            );
             if let Some(next_song) = maybe_next {
                 let sink = rodio::Sink::try_new(&self.handle).unwrap();
                 next_song.add_to_sink(&sink);
@@ -327,6 +438,27 @@ impl AudioClip {
             SourceType::Raw(source) => sink.append(source.clone()),
         }
     }
+    ///
+    /// add_to_spatial_sink_with_reverb
+    ///
+    /// Append the clip with a reverb that starts out at the given settings, and can be changed
+    /// while it plays through the returned control
+    pub fn add_to_spatial_sink_with_reverb(
+        &self,
+        sink: &SpatialSink,
+        reverb: &ReverbSettings,
+    ) -> ReverbControl {
+        let control = ReverbControl::new(*reverb);
+        match &self.source {
+            SourceType::Bytes(source) => {
+                sink.append(AdjustableReverb::new(source.clone(), control.clone()))
+            }
+            SourceType::Raw(source) => {
+                sink.append(AdjustableReverb::new(source.clone(), control.clone()))
+            }
+        }
+        control
+    }
     pub fn add_to_sink(&self, sink: &Sink) -> () {
         match &self.source {
             SourceType::Bytes(source) => sink.append(source.clone()),
@@ -360,6 +492,31 @@ pub fn stop_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     }
 }
 
+pub fn set_volume<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
+    context: &mut AudioContext<TAmbientKey, TCue>,
+    handle: AudioHandle,
+    volume: f32,
+) {
+    if let Some(sink) = context.handle_to_sink.get(&handle.id) {
+        sink.inner().set_volume(volume);
+    }
+}
+
+///
+/// set_emitter_position
+///
+/// Move a positional sound that's already playing, like a line spoken by a creature on the move
+pub fn set_emitter_position<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
+    context: &mut AudioContext<TAmbientKey, TCue>,
+    handle: &AudioHandle,
+    position: Vector3<f32>,
+) {
+    if let Some(sink) = context.handle_to_sink.get(&handle.id) {
+        let scaled_position = position / SOUND_SCALE_FACTOR;
+        sink.set_emitter_position([scaled_position.x, scaled_position.y, scaled_position.z]);
+    }
+}
+
 pub fn test_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     context: &mut AudioContext<TAmbientKey, TCue>,
     handle: AudioHandle,
@@ -369,7 +526,14 @@ pub fn test_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     let position = (context.last_left_ear_position + context.last_right_ear_position) / 2.0;
 
     let id = handle.id.clone();
-    let sink = play_audio_core(context, position, handle, maybe_channel, audio_clip);
+    let (sink, _reverb) = play_audio_core(
+        context,
+        position,
+        handle,
+        maybe_channel,
+        audio_clip,
+        ReverbSettings::dry(),
+    );
 
     context.handle_to_sink.insert(id, SinkAdapter::fixed(sink));
 }
@@ -383,11 +547,20 @@ pub fn play_spatial_audio<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
 ) {
     let id = handle.id.clone();
     let scaled_position = position / SOUND_SCALE_FACTOR;
-    let sink = play_audio_core(context, scaled_position, handle, maybe_channel, audio_clip);
+    let reverb = context.current_reverb();
+    let (sink, reverb_control) = play_audio_core(
+        context,
+        scaled_position,
+        handle,
+        maybe_channel,
+        audio_clip,
+        reverb,
+    );
 
     context
         .handle_to_sink
         .insert(id, SinkAdapter::positional(sink));
+    context.handle_to_reverb.insert(id, reverb_control);
 }
 
 pub fn play_audio_core<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
@@ -396,7 +569,8 @@ pub fn play_audio_core<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     handle: AudioHandle,
     maybe_channel: Option<AudioChannel>,
     audio_clip: Rc<AudioClip>,
-) -> SpatialSink {
+    reverb: ReverbSettings,
+) -> (SpatialSink, ReverbControl) {
     if let Some(channel) = maybe_channel {
         let maybe_previous_audio = context.channel_to_last_handle.get(&channel.name);
         if let Some(audio) = maybe_previous_audio {
@@ -420,7 +594,6 @@ pub fn play_audio_core<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
         }
     }
 
-    //let reverb = source.buffered().reverb(Duration::from_millis(40), 0.7);
     // let x = rand::thread_rng().gen_range(-1.0..1.0);
     // let y = rand::thread_rng().gen_range(-1.0..1.0);
     // let z = rand::thread_rng().gen_range(-1.0..1.0);
@@ -436,10 +609,21 @@ pub fn play_audio_core<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
     );
     let sink = rodio::SpatialSink::try_new(&context.handle, positions.0, positions.1, positions.2)
         .unwrap();
-    audio_clip.add_to_spatial_sink(&sink);
+    let reverb_control = audio_clip.add_to_spatial_sink_with_reverb(&sink, &reverb);
 
     //context.handle_to_sink.insert(handle.id, sink);
-    sink
+    (sink, reverb_control)
 
     //context.spatial_sinks.push(sink);
 }
+
+pub fn is_playing<TAmbientKey: Hash + Eq + Copy, TCue: Clone>(
+    context: &AudioContext<TAmbientKey, TCue>,
+    handle: &AudioHandle,
+) -> bool {
+    context
+        .handle_to_sink
+        .get(&handle.id)
+        .map(|sink| !sink.empty())
+        .unwrap_or(false)
+}
@@ -0,0 +1,221 @@
+// Reverb
+//
+// An echo on a playing sound whose delay and amplitude can change while it plays. rodio's own
+// reverb is fixed once a source is appended to a sink, which would leave sounds already playing
+// stuck with the reverb of the room they started in. Here, the settings live behind a shared
+// ReverbControl, and the audio update moves them along as the listener goes from room to room.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rodio::{Sample, Source};
+
+use super::ReverbSettings;
+
+// Longest delay the echo can have - the delay line is sized for this up front
+const MAX_REVERB_DELAY: Duration = Duration::from_millis(250);
+
+///
+/// ReverbControl
+///
+/// Handle to the settings of a playing AdjustableReverb. Cheap to clone, and safe to set from the
+/// game thread while the sound plays on the audio thread.
+#[derive(Clone, Debug)]
+pub struct ReverbControl {
+    // Delay in seconds, and amplitude - both stored as f32 bits
+    delay: Arc<AtomicU32>,
+    amplitude: Arc<AtomicU32>,
+}
+
+impl ReverbControl {
+    pub fn new(reverb: ReverbSettings) -> ReverbControl {
+        let control = ReverbControl {
+            delay: Arc::new(AtomicU32::new(0)),
+            amplitude: Arc::new(AtomicU32::new(0)),
+        };
+        control.set(reverb);
+        control
+    }
+
+    pub fn set(&self, reverb: ReverbSettings) {
+        let delay = reverb.delay.min(MAX_REVERB_DELAY).as_secs_f32();
+        self.delay.store(delay.to_bits(), Ordering::Relaxed);
+        self.amplitude
+            .store(reverb.amplitude.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> ReverbSettings {
+        ReverbSettings {
+            delay: Duration::from_secs_f32(f32::from_bits(self.delay.load(Ordering::Relaxed))),
+            amplitude: f32::from_bits(self.amplitude.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+///
+/// AdjustableReverb
+///
+/// Mixes a delayed, quieter copy of the input back in, like rodio's reverb - but reading the delay
+/// and amplitude from a ReverbControl for every sample
+pub struct AdjustableReverb<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    input: I,
+    control: ReverbControl,
+    // Past input samples, newest at the back
+    history: VecDeque<I::Item>,
+    max_history: usize,
+    // Samples left to play after the input has finished, so the echo isn't cut off
+    tail_remaining: Option<usize>,
+}
+
+impl<I> AdjustableReverb<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    pub fn new(input: I, control: ReverbControl) -> AdjustableReverb<I> {
+        let max_history = samples_for(&input, MAX_REVERB_DELAY) + 1;
+        AdjustableReverb {
+            input,
+            control,
+            history: VecDeque::with_capacity(max_history),
+            max_history,
+            tail_remaining: None,
+        }
+    }
+
+    fn delay_samples(&self) -> usize {
+        samples_for(&self.input, self.control.get().delay).min(self.max_history - 1)
+    }
+
+    // The input sample from `delay` samples ago, including the one just pushed
+    fn echo(&self, delay: usize) -> I::Item {
+        let reverb = self.control.get();
+        if reverb.is_dry() || delay >= self.history.len() {
+            return I::Item::zero_value();
+        }
+
+        self.history[self.history.len() - 1 - delay].amplify(reverb.amplitude)
+    }
+}
+
+// Number of interleaved samples covering a duration, in whole frames, so the echo of the left
+// channel stays on the left channel
+fn samples_for<I: Source>(input: &I, duration: Duration) -> usize
+where
+    I::Item: Sample,
+{
+    let frames = (duration.as_secs_f32() * input.sample_rate() as f32) as usize;
+    frames * input.channels() as usize
+}
+
+impl<I> Iterator for AdjustableReverb<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let delay = self.delay_samples();
+
+        if self.tail_remaining.is_none() {
+            if let Some(sample) = self.input.next() {
+                if self.history.len() == self.max_history {
+                    self.history.pop_front();
+                }
+                self.history.push_back(sample);
+                return Some(sample.saturating_add(self.echo(delay)));
+            }
+
+            self.tail_remaining = Some(if self.control.get().is_dry() {
+                0
+            } else {
+                delay
+            });
+        }
+
+        let remaining = self.tail_remaining.unwrap_or(0);
+        if remaining == 0 {
+            return None;
+        }
+
+        // Past the end of the input, only the echo is left - which gets closer to the end of the
+        // history with every sample
+        self.tail_remaining = Some(remaining - 1);
+        Some(self.echo(remaining - 1))
+    }
+}
+
+impl<I> Source for AdjustableReverb<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        match self.tail_remaining {
+            Some(remaining) => Some(remaining),
+            None => self.input.current_frame_len(),
+        }
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    #[test]
+    fn test_echo_follows_the_control_while_playing() {
+        // Given - a mono sound at 10 samples a second, so a 0.1s delay is one sample
+        let control = ReverbControl::new(ReverbSettings {
+            delay: Duration::from_millis(100),
+            amplitude: 0.5,
+        });
+        let input = SamplesBuffer::new(1, 10, vec![1.0f32, 0.0, 1.0, 0.0]);
+        let mut reverb = AdjustableReverb::new(input, control.clone());
+
+        // When - the amplitude changes halfway through
+        let first_half: Vec<f32> = (0..2).filter_map(|_| reverb.next()).collect();
+        control.set(ReverbSettings {
+            delay: Duration::from_millis(100),
+            amplitude: 0.25,
+        });
+        let second_half: Vec<f32> = reverb.collect();
+
+        // Then - the echo after the first pulse is at the old amplitude, and the echo after the
+        // second pulse - played after the input ended - is at the new one
+        assert_eq!(first_half, vec![1.0, 0.5]);
+        assert_eq!(second_half, vec![1.0, 0.25, 0.0]);
+    }
+
+    #[test]
+    fn test_dry_control_passes_input_through() {
+        // Given
+        let control = ReverbControl::new(ReverbSettings::dry());
+        let input = SamplesBuffer::new(1, 10, vec![1.0f32, 0.5, 0.25]);
+
+        // When
+        let output: Vec<f32> = AdjustableReverb::new(input, control).collect();
+
+        // Then
+        assert_eq!(output, vec![1.0, 0.5, 0.25]);
+    }
+}
@@ -10,6 +10,8 @@ pub mod materials;
 pub mod scene;
 mod shader;
 mod shader_program;
+pub mod software;
+mod software_engine;
 pub mod texture;
 pub mod texture_atlas;
 pub mod texture_descriptor;
@@ -19,6 +21,7 @@ pub mod util;
 pub use crate::engine::Engine;
 pub use crate::engine::EngineRenderContext;
 pub use crate::font::{Font, FontCharacterInfo};
+pub use crate::software_engine::SoftwareEngine;
 
 pub fn opengl() -> Box<dyn Engine> {
     let engine = gl_engine::init_gl();
@@ -30,6 +33,15 @@ pub fn opengles() -> Box<dyn Engine> {
     Box::new(engine)
 }
 
+///
+/// software
+///
+/// Draws on the CPU into an image, instead of with OpenGL - for screenshot tests on machines
+/// without a GPU
+pub fn software(width: u32, height: u32) -> SoftwareEngine {
+    SoftwareEngine::new(width, height, gl_engine::create_desktop_storage())
+}
+
 #[cfg(target_os = "android")]
 pub fn android() -> Box<dyn Engine> {
     let engine = gl_engine::init_android();
@@ -2,13 +2,16 @@ extern crate gl;
 use crate::engine::EngineRenderContext;
 use crate::scene::Material;
 use crate::shader_program::ShaderProgram;
+use crate::software::{self, ShaderContext, SoftwareShader, SoftwareVertex};
 use crate::texture::Texture;
 use crate::texture::TextureTrait;
 use crate::texture_descriptor::TextureDescriptor;
 use c_string::*;
 use cgmath::prelude::*;
 
+use cgmath::vec2;
 use cgmath::Matrix4;
+use cgmath::Vector4;
 use once_cell::sync::OnceCell;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -163,4 +166,42 @@ impl Material for LightmapMaterial {
         }
         true
     }
+
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        Some(self)
+    }
+}
+
+impl SoftwareShader for LightmapMaterial {
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32> {
+        software::project(context, vertex.position)
+    }
+
+    fn fragment(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Option<Vector4<f32>> {
+        // Same wrapping within the atlas as the fragment shader
+        let half_pixel = 0.5 / 4096.0;
+        let full_pixel = half_pixel * 2.0;
+        let atlas = vertex.lightmap_atlas;
+        let width = atlas.z - full_pixel;
+        let height = atlas.w - full_pixel;
+
+        let wrapped_tex_coord = vec2(
+            glsl_mod(vertex.lightmap_uv.x * width, width) + atlas.x + half_pixel,
+            glsl_mod(vertex.lightmap_uv.y * height, height) + atlas.y + half_pixel,
+        );
+
+        let lightmap_color = self
+            .lightmap_texture
+            .sample(context.render_context, wrapped_tex_coord);
+        let diffuse_color = self
+            .diffuse_texture
+            .sample(context.render_context, vertex.uv);
+        let frag_color = diffuse_color.mul_element_wise(lightmap_color);
+        Some(frag_color.truncate().extend(1.0))
+    }
+}
+
+// GLSL's mod, which (unlike %) follows the sign of the divisor
+fn glsl_mod(x: f32, y: f32) -> f32 {
+    x - y * (x / y).floor()
 }
@@ -7,12 +7,14 @@ use crate::scene::Material;
 use crate::shader;
 use crate::shader::Shader;
 use crate::shader_program::ShaderProgram;
+use crate::software::{ShaderContext, SoftwareShader, SoftwareVertex};
 
 use crate::texture::Texture;
 use crate::texture::TextureTrait;
 use crate::texture_descriptor::TextureDescriptor;
 use c_string::*;
 use cgmath::prelude::*;
+use cgmath::vec4;
 use cgmath::Matrix4;
 use cgmath::Vector3;
 use cgmath::Vector4;
@@ -168,4 +170,28 @@ impl Material for ScreenSpaceMaterial {
         self.draw_common(render_context, view_matrix, world_matrix);
         true
     }
+
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        Some(self)
+    }
+}
+
+impl SoftwareShader for ScreenSpaceMaterial {
+    // Like the GL engine, screen-space materials are drawn in the transparent pass
+    fn is_transparent(&self) -> bool {
+        true
+    }
+
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32> {
+        let screen_size = context.render_context.screen_size;
+        let projection = cgmath::ortho(0.0, screen_size.x, screen_size.y, 0.0, 0.0, 1.0);
+        projection * context.world_matrix * vec4(vertex.position.x, vertex.position.y, 0.0, 1.0)
+    }
+
+    fn fragment(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Option<Vector4<f32>> {
+        let sampled = self
+            .diffuse_texture
+            .sample(context.render_context, vertex.uv);
+        Some(self.text_color.mul_element_wise(sampled))
+    }
 }
@@ -8,6 +8,7 @@ use crate::scene::Material;
 use crate::shader;
 use crate::shader::Shader;
 use crate::shader_program::ShaderProgram;
+use crate::software::{self, ShaderContext, SoftwareShader, SoftwareVertex};
 
 use crate::texture::Texture;
 use crate::texture::TextureTrait;
@@ -15,6 +16,7 @@ use crate::texture_descriptor::TextureDescriptor;
 use c_string::*;
 use cgmath::prelude::*;
 use cgmath::Matrix4;
+use cgmath::Vector4;
 
 use once_cell::sync::OnceCell;
 
@@ -192,6 +194,10 @@ where
             false
         }
     }
+
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        Some(self)
+    }
 }
 
 pub fn create<T>(diffuse_texture: T, emissivity: f32, transparency: f32) -> Box<dyn Material>
@@ -205,3 +211,23 @@ where
         transparency,
     })
 }
+
+impl<T> SoftwareShader for BasicMaterial<T>
+where
+    T: Deref<Target = dyn TextureTrait>,
+{
+    fn is_transparent(&self) -> bool {
+        BasicMaterial::is_transparent(self)
+    }
+
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32> {
+        software::project(context, vertex.position)
+    }
+
+    fn fragment(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Option<Vector4<f32>> {
+        let texture_color = self
+            .diffuse_texture
+            .sample(context.render_context, vertex.uv);
+        software::unlit_fragment(texture_color, self.emissivity, self.transparency)
+    }
+}
@@ -8,13 +8,16 @@ use crate::scene::Material;
 use crate::shader;
 use crate::shader::Shader;
 use crate::shader_program::ShaderProgram;
+use crate::software::{self, ShaderContext, SoftwareShader, SoftwareVertex};
 
 use crate::texture::Texture;
 use crate::texture::TextureTrait;
 use crate::texture_descriptor::TextureDescriptor;
 use c_string::*;
 use cgmath::prelude::*;
+use cgmath::vec3;
 use cgmath::Matrix4;
+use cgmath::Vector4;
 
 use once_cell::sync::OnceCell;
 
@@ -217,4 +220,36 @@ where
             false
         }
     }
+
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        Some(self)
+    }
+}
+
+impl<T> SoftwareShader for BillboardMaterial<T>
+where
+    T: Deref<Target = dyn TextureTrait>,
+{
+    fn is_transparent(&self) -> bool {
+        BillboardMaterial::is_transparent(self)
+    }
+
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32> {
+        let view = context.view_matrix;
+        let right = vec3(view.x.x, view.y.x, view.z.x);
+        let up = vec3(view.x.y, view.y.y, view.z.y);
+        let billboard_center = context.world_matrix.w.truncate();
+
+        let adjusted_pos = billboard_center
+            + right * vertex.position.x * self.scale
+            + up * vertex.position.y * self.scale;
+        context.render_context.projection_matrix * view * adjusted_pos.extend(1.0)
+    }
+
+    fn fragment(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Option<Vector4<f32>> {
+        let texture_color = self
+            .diffuse_texture
+            .sample(context.render_context, vertex.uv);
+        software::unlit_fragment(texture_color, self.emissivity, self.transparency)
+    }
 }
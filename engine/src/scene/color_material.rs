@@ -2,10 +2,11 @@ extern crate gl;
 use crate::engine::EngineRenderContext;
 use crate::scene::Material;
 use crate::shader_program::ShaderProgram;
+use crate::software::{self, ShaderContext, SoftwareShader, SoftwareVertex};
 
 use c_string::*;
 use cgmath::prelude::*;
-use cgmath::{Matrix4, Vector3};
+use cgmath::{Matrix4, Vector3, Vector4};
 use once_cell::sync::OnceCell;
 
 const VERTEX_SHADER_SOURCE: &str = r#"
@@ -110,6 +111,10 @@ impl Material for ColorMaterial {
         }
         true
     }
+
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        Some(self)
+    }
 }
 
 pub fn create(color: Vector3<f32>) -> Box<dyn Material> {
@@ -118,3 +123,13 @@ pub fn create(color: Vector3<f32>) -> Box<dyn Material> {
         color,
     })
 }
+
+impl SoftwareShader for ColorMaterial {
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32> {
+        software::project(context, vertex.position)
+    }
+
+    fn fragment(&self, _context: &ShaderContext, _vertex: &SoftwareVertex) -> Option<Vector4<f32>> {
+        Some(self.color.extend(1.0))
+    }
+}
@@ -3,12 +3,29 @@ use once_cell::sync::OnceCell;
 
 use self::gl::types::*;
 pub use crate::scene::Geometry;
+use crate::software::SoftwareVertex;
+use cgmath::{vec2, vec3};
 use std::mem;
 use std::os::raw::c_void;
 use std::ptr;
 
 pub struct Cube {}
 
+// Position and uv of each vertex
+const VERTICES: [f32; 180] = [
+    -0.5, -0.5, -0.5, 0.0, 0.0, 0.5, -0.5, -0.5, 1.0, 0.0, 0.5, 0.5, -0.5, 1.0, 1.0, 0.5, 0.5,
+    -0.5, 1.0, 1.0, -0.5, 0.5, -0.5, 0.0, 1.0, -0.5, -0.5, -0.5, 0.0, 0.0, -0.5, -0.5, 0.5, 0.0,
+    0.0, 0.5, -0.5, 0.5, 1.0, 0.0, 0.5, 0.5, 0.5, 1.0, 1.0, 0.5, 0.5, 0.5, 1.0, 1.0, -0.5, 0.5,
+    0.5, 0.0, 1.0, -0.5, -0.5, 0.5, 0.0, 0.0, -0.5, 0.5, 0.5, 1.0, 0.0, -0.5, 0.5, -0.5, 1.0, 1.0,
+    -0.5, -0.5, -0.5, 0.0, 1.0, -0.5, -0.5, -0.5, 0.0, 1.0, -0.5, -0.5, 0.5, 0.0, 0.0, -0.5, 0.5,
+    0.5, 1.0, 0.0, 0.5, 0.5, 0.5, 1.0, 0.0, 0.5, 0.5, -0.5, 1.0, 1.0, 0.5, -0.5, -0.5, 0.0, 1.0,
+    0.5, -0.5, -0.5, 0.0, 1.0, 0.5, -0.5, 0.5, 0.0, 0.0, 0.5, 0.5, 0.5, 1.0, 0.0, -0.5, -0.5, -0.5,
+    0.0, 1.0, 0.5, -0.5, -0.5, 1.0, 1.0, 0.5, -0.5, 0.5, 1.0, 0.0, 0.5, -0.5, 0.5, 1.0, 0.0, -0.5,
+    -0.5, 0.5, 0.0, 0.0, -0.5, -0.5, -0.5, 0.0, 1.0, -0.5, 0.5, -0.5, 0.0, 1.0, 0.5, 0.5, -0.5,
+    1.0, 1.0, 0.5, 0.5, 0.5, 1.0, 0.0, 0.5, 0.5, 0.5, 1.0, 0.0, -0.5, 0.5, 0.5, 0.0, 0.0, -0.5,
+    0.5, -0.5, 0.0, 1.0,
+];
+
 static CUBE_GEOMETRY: OnceCell<GLuint> = OnceCell::new();
 
 pub fn create() -> Cube {
@@ -20,20 +37,6 @@ pub fn create() -> Cube {
 impl Geometry for Cube {
     fn draw(&self) {
         let vao = *CUBE_GEOMETRY.get_or_init(|| {
-            let vertices: [f32; 180] = [
-                -0.5, -0.5, -0.5, 0.0, 0.0, 0.5, -0.5, -0.5, 1.0, 0.0, 0.5, 0.5, -0.5, 1.0, 1.0,
-                0.5, 0.5, -0.5, 1.0, 1.0, -0.5, 0.5, -0.5, 0.0, 1.0, -0.5, -0.5, -0.5, 0.0, 0.0,
-                -0.5, -0.5, 0.5, 0.0, 0.0, 0.5, -0.5, 0.5, 1.0, 0.0, 0.5, 0.5, 0.5, 1.0, 1.0, 0.5,
-                0.5, 0.5, 1.0, 1.0, -0.5, 0.5, 0.5, 0.0, 1.0, -0.5, -0.5, 0.5, 0.0, 0.0, -0.5, 0.5,
-                0.5, 1.0, 0.0, -0.5, 0.5, -0.5, 1.0, 1.0, -0.5, -0.5, -0.5, 0.0, 1.0, -0.5, -0.5,
-                -0.5, 0.0, 1.0, -0.5, -0.5, 0.5, 0.0, 0.0, -0.5, 0.5, 0.5, 1.0, 0.0, 0.5, 0.5, 0.5,
-                1.0, 0.0, 0.5, 0.5, -0.5, 1.0, 1.0, 0.5, -0.5, -0.5, 0.0, 1.0, 0.5, -0.5, -0.5,
-                0.0, 1.0, 0.5, -0.5, 0.5, 0.0, 0.0, 0.5, 0.5, 0.5, 1.0, 0.0, -0.5, -0.5, -0.5, 0.0,
-                1.0, 0.5, -0.5, -0.5, 1.0, 1.0, 0.5, -0.5, 0.5, 1.0, 0.0, 0.5, -0.5, 0.5, 1.0, 0.0,
-                -0.5, -0.5, 0.5, 0.0, 0.0, -0.5, -0.5, -0.5, 0.0, 1.0, -0.5, 0.5, -0.5, 0.0, 1.0,
-                0.5, 0.5, -0.5, 1.0, 1.0, 0.5, 0.5, 0.5, 1.0, 0.0, 0.5, 0.5, 0.5, 1.0, 0.0, -0.5,
-                0.5, 0.5, 0.0, 0.0, -0.5, 0.5, -0.5, 0.0, 1.0,
-            ];
             let indices = [
                 0, 1, 3, // first Triangle
                 1, 2, 3, // second Triangle
@@ -50,8 +53,8 @@ impl Geometry for Cube {
                 gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
                 gl::BufferData(
                     gl::ARRAY_BUFFER,
-                    (vertices.len() * mem::size_of::<GLfloat>()) as GLsizeiptr,
-                    &vertices[0] as *const f32 as *const c_void,
+                    (VERTICES.len() * mem::size_of::<GLfloat>()) as GLsizeiptr,
+                    &VERTICES[0] as *const f32 as *const c_void,
                     gl::STATIC_DRAW,
                 );
 
@@ -99,4 +102,14 @@ impl Geometry for Cube {
             gl::DrawArrays(gl::TRIANGLES, 0, 36);
         }
     }
+
+    fn software_vertices(&self) -> Vec<SoftwareVertex> {
+        VERTICES
+            .chunks(5)
+            .map(|v| SoftwareVertex {
+                uv: vec2(v[3], v[4]),
+                ..SoftwareVertex::new(vec3(v[0], v[1], v[2]))
+            })
+            .collect()
+    }
 }
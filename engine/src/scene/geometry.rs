@@ -1,5 +1,16 @@
+use crate::software::SoftwareVertex;
+
 pub trait Geometry {
     fn draw(&self);
+
+    ///
+    /// software_vertices
+    ///
+    /// The triangle list drawn by the software engine. Geometry that isn't drawn as triangles,
+    /// like lines, has none.
+    fn software_vertices(&self) -> Vec<SoftwareVertex> {
+        Vec::new()
+    }
 }
 
 pub struct EmptyMesh;
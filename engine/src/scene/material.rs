@@ -1,4 +1,5 @@
 use crate::engine::EngineRenderContext;
+use crate::software::SoftwareShader;
 use cgmath::Matrix4;
 
 pub trait Material {
@@ -20,4 +21,13 @@ pub trait Material {
     ) -> bool {
         false
     }
+
+    ///
+    /// software_shader
+    ///
+    /// The CPU equivalent of the material's shaders, used by the software engine. Materials
+    /// without one aren't drawn by it.
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        None
+    }
 }
@@ -2,6 +2,7 @@ extern crate gl;
 use self::gl::types::*;
 pub use crate::scene::Geometry;
 pub use crate::scene::VertexPositionTexture;
+use std::cell::{Cell, RefCell};
 use std::mem;
 use std::mem::size_of;
 use std::os::raw::c_void;
@@ -9,21 +10,57 @@ use std::ptr;
 
 use super::Vertex;
 use super::VertexAttributeType;
+use crate::software::SoftwareVertex;
 
-#[derive(Clone)]
+#[derive(Clone, Copy)]
+struct MeshBuffers {
+    vbo: GLuint,
+    vao: GLuint,
+    ebo: GLuint,
+}
+
+///
+/// Mesh
+///
+/// Vertices are kept on the CPU until the mesh is first drawn, so meshes can be created without
+/// a GL context - and rasterized by the software engine, which never uploads them.
 pub struct Mesh {
-    pub vbo: GLuint,
-    pub vao: GLuint,
-    pub ebo: GLuint,
+    vertices: RefCell<Option<Box<dyn VertexData>>>,
+    buffers: Cell<Option<MeshBuffers>>,
 
     pub triangle_count: i32,
 }
 
+// Like textures, meshes are only used from the render thread
+unsafe impl Send for Mesh {}
+unsafe impl Sync for Mesh {}
+
+trait VertexData {
+    fn upload(&self) -> MeshBuffers;
+    fn software_vertices(&self) -> Vec<SoftwareVertex>;
+}
+
+impl<T: Vertex> VertexData for Vec<T> {
+    fn upload(&self) -> MeshBuffers {
+        upload(self)
+    }
+
+    fn software_vertices(&self) -> Vec<SoftwareVertex> {
+        self.iter().map(|v| v.to_software_vertex()).collect()
+    }
+}
+
 // use std::backtrace::Backtrace;
 
-pub fn create<T: Vertex>(raw_vertices: Vec<T>) -> Mesh {
-    let triangle_count = (raw_vertices.len()) as i32;
+pub fn create<T: Vertex + 'static>(raw_vertices: Vec<T>) -> Mesh {
+    Mesh {
+        triangle_count: raw_vertices.len() as i32,
+        vertices: RefCell::new(Some(Box::new(raw_vertices))),
+        buffers: Cell::new(None),
+    }
+}
 
+fn upload<T: Vertex>(raw_vertices: &[T]) -> MeshBuffers {
     // let vertices: Vec<f32> = raw_vertices
     //     .into_iter()
     //     .flat_map(|v| {
@@ -111,31 +148,58 @@ pub fn create<T: Vertex>(raw_vertices: Vec<T>) -> Mesh {
     }
 
     // uncomment this call to draw in wireframe polygons.
-    Mesh {
-        triangle_count,
+    MeshBuffers {
         vao: VAO,
         vbo: VBO,
         ebo: EBO,
     }
 }
 
+impl Mesh {
+    fn buffers(&self) -> MeshBuffers {
+        if let Some(buffers) = self.buffers.get() {
+            return buffers;
+        }
+
+        let vertices = self
+            .vertices
+            .borrow_mut()
+            .take()
+            .expect("mesh has no vertices to upload");
+        let buffers = vertices.upload();
+        self.buffers.set(Some(buffers));
+        buffers
+    }
+}
+
 impl Geometry for Mesh {
     fn draw(&self) {
+        let buffers = self.buffers();
         unsafe {
             //gl::PolygonMode(gl::FRONT_AND_BACK, gl::LINE);
-            gl::BindVertexArray(self.vao);
+            gl::BindVertexArray(buffers.vao);
             //    gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, self.ebo);
             gl::DrawArrays(gl::TRIANGLES, 0, self.triangle_count);
         }
     }
+
+    fn software_vertices(&self) -> Vec<SoftwareVertex> {
+        self.vertices
+            .borrow()
+            .as_ref()
+            .map(|vertices| vertices.software_vertices())
+            .unwrap_or_default()
+    }
 }
 
 impl Drop for Mesh {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteBuffers(1, &self.ebo);
-            gl::DeleteBuffers(1, &self.vbo);
-            gl::DeleteVertexArrays(1, &self.vao);
+        if let Some(buffers) = self.buffers.get() {
+            unsafe {
+                gl::DeleteBuffers(1, &buffers.ebo);
+                gl::DeleteBuffers(1, &buffers.vbo);
+                gl::DeleteVertexArrays(1, &buffers.vao);
+            }
         }
     }
 }
@@ -9,6 +9,7 @@ use std::os::raw::c_void;
 use std::ptr;
 
 use super::{mesh, Mesh, VertexPositionTexture};
+use crate::software::SoftwareVertex;
 
 pub struct Quad;
 
@@ -18,41 +19,47 @@ pub fn create() -> Quad {
     Quad
 }
 
+fn geometry() -> &'static Mesh {
+    QUAD_GEOMETRY.get_or_init(|| {
+        let vertices: [VertexPositionTexture; 6] = [
+            // Tri 1
+            VertexPositionTexture {
+                position: vec3(-0.5, -0.5, 0.0),
+                uv: vec2(0.0, 0.0),
+            },
+            VertexPositionTexture {
+                position: vec3(-0.5, 0.5, 0.0),
+                uv: vec2(0.0, 1.0),
+            },
+            VertexPositionTexture {
+                position: vec3(0.5, 0.5, 0.0),
+                uv: vec2(1.0, 1.0),
+            },
+            // Tri2
+            VertexPositionTexture {
+                position: vec3(0.5, -0.5, 0.0),
+                uv: vec2(1.0, 0.0),
+            },
+            VertexPositionTexture {
+                position: vec3(0.5, 0.5, 0.0),
+                uv: vec2(1.0, 1.0),
+            },
+            VertexPositionTexture {
+                position: vec3(-0.5, -0.5, 0.0),
+                uv: vec2(0.0, 0.0),
+            },
+        ];
+
+        mesh::create(vertices.to_vec())
+    })
+}
+
 impl Geometry for Quad {
     fn draw(&self) {
-        let mesh = QUAD_GEOMETRY.get_or_init(|| {
-            let vertices: [VertexPositionTexture; 6] = [
-                // Tri 1
-                VertexPositionTexture {
-                    position: vec3(-0.5, -0.5, 0.0),
-                    uv: vec2(0.0, 0.0),
-                },
-                VertexPositionTexture {
-                    position: vec3(-0.5, 0.5, 0.0),
-                    uv: vec2(0.0, 1.0),
-                },
-                VertexPositionTexture {
-                    position: vec3(0.5, 0.5, 0.0),
-                    uv: vec2(1.0, 1.0),
-                },
-                // Tri2
-                VertexPositionTexture {
-                    position: vec3(0.5, -0.5, 0.0),
-                    uv: vec2(1.0, 0.0),
-                },
-                VertexPositionTexture {
-                    position: vec3(0.5, 0.5, 0.0),
-                    uv: vec2(1.0, 1.0),
-                },
-                VertexPositionTexture {
-                    position: vec3(-0.5, -0.5, 0.0),
-                    uv: vec2(0.0, 0.0),
-                },
-            ];
-
-            mesh::create(vertices.to_vec())
-        });
-
-        mesh.draw();
+        geometry().draw();
+    }
+
+    fn software_vertices(&self) -> Vec<SoftwareVertex> {
+        geometry().software_vertices()
     }
 }
@@ -18,6 +18,7 @@ pub use crate::scene::Geometry;
 pub use crate::scene::Material;
 
 use crate::gl_engine::OpenGLEngine;
+use crate::software::{self, FrameBuffer, ShaderContext};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -246,6 +247,35 @@ impl SceneObject {
             self.geometry.draw();
         }
     }
+
+    ///
+    /// draw_software
+    ///
+    /// Rasterize the object with the software engine, if its material has a software shader and
+    /// belongs in the pass being drawn
+    pub fn draw_software(
+        &self,
+        frame_buffer: &mut FrameBuffer,
+        render_context: &EngineRenderContext,
+        view: &Matrix4<f32>,
+        transparent: bool,
+    ) {
+        let material = self.material.borrow();
+        let shader = match material.software_shader() {
+            Some(shader) if shader.is_transparent() == transparent => shader,
+            _ => return,
+        };
+
+        let context = ShaderContext {
+            render_context,
+            view_matrix: *view,
+            world_matrix: self.transform * self.local_transform,
+            skinning_data: &self.skinning_data,
+        };
+        let vertices = self.geometry.software_vertices();
+        software::draw_triangles(frame_buffer, shader, &context, &vertices, !transparent);
+    }
+
     pub fn set_transform(&mut self, transform: Matrix4<f32>) {
         self.transform = transform;
     }
@@ -9,6 +9,7 @@ use crate::scene::Material;
 use crate::shader;
 use crate::shader::Shader;
 use crate::shader_program::ShaderProgram;
+use crate::software::{self, ShaderContext, SoftwareShader, SoftwareVertex};
 
 use crate::texture::Texture;
 use crate::texture::TextureTrait;
@@ -17,6 +18,7 @@ use c_string::*;
 use cgmath::num_traits::ToPrimitive;
 use cgmath::prelude::*;
 use cgmath::Matrix4;
+use cgmath::Vector4;
 
 use cgmath::vec3;
 use once_cell::sync::OnceCell;
@@ -203,6 +205,10 @@ impl Material for SkinnedMaterial {
             false
         }
     }
+
+    fn software_shader(&self) -> Option<&dyn SoftwareShader> {
+        Some(self)
+    }
 }
 
 impl SkinnedMaterial {
@@ -219,3 +225,29 @@ impl SkinnedMaterial {
         })
     }
 }
+
+impl SoftwareShader for SkinnedMaterial {
+    fn is_transparent(&self) -> bool {
+        SkinnedMaterial::is_transparent(self)
+    }
+
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32> {
+        let bone_matrix = context
+            .skinning_data
+            .get(vertex.bone_indices[0] as usize)
+            .copied()
+            .unwrap_or_else(Matrix4::identity);
+        let mod_position = bone_matrix * vertex.position.extend(1.0);
+        context.render_context.projection_matrix
+            * context.view_matrix
+            * context.world_matrix
+            * mod_position
+    }
+
+    fn fragment(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Option<Vector4<f32>> {
+        let texture_color = self
+            .diffuse_texture
+            .sample(context.render_context, vertex.uv);
+        software::unlit_fragment(texture_color, self.emissivity, self.transparency)
+    }
+}
@@ -1,8 +1,10 @@
 use std::{ffi::c_void, mem::size_of};
 
-use cgmath::{Vector2, Vector3, Vector4};
+use cgmath::{vec3, Vector2, Vector3, Vector4};
 use gl::types;
 
+use crate::software::SoftwareVertex;
+
 pub struct TextVertex {
     pub position: Vector2<f32>,
     pub uv: Vector2<f32>,
@@ -27,6 +29,13 @@ impl Vertex for TextVertex {
             },
         ]
     }
+
+    fn to_software_vertex(&self) -> SoftwareVertex {
+        SoftwareVertex {
+            uv: self.uv,
+            ..SoftwareVertex::new(vec3(self.position.x, self.position.y, 0.0))
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,6 +56,10 @@ impl Vertex for VertexPosition {
             size: 3,
         }]
     }
+
+    fn to_software_vertex(&self) -> SoftwareVertex {
+        SoftwareVertex::new(self.position)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -94,6 +107,15 @@ impl Vertex for VertexPositionTextureLightmapAtlas {
             },
         ]
     }
+
+    fn to_software_vertex(&self) -> SoftwareVertex {
+        SoftwareVertex {
+            uv: self.uv,
+            lightmap_uv: self.lightmap_uv,
+            lightmap_atlas: self.lightmap_atlas,
+            ..SoftwareVertex::new(self.position)
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -128,6 +150,14 @@ impl Vertex for VertexPositionTextureSkinned {
             },
         ]
     }
+
+    fn to_software_vertex(&self) -> SoftwareVertex {
+        SoftwareVertex {
+            uv: self.uv,
+            bone_indices: self.bone_indices,
+            ..SoftwareVertex::new(self.position)
+        }
+    }
 }
 
 pub enum VertexAttributeType {
@@ -145,6 +175,7 @@ pub struct VertexAttribute {
 pub trait Vertex {
     fn get_total_size() -> isize;
     fn get_vertex_attributes() -> Vec<VertexAttribute>;
+    fn to_software_vertex(&self) -> SoftwareVertex;
 }
 
 impl Vertex for VertexPositionTexture {
@@ -166,4 +197,11 @@ impl Vertex for VertexPositionTexture {
             },
         ]
     }
+
+    fn to_software_vertex(&self) -> SoftwareVertex {
+        SoftwareVertex {
+            uv: self.uv,
+            ..SoftwareVertex::new(self.position)
+        }
+    }
 }
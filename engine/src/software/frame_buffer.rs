@@ -0,0 +1,88 @@
+use cgmath::{vec4, Vector4};
+use image::RgbaImage;
+
+///
+/// FrameBuffer
+///
+/// Color and depth for each pixel, with row 0 at the top of the screen
+pub struct FrameBuffer {
+    width: u32,
+    height: u32,
+    color: Vec<Vector4<f32>>,
+    depth: Vec<f32>,
+}
+
+impl FrameBuffer {
+    pub fn new(width: u32, height: u32) -> FrameBuffer {
+        let size = (width * height) as usize;
+        FrameBuffer {
+            width,
+            height,
+            color: vec![vec4(0.0, 0.0, 0.0, 0.0); size],
+            depth: vec![1.0; size],
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn clear(&mut self, color: Vector4<f32>) {
+        self.color.fill(color);
+        self.depth.fill(1.0);
+    }
+
+    pub fn color(&self, x: u32, y: u32) -> Vector4<f32> {
+        self.color[self.index(x, y)]
+    }
+
+    pub fn depth(&self, x: u32, y: u32) -> f32 {
+        self.depth[self.index(x, y)]
+    }
+
+    pub fn set_depth(&mut self, x: u32, y: u32, depth: f32) {
+        let idx = self.index(x, y);
+        self.depth[idx] = depth;
+    }
+
+    ///
+    /// blend
+    ///
+    /// Blend a color over the pixel, with the same function the GL engine uses -
+    /// (SRC_ALPHA, ONE_MINUS_SRC_ALPHA)
+    pub fn blend(&mut self, x: u32, y: u32, color: Vector4<f32>) {
+        let idx = self.index(x, y);
+        let src = clamp(color);
+        let dst = self.color[idx];
+        self.color[idx] = src * src.w + dst * (1.0 - src.w);
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| {
+            let color = clamp(self.color(x, y)) * 255.0;
+            image::Rgba([
+                color.x.round() as u8,
+                color.y.round() as u8,
+                color.z.round() as u8,
+                color.w.round() as u8,
+            ])
+        })
+    }
+
+    fn index(&self, x: u32, y: u32) -> usize {
+        (y * self.width + x) as usize
+    }
+}
+
+fn clamp(color: Vector4<f32>) -> Vector4<f32> {
+    vec4(
+        color.x.clamp(0.0, 1.0),
+        color.y.clamp(0.0, 1.0),
+        color.z.clamp(0.0, 1.0),
+        color.w.clamp(0.0, 1.0),
+    )
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use image::RgbaImage;
+
+// Environment variable that re-records golden images, instead of comparing against them
+const UPDATE_GOLDEN_IMAGES: &str = "UPDATE_GOLDEN_IMAGES";
+
+// Per-channel difference allowed before a pixel counts as different - absorbs float rounding
+// differences between platforms
+const GOLDEN_TOLERANCE: u8 = 2;
+
+///
+/// ImageDifference
+///
+/// How much two images differ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageDifference {
+    pub differing_pixels: usize,
+    pub max_channel_difference: u8,
+}
+
+impl ImageDifference {
+    pub fn is_match(&self) -> bool {
+        self.differing_pixels == 0
+    }
+}
+
+///
+/// compare_images
+///
+/// Count the pixels where any channel differs by more than the tolerance. Images of different
+/// sizes differ at every pixel.
+pub fn compare_images(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> ImageDifference {
+    if expected.dimensions() != actual.dimensions() {
+        return ImageDifference {
+            differing_pixels: (expected.width() * expected.height())
+                .max(actual.width() * actual.height()) as usize,
+            max_channel_difference: u8::MAX,
+        };
+    }
+
+    let mut ret = ImageDifference {
+        differing_pixels: 0,
+        max_channel_difference: 0,
+    };
+    for (expected_pixel, actual_pixel) in expected.pixels().zip(actual.pixels()) {
+        let difference = expected_pixel
+            .0
+            .iter()
+            .zip(actual_pixel.0.iter())
+            .map(|(e, a)| e.abs_diff(*a))
+            .max()
+            .unwrap_or(0);
+
+        ret.max_channel_difference = ret.max_channel_difference.max(difference);
+        if difference > tolerance {
+            ret.differing_pixels += 1;
+        }
+    }
+    ret
+}
+
+///
+/// assert_matches_golden
+///
+/// Compare a screenshot against the golden image at the path. The golden image is only recorded
+/// when UPDATE_GOLDEN_IMAGES is set - a missing golden image fails, so a test can't pass by
+/// recording whatever it rendered. On a mismatch, the screenshot is saved next to the golden
+/// image as '<name>.actual.png' for inspection.
+pub fn assert_matches_golden(actual: &RgbaImage, golden_path: impl AsRef<Path>) {
+    let golden_path = golden_path.as_ref();
+    if std::env::var_os(UPDATE_GOLDEN_IMAGES).is_some() {
+        if let Some(parent) = golden_path.parent() {
+            std::fs::create_dir_all(parent).expect("unable to create golden image folder");
+        }
+        actual
+            .save(golden_path)
+            .expect("unable to save golden image");
+        return;
+    }
+
+    if !golden_path.exists() {
+        let actual_path = golden_path.with_extension("actual.png");
+        actual
+            .save(&actual_path)
+            .expect("unable to save screenshot");
+        panic!(
+            "golden image {golden_path:?} is missing. Saved the screenshot to {actual_path:?}, \
+             run with {UPDATE_GOLDEN_IMAGES}=1 to record it."
+        );
+    }
+
+    let expected = image::open(golden_path)
+        .unwrap_or_else(|err| panic!("unable to open golden image {golden_path:?}: {err}"))
+        .to_rgba8();
+
+    let difference = compare_images(&expected, actual, GOLDEN_TOLERANCE);
+    if !difference.is_match() {
+        let actual_path = golden_path.with_extension("actual.png");
+        actual
+            .save(&actual_path)
+            .expect("unable to save screenshot");
+        panic!(
+            "screenshot does not match {golden_path:?} - {} pixels differ, by up to {}. \
+             Saved the screenshot to {actual_path:?}, run with {UPDATE_GOLDEN_IMAGES}=1 to accept it.",
+            difference.differing_pixels, difference.max_channel_difference
+        );
+    }
+}
@@ -0,0 +1,17 @@
+///
+/// software
+///
+/// A CPU rasterizer, used by the software engine to draw scenes without a GPU - so screenshot
+/// tests of levels, GUIs and models can run headless.
+///
+mod frame_buffer;
+mod golden;
+mod rasterizer;
+mod sampler;
+mod shader;
+
+pub use frame_buffer::*;
+pub use golden::*;
+pub use rasterizer::*;
+pub use sampler::*;
+pub use shader::*;
@@ -0,0 +1,191 @@
+use cgmath::Vector4;
+
+use super::{FrameBuffer, ShaderContext, SoftwareShader, SoftwareVertex};
+
+#[derive(Clone, Copy)]
+struct ClipVertex {
+    position: Vector4<f32>,
+    vertex: SoftwareVertex,
+}
+
+#[derive(Clone, Copy)]
+struct ScreenVertex {
+    x: f32,
+    y: f32,
+    depth: f32,
+    inv_w: f32,
+}
+
+///
+/// draw_triangles
+///
+/// Rasterize a triangle list with the shader, depth testing against the frame buffer and
+/// blending over it. Both sides of each triangle are drawn - the GL engine doesn't cull faces.
+pub fn draw_triangles(
+    frame_buffer: &mut FrameBuffer,
+    shader: &dyn SoftwareShader,
+    context: &ShaderContext,
+    vertices: &[SoftwareVertex],
+    write_depth: bool,
+) {
+    for triangle in vertices.chunks_exact(3) {
+        let clip_vertices: Vec<ClipVertex> = triangle
+            .iter()
+            .map(|vertex| ClipVertex {
+                position: shader.vertex(context, vertex),
+                vertex: *vertex,
+            })
+            .collect();
+
+        let polygon = clip_near(&clip_vertices);
+        if polygon.len() < 3 {
+            continue;
+        }
+
+        for pair in polygon[1..].windows(2) {
+            rasterize(
+                frame_buffer,
+                shader,
+                context,
+                [polygon[0], pair[0], pair[1]],
+                write_depth,
+            );
+        }
+    }
+}
+
+// Clip a polygon against the near plane (z >= -w in clip space), so vertices behind the camera
+// aren't projected through it
+fn clip_near(polygon: &[ClipVertex]) -> Vec<ClipVertex> {
+    let distance = |v: &ClipVertex| v.position.z + v.position.w;
+
+    let mut ret = Vec::with_capacity(polygon.len() + 1);
+    for (idx, current) in polygon.iter().enumerate() {
+        let next = &polygon[(idx + 1) % polygon.len()];
+        let (current_distance, next_distance) = (distance(current), distance(next));
+
+        if current_distance >= 0.0 {
+            ret.push(*current);
+        }
+
+        if (current_distance >= 0.0) != (next_distance >= 0.0) {
+            let t = current_distance / (current_distance - next_distance);
+            ret.push(ClipVertex {
+                position: current.position + (next.position - current.position) * t,
+                vertex: SoftwareVertex::blend(&[current.vertex, next.vertex], &[1.0 - t, t]),
+            });
+        }
+    }
+    ret
+}
+
+fn to_screen(position: Vector4<f32>, width: f32, height: f32) -> ScreenVertex {
+    let ndc = position.truncate() / position.w;
+    ScreenVertex {
+        x: (ndc.x * 0.5 + 0.5) * width,
+        y: (1.0 - (ndc.y * 0.5 + 0.5)) * height,
+        depth: ndc.z * 0.5 + 0.5,
+        inv_w: 1.0 / position.w,
+    }
+}
+
+fn edge(a: &ScreenVertex, b: &ScreenVertex, x: f32, y: f32) -> f32 {
+    (b.x - a.x) * (y - a.y) - (b.y - a.y) * (x - a.x)
+}
+
+// Pixels exactly on an edge shared by two triangles belong to only one of them, so they aren't
+// blended twice. The edge is traversed in opposite directions by each triangle.
+fn covers(weight: f32, a: &ScreenVertex, b: &ScreenVertex) -> bool {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    weight > 0.0 || (weight == 0.0 && (dy < 0.0 || (dy == 0.0 && dx > 0.0)))
+}
+
+fn rasterize(
+    frame_buffer: &mut FrameBuffer,
+    shader: &dyn SoftwareShader,
+    context: &ShaderContext,
+    triangle: [ClipVertex; 3],
+    write_depth: bool,
+) {
+    if triangle.iter().any(|v| v.position.w <= f32::EPSILON) {
+        return;
+    }
+
+    let width = frame_buffer.width() as f32;
+    let height = frame_buffer.height() as f32;
+    let mut screen = triangle.map(|v| to_screen(v.position, width, height));
+    let mut vertices = triangle.map(|v| v.vertex);
+
+    let mut area = edge(&screen[0], &screen[1], screen[2].x, screen[2].y);
+    if area.abs() <= f32::EPSILON || !area.is_finite() {
+        return;
+    }
+
+    // Wind every triangle the same way, so the edge tests have the same sign for both sides
+    if area < 0.0 {
+        screen.swap(1, 2);
+        vertices.swap(1, 2);
+        area = -area;
+    }
+
+    let min_x = screen.iter().map(|s| s.x).fold(f32::MAX, f32::min).max(0.0);
+    let max_x = screen
+        .iter()
+        .map(|s| s.x)
+        .fold(f32::MIN, f32::max)
+        .min(width);
+    let min_y = screen.iter().map(|s| s.y).fold(f32::MAX, f32::min).max(0.0);
+    let max_y = screen
+        .iter()
+        .map(|s| s.y)
+        .fold(f32::MIN, f32::max)
+        .min(height);
+    if min_x >= max_x || min_y >= max_y {
+        return;
+    }
+
+    for y in (min_y.floor() as u32)..(max_y.ceil() as u32) {
+        for x in (min_x.floor() as u32)..(max_x.ceil() as u32) {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let w0 = edge(&screen[1], &screen[2], px, py);
+            let w1 = edge(&screen[2], &screen[0], px, py);
+            let w2 = edge(&screen[0], &screen[1], px, py);
+            if !covers(w0, &screen[1], &screen[2])
+                || !covers(w1, &screen[2], &screen[0])
+                || !covers(w2, &screen[0], &screen[1])
+            {
+                continue;
+            }
+
+            let weights = [w0 / area, w1 / area, w2 / area];
+
+            // Depth is linear in screen space, like gl_FragCoord.z
+            let depth = weights[0] * screen[0].depth
+                + weights[1] * screen[1].depth
+                + weights[2] * screen[2].depth;
+            if !(0.0..=1.0).contains(&depth) || depth >= frame_buffer.depth(x, y) {
+                continue;
+            }
+
+            // ...but the vertex attributes need perspective correction
+            let mut corrected = [
+                weights[0] * screen[0].inv_w,
+                weights[1] * screen[1].inv_w,
+                weights[2] * screen[2].inv_w,
+            ];
+            let total: f32 = corrected.iter().sum();
+            if total <= f32::EPSILON {
+                continue;
+            }
+            corrected.iter_mut().for_each(|w| *w /= total);
+
+            let vertex = SoftwareVertex::blend(&vertices, &corrected);
+            if let Some(color) = shader.fragment(context, &vertex) {
+                frame_buffer.blend(x, y, color);
+                if write_depth {
+                    frame_buffer.set_depth(x, y, depth);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use cgmath::{Vector2, Vector4};
+
+use crate::texture_format::{PixelFormat, RawTextureData};
+
+///
+/// MISSING_TEXTURE_COLOR
+///
+/// Sampled from textures without pixels on the CPU - ones that have already been uploaded to
+/// OpenGL
+pub const MISSING_TEXTURE_COLOR: Vector4<f32> = Vector4 {
+    x: 1.0,
+    y: 0.0,
+    z: 1.0,
+    w: 1.0,
+};
+
+///
+/// sample
+///
+/// Bilinear filtered color at the given uv, like GL_LINEAR. Coordinates outside 0..1 repeat when
+/// wrapping, otherwise they are clamped to the edge.
+pub fn sample(texture: &RawTextureData, wrap: bool, uv: Vector2<f32>) -> Vector4<f32> {
+    if texture.width == 0 || texture.height == 0 {
+        return MISSING_TEXTURE_COLOR;
+    }
+
+    // Texel centers are at half-pixel offsets
+    let x = uv.x * texture.width as f32 - 0.5;
+    let y = uv.y * texture.height as f32 - 0.5;
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let fx = x - x0;
+    let fy = y - y0;
+
+    let texel = |dx: i32, dy: i32| {
+        let tx = wrap_coordinate(x0 as i32 + dx, texture.width, wrap);
+        let ty = wrap_coordinate(y0 as i32 + dy, texture.height, wrap);
+        read_pixel(texture, tx, ty)
+    };
+
+    let top = texel(0, 0) * (1.0 - fx) + texel(1, 0) * fx;
+    let bottom = texel(0, 1) * (1.0 - fx) + texel(1, 1) * fx;
+    top * (1.0 - fy) + bottom * fy
+}
+
+///
+/// copy_region
+///
+/// Replace the pixels of a texture, starting at (x, y), with the source pixels - converting
+/// between formats like glTexSubImage2D does
+pub fn copy_region(target: &mut RawTextureData, x: u32, y: u32, source: &RawTextureData) {
+    let target_size = pixel_size(target.format);
+    for sy in 0..source.height {
+        for sx in 0..source.width {
+            let (tx, ty) = (x + sx, y + sy);
+            if tx >= target.width || ty >= target.height {
+                continue;
+            }
+
+            let rgba = read_pixel_bytes(source, sx, sy);
+            let idx = ((ty * target.width + tx) * target_size) as usize;
+            target.bytes[idx..idx + target_size as usize]
+                .copy_from_slice(&rgba[..target_size as usize]);
+        }
+    }
+}
+
+fn wrap_coordinate(coordinate: i32, size: u32, wrap: bool) -> u32 {
+    if wrap {
+        coordinate.rem_euclid(size as i32) as u32
+    } else {
+        coordinate.clamp(0, size as i32 - 1) as u32
+    }
+}
+
+fn pixel_size(format: PixelFormat) -> u32 {
+    match format {
+        PixelFormat::RGB => 3,
+        PixelFormat::RGBA => 4,
+    }
+}
+
+fn read_pixel_bytes(texture: &RawTextureData, x: u32, y: u32) -> [u8; 4] {
+    let size = pixel_size(texture.format);
+    let idx = ((y * texture.width + x) * size) as usize;
+    let bytes = &texture.bytes[idx..idx + size as usize];
+    match texture.format {
+        PixelFormat::RGB => [bytes[0], bytes[1], bytes[2], 255],
+        PixelFormat::RGBA => [bytes[0], bytes[1], bytes[2], bytes[3]],
+    }
+}
+
+fn read_pixel(texture: &RawTextureData, x: u32, y: u32) -> Vector4<f32> {
+    let [r, g, b, a] = read_pixel_bytes(texture, x, y);
+    Vector4::new(r as f32, g as f32, b as f32, a as f32) / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{vec2, InnerSpace};
+
+    fn checker() -> RawTextureData {
+        // Black and white, 2x1
+        RawTextureData {
+            bytes: vec![0, 0, 0, 255, 255, 255, 255, 255],
+            width: 2,
+            height: 1,
+            format: PixelFormat::RGBA,
+        }
+    }
+
+    #[test]
+    fn test_sample_filters_between_texels() {
+        // Given
+        let texture = checker();
+
+        // When
+        let center = sample(&texture, false, vec2(0.5, 0.5));
+        let left = sample(&texture, false, vec2(0.25, 0.5));
+
+        // Then
+        assert!((center - Vector4::new(0.5, 0.5, 0.5, 1.0)).magnitude() < 0.001);
+        assert!((left - Vector4::new(0.0, 0.0, 0.0, 1.0)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn test_copy_region_converts_rgb_to_rgba() {
+        // Given
+        let mut texture = checker();
+        let region = RawTextureData {
+            bytes: vec![10, 20, 30],
+            width: 1,
+            height: 1,
+            format: PixelFormat::RGB,
+        };
+
+        // When
+        copy_region(&mut texture, 1, 0, &region);
+
+        // Then
+        assert_eq!(texture.bytes, vec![0, 0, 0, 255, 10, 20, 30, 255]);
+    }
+}
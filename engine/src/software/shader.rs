@@ -0,0 +1,116 @@
+use cgmath::{vec2, vec4, Matrix4, Vector2, Vector3, Vector4, Zero};
+
+use crate::engine::EngineRenderContext;
+
+///
+/// SoftwareVertex
+///
+/// Every vertex attribute used by the materials - the vertex types are converted to this, so
+/// a single rasterizer can draw all of them.
+#[derive(Clone, Copy, Debug)]
+pub struct SoftwareVertex {
+    pub position: Vector3<f32>,
+    pub uv: Vector2<f32>,
+    pub lightmap_uv: Vector2<f32>,
+    pub lightmap_atlas: Vector4<f32>,
+    pub bone_indices: [u32; 4],
+}
+
+impl SoftwareVertex {
+    pub fn new(position: Vector3<f32>) -> SoftwareVertex {
+        SoftwareVertex {
+            position,
+            uv: vec2(0.0, 0.0),
+            lightmap_uv: vec2(0.0, 0.0),
+            lightmap_atlas: vec4(0.0, 0.0, 0.0, 0.0),
+            bone_indices: [0; 4],
+        }
+    }
+
+    ///
+    /// blend
+    ///
+    /// Weighted sum of the attributes of several vertices. Bone indices can't be blended, so they
+    /// come from the first vertex.
+    pub fn blend(vertices: &[SoftwareVertex], weights: &[f32]) -> SoftwareVertex {
+        let mut ret = SoftwareVertex {
+            bone_indices: vertices[0].bone_indices,
+            ..SoftwareVertex::new(Vector3::zero())
+        };
+
+        for (vertex, weight) in vertices.iter().zip(weights) {
+            ret.position += vertex.position * *weight;
+            ret.uv += vertex.uv * *weight;
+            ret.lightmap_uv += vertex.lightmap_uv * *weight;
+            ret.lightmap_atlas += vertex.lightmap_atlas * *weight;
+        }
+
+        ret
+    }
+}
+
+///
+/// ShaderContext
+///
+/// The uniforms available to a software shader
+pub struct ShaderContext<'a> {
+    pub render_context: &'a EngineRenderContext,
+    pub view_matrix: Matrix4<f32>,
+    pub world_matrix: Matrix4<f32>,
+    pub skinning_data: &'a [Matrix4<f32>],
+}
+
+///
+/// SoftwareShader
+///
+/// The CPU equivalent of a material's vertex and fragment shaders
+pub trait SoftwareShader {
+    ///
+    /// is_transparent
+    ///
+    /// Whether the material is drawn in the transparent pass, which doesn't write depth
+    fn is_transparent(&self) -> bool {
+        false
+    }
+
+    ///
+    /// vertex
+    ///
+    /// Clip-space position of the vertex
+    fn vertex(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Vector4<f32>;
+
+    ///
+    /// fragment
+    ///
+    /// Color of a pixel, given the interpolated vertex - or None to discard it
+    fn fragment(&self, context: &ShaderContext, vertex: &SoftwareVertex) -> Option<Vector4<f32>>;
+}
+
+///
+/// project
+///
+/// projection * view * world * position - the vertex shader shared by most materials
+pub fn project(context: &ShaderContext, position: Vector3<f32>) -> Vector4<f32> {
+    context.render_context.projection_matrix
+        * context.view_matrix
+        * context.world_matrix
+        * position.extend(1.0)
+}
+
+///
+/// unlit_fragment
+///
+/// The fragment shader shared by the basic, billboard and skinned materials
+pub fn unlit_fragment(
+    texture_color: Vector4<f32>,
+    emissivity: f32,
+    transparency: f32,
+) -> Option<Vector4<f32>> {
+    if texture_color.w < 0.1 {
+        return None;
+    }
+
+    let rgb = texture_color.truncate();
+    let rgb = rgb * 0.5 + rgb * emissivity;
+    Some(rgb.extend(texture_color.w * (1.0 - transparency)))
+}
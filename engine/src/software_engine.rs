@@ -0,0 +1,282 @@
+///
+/// software_engine.rs
+///
+/// An implementation of the engine that draws scenes on the CPU, into an image - so screenshot
+/// tests run without a GPU
+///
+use std::cell::{Ref, RefCell};
+
+use cgmath::vec4;
+use image::RgbaImage;
+
+use crate::engine::{Engine, EngineRenderContext};
+use crate::file_system::Storage;
+use crate::scene::Scene;
+use crate::software::FrameBuffer;
+use crate::util;
+
+pub struct SoftwareEngine {
+    storage: Box<dyn Storage>,
+    frame_buffer: RefCell<FrameBuffer>,
+}
+
+impl SoftwareEngine {
+    pub fn new(width: u32, height: u32, storage: Box<dyn Storage>) -> SoftwareEngine {
+        SoftwareEngine {
+            storage,
+            frame_buffer: RefCell::new(FrameBuffer::new(width, height)),
+        }
+    }
+
+    pub fn frame_buffer(&self) -> Ref<FrameBuffer> {
+        self.frame_buffer.borrow()
+    }
+
+    ///
+    /// screenshot
+    ///
+    /// The last rendered frame
+    pub fn screenshot(&self) -> RgbaImage {
+        self.frame_buffer.borrow().to_image()
+    }
+}
+
+impl Engine for SoftwareEngine {
+    fn get_storage(&self) -> &Box<dyn Storage> {
+        &self.storage
+    }
+
+    fn render(&self, render_context: &EngineRenderContext, scene: &Scene) {
+        let mut frame_buffer = self.frame_buffer.borrow_mut();
+        frame_buffer.clear(vec4(0.0, 0.0, 0.0, 0.0));
+
+        let view = util::compute_view_matrix_from_render_context(render_context);
+
+        // Same passes as the GL engine - opaque, then transparent without writing depth
+        scene
+            .iter()
+            .for_each(|s| s.draw_software(&mut frame_buffer, render_context, &view, false));
+        scene
+            .iter()
+            .for_each(|s| s.draw_software(&mut frame_buffer, render_context, &view, true));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use cgmath::{vec2, vec3, Deg, Matrix4, Quaternion, SquareMatrix, Vector3};
+
+    use super::*;
+    use crate::materials::LightmapMaterial;
+    use crate::scene::{
+        basic_material, color_material, mesh, quad, SceneObject, SkinnedMaterial,
+        VertexPositionTextureLightmapAtlas, VertexPositionTextureSkinned,
+    };
+    use crate::texture::{self, Texture, TextureTrait};
+    use crate::texture_format::{PixelFormat, RawTextureData};
+
+    const SIZE: u32 = 32;
+
+    fn engine() -> SoftwareEngine {
+        crate::software(SIZE, SIZE)
+    }
+
+    // Camera at the origin, looking down -z
+    fn render_context() -> EngineRenderContext {
+        EngineRenderContext {
+            time: 0.0,
+            camera_offset: vec3(0.0, 0.0, 0.0),
+            camera_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            head_offset: vec3(0.0, 0.0, 0.0),
+            head_rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            projection_matrix: cgmath::perspective(Deg(90.0), 1.0, 0.1, 100.0),
+            screen_size: vec2(SIZE as f32, SIZE as f32),
+        }
+    }
+
+    fn solid_texture(rgba: [u8; 4]) -> Rc<Texture> {
+        Rc::new(texture::init_from_memory(RawTextureData {
+            bytes: rgba.to_vec(),
+            width: 1,
+            height: 1,
+            format: PixelFormat::RGBA,
+        }))
+    }
+
+    fn color_quad(color: Vector3<f32>, transform: Matrix4<f32>) -> SceneObject {
+        let mut obj = SceneObject::new(color_material::create(color), Box::new(quad::create()));
+        obj.set_transform(transform);
+        obj
+    }
+
+    fn pixel(engine: &SoftwareEngine, x: u32, y: u32) -> [u8; 4] {
+        engine.screenshot().get_pixel(x, y).0
+    }
+
+    #[test]
+    fn test_renders_nearest_surface() {
+        // Given - a red quad behind a smaller green one, added first
+        let engine = engine();
+        let scene = vec![
+            color_quad(
+                vec3(0.0, 1.0, 0.0),
+                Matrix4::from_translation(vec3(0.0, 0.0, -1.0)),
+            ),
+            color_quad(
+                vec3(1.0, 0.0, 0.0),
+                Matrix4::from_translation(vec3(0.0, 0.0, -2.0)) * Matrix4::from_scale(4.0),
+            ),
+        ];
+
+        // When
+        engine.render(&render_context(), &scene);
+
+        // Then
+        assert_eq!(pixel(&engine, SIZE / 2, SIZE / 2), [0, 255, 0, 255]);
+        assert_eq!(pixel(&engine, 2, 2), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_blends_transparent_over_opaque() {
+        // Given - a half transparent, fully emissive white quad in front of a black one
+        let engine = engine();
+        let white: Rc<dyn TextureTrait> = solid_texture([255, 255, 255, 255]);
+        let mut overlay = SceneObject::new(
+            basic_material::create(white, 1.0, 0.5),
+            Box::new(quad::create()),
+        );
+        overlay.set_transform(Matrix4::from_translation(vec3(0.0, 0.0, -1.0)));
+        let scene = vec![
+            overlay,
+            color_quad(
+                vec3(0.0, 0.0, 0.0),
+                Matrix4::from_translation(vec3(0.0, 0.0, -2.0)) * Matrix4::from_scale(4.0),
+            ),
+        ];
+
+        // When
+        engine.render(&render_context(), &scene);
+
+        // Then
+        let [r, g, b, _] = pixel(&engine, SIZE / 2, SIZE / 2);
+        assert!((126..=129).contains(&r), "expected half gray, got {r}");
+        assert!(r == g && g == b);
+        assert_eq!(pixel(&engine, 2, 2), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_lightmap_modulates_diffuse() {
+        // Given - a white surface, lit by a dark red lightmap
+        let engine = engine();
+        let lightmap = solid_texture([128, 0, 0, 255]);
+        let diffuse: Rc<dyn TextureTrait> = solid_texture([255, 255, 255, 255]);
+        let vertex = |x: f32, y: f32| VertexPositionTextureLightmapAtlas {
+            position: vec3(x, y, -1.0),
+            uv: vec2(0.0, 0.0),
+            lightmap_uv: vec2(0.5, 0.5),
+            lightmap_atlas: cgmath::vec4(0.0, 0.0, 1.0, 1.0),
+        };
+        let vertices = vec![
+            vertex(-2.0, -2.0),
+            vertex(2.0, -2.0),
+            vertex(2.0, 2.0),
+            vertex(-2.0, -2.0),
+            vertex(2.0, 2.0),
+            vertex(-2.0, 2.0),
+        ];
+        let scene = vec![SceneObject::new(
+            LightmapMaterial::create(lightmap, diffuse),
+            Box::new(mesh::create(vertices)),
+        )];
+
+        // When
+        engine.render(&render_context(), &scene);
+
+        // Then
+        assert_eq!(pixel(&engine, SIZE / 2, SIZE / 2), [128, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_skinned_vertices_follow_bones() {
+        // Given - a quad on the left, skinned to a bone that moves it to the right
+        let engine = engine();
+        let white: Rc<dyn TextureTrait> = solid_texture([255, 255, 255, 255]);
+        let vertex = |x: f32, y: f32| VertexPositionTextureSkinned {
+            position: vec3(x, y, -1.0),
+            uv: vec2(0.0, 0.0),
+            bone_indices: [1, 0, 0, 0],
+        };
+        let vertices = vec![
+            vertex(-0.9, -0.2),
+            vertex(-0.5, -0.2),
+            vertex(-0.5, 0.2),
+            vertex(-0.9, -0.2),
+            vertex(-0.5, 0.2),
+            vertex(-0.9, 0.2),
+        ];
+        let mut obj = SceneObject::new(
+            SkinnedMaterial::create(white, 1.0, 0.0),
+            Box::new(mesh::create(vertices)),
+        );
+        let mut skinning_data = [Matrix4::identity(); 40];
+        skinning_data[1] = Matrix4::from_translation(vec3(1.4, 0.0, 0.0));
+        obj.set_skinning_data(skinning_data);
+
+        // When
+        let scene = vec![obj];
+        engine.render(&render_context(), &scene);
+
+        // Then
+        let y = SIZE / 2;
+        assert_eq!(pixel(&engine, SIZE * 3 / 4, y), [255, 255, 255, 255]);
+        assert_eq!(pixel(&engine, 5, y), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_screen_space_quad_is_drawn_over_scene() {
+        // Given - a blue GUI quad in the top left quarter of the screen
+        let engine = engine();
+        let blue: Rc<dyn TextureTrait> = solid_texture([0, 0, 255, 255]);
+        let half = SIZE as f32 / 2.0;
+        let scene = vec![
+            color_quad(
+                vec3(1.0, 0.0, 0.0),
+                Matrix4::from_translation(vec3(0.0, 0.0, -1.0)) * Matrix4::from_scale(4.0),
+            ),
+            SceneObject::screen_space_quad(blue, vec2(0.0, 0.0), vec2(half, half)),
+        ];
+
+        // When
+        engine.render(&render_context(), &scene);
+
+        // Then
+        assert_eq!(pixel(&engine, 4, 4), [0, 0, 255, 255]);
+        assert_eq!(pixel(&engine, SIZE - 4, SIZE - 4), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_quadrants_match_golden() {
+        // Given - a quad filling each quarter of the screen, in a different color
+        let engine = engine();
+        let quadrant = |color: Vector3<f32>, x: f32, y: f32| {
+            color_quad(color, Matrix4::from_translation(vec3(x, y, -1.0)))
+        };
+        let scene = vec![
+            quadrant(vec3(1.0, 0.0, 0.0), -0.5, 0.5),
+            quadrant(vec3(0.0, 1.0, 0.0), 0.5, 0.5),
+            quadrant(vec3(0.0, 0.0, 1.0), -0.5, -0.5),
+            quadrant(vec3(1.0, 1.0, 1.0), 0.5, -0.5),
+        ];
+
+        // When
+        engine.render(&render_context(), &scene);
+
+        // Then
+        crate::software::assert_matches_golden(
+            &engine.screenshot(),
+            concat!(env!("CARGO_MANIFEST_DIR"), "/golden/quadrants.png"),
+        );
+    }
+}
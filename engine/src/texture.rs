@@ -2,16 +2,25 @@ use crate::texture_format;
 use crate::texture_format::RawTextureData;
 use crate::texture_format::TextureFormat;
 use crate::EngineRenderContext;
+use cgmath::{Vector2, Vector4};
 use gl::types;
 
+use std::cell::{Cell, RefCell};
 use std::os::raw::c_void;
 use std::rc::Rc;
 use std::time::Duration;
 
+///
+/// Texture
+///
+/// Pixel data is kept on the CPU until the texture is first bound, so textures can be created
+/// without a GL context - and sampled by the software engine, which never binds them.
 pub struct Texture {
-    gl_id: types::GLuint,
+    gl_id: Cell<types::GLuint>,
     width: u32,
     height: u32,
+    wrap: bool,
+    pixels: RefCell<Option<RawTextureData>>,
 }
 
 // Will this cause problems for multi-threading??
@@ -21,6 +30,12 @@ unsafe impl Sync for Texture {}
 pub trait TextureTrait {
     fn bind0(&self, render_context: &EngineRenderContext) -> ();
     fn bind1(&self, render_context: &EngineRenderContext) -> ();
+
+    ///
+    /// sample
+    ///
+    /// Filtered color at the given uv, for the software engine
+    fn sample(&self, render_context: &EngineRenderContext, uv: Vector2<f32>) -> Vector4<f32>;
 }
 
 impl TextureTrait for Texture {
@@ -30,6 +45,13 @@ impl TextureTrait for Texture {
     fn bind1(&self, render_context: &EngineRenderContext) -> () {
         bind1(self);
     }
+    fn sample(&self, _render_context: &EngineRenderContext, uv: Vector2<f32>) -> Vector4<f32> {
+        match self.pixels.borrow().as_ref() {
+            Some(pixels) => crate::software::sample(pixels, self.wrap, uv),
+            // Already uploaded to OpenGL, so the pixels are gone
+            None => crate::software::MISSING_TEXTURE_COLOR,
+        }
+    }
 }
 
 pub struct AnimatedTexture {
@@ -57,6 +79,11 @@ impl TextureTrait for AnimatedTexture {
         let frame = frame % self.textures.len();
         bind1(&self.textures[frame]);
     }
+    fn sample(&self, render_context: &EngineRenderContext, uv: Vector2<f32>) -> Vector4<f32> {
+        let frame = (render_context.time / self.time_per_frame) as usize;
+        let frame = frame % self.textures.len();
+        self.textures[frame].sample(render_context, uv)
+    }
 }
 
 impl Texture {
@@ -66,27 +93,44 @@ impl Texture {
     pub fn height(&self) -> u32 {
         self.height
     }
+
+    fn gl_id(&self) -> types::GLuint {
+        if self.gl_id.get() == 0 {
+            let pixels = self
+                .pixels
+                .borrow_mut()
+                .take()
+                .expect("texture has no pixels to upload");
+            self.gl_id.set(upload(&pixels, self.wrap));
+        }
+        self.gl_id.get()
+    }
 }
 
 impl Drop for Texture {
     fn drop(&mut self) {
-        unsafe {
-            gl::DeleteTextures(1, &self.gl_id);
+        let gl_id = self.gl_id.get();
+        if gl_id != 0 {
+            unsafe {
+                gl::DeleteTextures(1, &gl_id);
+            }
         }
     }
 }
 
 pub fn bind0(texture: &Texture) {
+    let gl_id = texture.gl_id();
     unsafe {
         gl::ActiveTexture(gl::TEXTURE0);
-        gl::BindTexture(gl::TEXTURE_2D, texture.gl_id);
+        gl::BindTexture(gl::TEXTURE_2D, gl_id);
     }
 }
 
 pub fn bind1(texture: &Texture) {
+    let gl_id = texture.gl_id();
     unsafe {
         gl::ActiveTexture(gl::TEXTURE1);
-        gl::BindTexture(gl::TEXTURE_2D, texture.gl_id);
+        gl::BindTexture(gl::TEXTURE_2D, gl_id);
     }
 }
 
@@ -110,17 +154,35 @@ pub fn init_from_memory(raw_texture_data: RawTextureData) -> Texture {
 }
 
 pub fn init_from_memory2(raw_texture_data: RawTextureData, options: &TextureOptions) -> Texture {
+    let pixel_size_in_bytes = match raw_texture_data.format {
+        texture_format::PixelFormat::RGB => 3,
+        texture_format::PixelFormat::RGBA => 4,
+    };
+
+    assert!(
+        raw_texture_data.bytes.len()
+            == (raw_texture_data.width * raw_texture_data.height * pixel_size_in_bytes) as usize,
+        "Texture data size does not match width and height - width: {} height: {} pixel_size_in_bytes: {} actual_bytes: {}",
+        raw_texture_data.width, raw_texture_data.height, pixel_size_in_bytes, raw_texture_data.bytes.len()
+    );
+
+    Texture {
+        gl_id: Cell::new(0),
+        width: raw_texture_data.width,
+        height: raw_texture_data.height,
+        wrap: options.wrap,
+        pixels: RefCell::new(Some(raw_texture_data)),
+    }
+}
+
+fn upload(raw_texture_data: &RawTextureData, wrap: bool) -> types::GLuint {
     let mut texture = 0;
     unsafe {
         gl::GenTextures(1, &mut texture);
         gl::BindTexture(gl::TEXTURE_2D, texture); // all upcoming GL_TEXTURE_2D operations now have effect on this texture object
                                                   // set the texture wrapping parameters
 
-        let wrap = if options.wrap {
-            gl::REPEAT
-        } else {
-            gl::CLAMP_TO_EDGE
-        };
+        let wrap = if wrap { gl::REPEAT } else { gl::CLAMP_TO_EDGE };
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, wrap as i32); // set texture wrapping to gl::REPEAT (default wrapping method)
         gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, wrap as i32);
         // gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32); // set texture wrapping to gl::REPEAT (default wrapping method)
@@ -136,18 +198,6 @@ pub fn init_from_memory2(raw_texture_data: RawTextureData, options: &TextureOpti
         texture_format::PixelFormat::RGBA => gl::RGBA,
     };
 
-    let pixel_size_in_bytes = match raw_texture_data.format {
-        texture_format::PixelFormat::RGB => 3,
-        texture_format::PixelFormat::RGBA => 4,
-    };
-
-    assert!(
-        raw_texture_data.bytes.len()
-            == (raw_texture_data.width * raw_texture_data.height * pixel_size_in_bytes) as usize,
-        "Texture data size does not match width and height - width: {} height: {} pixel_size_in_bytes: {} actual_bytes: {}",
-        raw_texture_data.width, raw_texture_data.height, pixel_size_in_bytes, raw_texture_data.bytes.len()
-    );
-
     unsafe {
         gl::TexImage2D(
             gl::TEXTURE_2D,
@@ -163,13 +213,49 @@ pub fn init_from_memory2(raw_texture_data: RawTextureData, options: &TextureOpti
         //gl::GenerateMipmap(gl::TEXTURE_2D);
     }
 
-    Texture {
-        gl_id: texture,
-        width: raw_texture_data.width,
-        height: raw_texture_data.height,
+    texture
+}
+
+///
+/// update_region
+///
+/// Replace a region of an existing texture, starting at pixel (x, y), with new pixel data.
+/// Used for textures that change at runtime, like animated lightmaps. Textures are uploaded
+/// as a single level, without mipmaps, so level 0 is the whole texture.
+pub fn update_region(texture: &Texture, x: u32, y: u32, raw_texture_data: &RawTextureData) {
+    let pixel_format = match raw_texture_data.format {
+        texture_format::PixelFormat::RGB => gl::RGB,
+        texture_format::PixelFormat::RGBA => gl::RGBA,
+    };
+
+    if raw_texture_data.bytes.is_empty() {
+        return;
+    }
+
+    // Not uploaded yet, so patch the pixels that will be
+    if let Some(pixels) = texture.pixels.borrow_mut().as_mut() {
+        crate::software::copy_region(pixels, x, y, raw_texture_data);
+        return;
+    }
+
+    unsafe {
+        gl::BindTexture(gl::TEXTURE_2D, texture.gl_id.get());
+
+        // Rows of RGB data aren't necessarily 4-byte aligned
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+        gl::TexSubImage2D(
+            gl::TEXTURE_2D,
+            0,
+            x as i32,
+            y as i32,
+            raw_texture_data.width as i32,
+            raw_texture_data.height as i32,
+            pixel_format,
+            gl::UNSIGNED_BYTE,
+            &raw_texture_data.bytes[0] as *const u8 as *const c_void,
+        );
+        gl::PixelStorei(gl::UNPACK_ALIGNMENT, 4);
     }
-    // */
-    // Texture { gl_id: 0 }
 }
 
 pub fn init<T: crate::texture_format::TextureFormat>(
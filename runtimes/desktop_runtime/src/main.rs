@@ -16,9 +16,12 @@ use shock2vr::command::MoveInventoryCommand;
 
 use shock2vr::command::SaveCommand;
 use shock2vr::command::SpawnItemCommand;
+use shock2vr::command::TeleportPlayerCommand;
 
 use std::time::Instant;
 
+use shock2vr::head_collision::HeadCollisionMode;
+use shock2vr::locomotion::LocomotionSettings;
 use shock2vr::GameOptions;
 use shock2vr::SpawnLocation;
 use tracing::trace;
@@ -41,6 +44,9 @@ use std::time::Duration;
 const SCR_WIDTH: u32 = 800;
 const SCR_HEIGHT: u32 = 600;
 
+// Units per second the spectator camera flies at - doubled with shift
+const SPECTATOR_SPEED: f32 = 20.0 / SCALE_FACTOR;
+
 struct MousePosition {
     x: f32,
     y: f32,
@@ -107,6 +113,14 @@ struct Args {
     #[arg(long = "debug-draw")]
     debug_draw: bool,
 
+    /// Locomotion preset - smooth, comfort or teleport. Defaults to the last one chosen in game
+    #[arg(long = "locomotion", default_value = None)]
+    locomotion: Option<String>,
+
+    /// What happens when leaning into a wall - pushback or fade. Defaults to pushback
+    #[arg(long = "head-collision", default_value = None)]
+    head_collision: Option<String>,
+
     #[arg(short, long, default_value = None)]
     save_file: Option<String>,
     // Number of times to greet
@@ -168,6 +182,47 @@ fn f32_from_bool(v: bool) -> f32 {
     }
 }
 
+///
+/// SpectatorCamera
+///
+/// A free-fly camera, detached from the player's body - it flies through walls, so level
+/// geometry, portal culling and AI can be inspected from outside. The game keeps running, and
+/// portal culling is still done from the player's point of view.
+struct SpectatorCamera {
+    position: Vector3<f32>,
+    // Rotation of the pawn when spectating started, so the view doesn't jump
+    rotation: Quaternion<f32>,
+}
+
+impl SpectatorCamera {
+    pub fn new(position: Vector3<f32>, rotation: Quaternion<f32>) -> SpectatorCamera {
+        SpectatorCamera { position, rotation }
+    }
+
+    ///
+    /// fly
+    ///
+    /// Move along the view direction with the right thumbstick, and up or down with the left
+    pub fn fly(
+        &mut self,
+        camera_context: &CameraContext,
+        input_context: &InputContext,
+        delta_time: f32,
+    ) {
+        let rotation = self.rotation * camera_rotation(camera_context);
+        let forward = rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
+        let right = rotation.rotate_vector(vec3(1.0, 0.0, 0.0));
+        let up = vec3(0.0, 1.0, 0.0);
+
+        let move_thumbstick = input_context.right_hand.thumbstick;
+        let vertical_thumbstick = input_context.left_hand.thumbstick;
+        let movement =
+            forward * move_thumbstick.y - right * move_thumbstick.x + up * vertical_thumbstick.y;
+
+        self.position += movement * SPECTATOR_SPEED * delta_time;
+    }
+}
+
 pub enum Mode {
     Gameplay,
     Editor,
@@ -176,6 +231,30 @@ pub enum Mode {
 pub enum Effect {
     SwitchToEditorMode,
     SwitchToGameplayMode,
+    ToggleSpectatorMode,
+    TeleportPlayerToSpectator,
+    ToggleConsole,
+}
+
+///
+/// spawn_console_reader
+///
+/// Read console commands from stdin on a background thread, so the render loop doesn't block
+fn spawn_console_reader() -> Receiver<String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            match line {
+                Ok(line) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+    });
+    receiver
 }
 
 pub fn main() {
@@ -231,8 +310,17 @@ pub fn main() {
         args.experimental.unwrap_or(vec![]).into_iter().collect();
 
     let (mission, spawn_location) = parse_mission(&args.mission);
+    let locomotion = args.locomotion.map(|name| {
+        LocomotionSettings::from_preset(&name)
+            .unwrap_or_else(|| panic!("unknown locomotion preset: {name}"))
+    });
+
+    let head_collision = args.head_collision.map(|name| {
+        HeadCollisionMode::from_name(&name)
+            .unwrap_or_else(|| panic!("unknown head collision mode: {name}"))
+    });
 
-    let options = GameOptions {
+    let mut options = GameOptions {
         mission,
         spawn_location,
         save_file: args.save_file,
@@ -241,8 +329,12 @@ pub fn main() {
         debug_portals: args.debug_portals,
         render_particles: true,
         experimental_features,
+        locomotion,
         ..GameOptions::default()
     };
+    if let Some(head_collision) = head_collision {
+        options.head_collision = head_collision;
+    }
     let mut game = shock2vr::Game::init(file_system, options);
     // FOR SCREENSHOT
     // let mut camera_context = CameraContext {
@@ -261,6 +353,8 @@ pub fn main() {
 
     let mut frame = 0;
     let mut last_input_state = InputState::new();
+    let mut spectator_camera: Option<SpectatorCamera> = None;
+    let console_lines = spawn_console_reader();
 
     let _mode = Mode::Gameplay;
     // render loop
@@ -272,7 +366,7 @@ pub fn main() {
         let delta_time = time - last_time;
         last_time = time;
 
-        let (input_context, new_input_state, commands, _effects) = process_events(
+        let (mut input_context, new_input_state, mut commands, effects) = process_events(
             &mut window,
             &mut camera_context,
             &mut hand_context,
@@ -281,6 +375,42 @@ pub fn main() {
             delta_time,
         );
         last_input_state = new_input_state;
+
+        let head_height = if last_input_state.is_crouching {
+            1.5
+        } else {
+            4.0
+        };
+        let head_offset = vec3(0.0, head_height / SCALE_FACTOR, 0.0);
+        input_context.head.position = head_offset;
+
+        let mut toggle_spectator = false;
+        for effect in effects {
+            match effect {
+                Effect::ToggleSpectatorMode => toggle_spectator = !toggle_spectator,
+                Effect::ToggleConsole => game.console().toggle_visible(),
+                Effect::TeleportPlayerToSpectator => {
+                    if let Some(spectator) = &spectator_camera {
+                        commands.push(Box::new(TeleportPlayerCommand::new(
+                            spectator.position - head_offset,
+                        )));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        while let Ok(line) = console_lines.try_recv() {
+            game.console().execute(&line);
+        }
+
+        // While spectating, movement flies the camera instead of the player
+        if let Some(spectator) = &mut spectator_camera {
+            spectator.fly(&camera_context, &input_context, delta_time);
+            input_context.left_hand.thumbstick = vec2(0.0, 0.0);
+            input_context.right_hand.thumbstick = vec2(0.0, 0.0);
+        }
+
         let ratio = SCR_WIDTH as f32 / SCR_HEIGHT as f32;
         let projection_matrix: cgmath::Matrix4<f32> =
             cgmath::perspective(cgmath::Deg(45.0), ratio, 0.1, 1000.0);
@@ -296,30 +426,48 @@ pub fn main() {
 
         let (mut scene, pawn_offset, pawn_rotation) = profile!("game.render", { game.render() });
 
-        let head_height = if last_input_state.is_crouching {
-            1.5
-        } else {
-            4.0
-        };
-        let render_context = engine::EngineRenderContext {
+        if toggle_spectator {
+            spectator_camera = match spectator_camera {
+                Some(_) => None,
+                None => Some(SpectatorCamera::new(
+                    pawn_offset + pawn_rotation.rotate_vector(head_offset),
+                    pawn_rotation,
+                )),
+            };
+        }
+
+        let player_render_context = engine::EngineRenderContext {
             time: glfw.get_time() as f32,
             camera_offset: pawn_offset,
             camera_rotation: pawn_rotation,
 
-            head_offset: vec3(0.0, head_height / SCALE_FACTOR, 0.0),
+            head_offset,
             head_rotation: camera_rotation(&camera_context),
 
             projection_matrix,
             screen_size,
         };
 
+        // Cull from the player's eyes, even when spectating - to inspect the culling from outside
+        let player_view = compute_view_matrix_from_render_context(&player_render_context);
+
+        let render_context = match &spectator_camera {
+            Some(spectator) => engine::EngineRenderContext {
+                camera_offset: spectator.position,
+                camera_rotation: spectator.rotation,
+                head_offset: vec3(0.0, 0.0, 0.0),
+                ..player_render_context
+            },
+            None => player_render_context,
+        };
+
         let view = compute_view_matrix_from_render_context(&render_context);
         let per_eye_scene = profile!(
             "game.render_per_eye",
             game.render_per_eye(view, projection_matrix, screen_size)
         );
 
-        game.finish_render(view, projection_matrix, screen_size);
+        game.finish_render(player_view, projection_matrix, screen_size);
 
         frame += 1;
 
@@ -371,6 +519,8 @@ fn parse_mission(mission: &str) -> (String, SpawnLocation) {
 struct InputState {
     quick_load_pressed: bool,
     quick_save_pressed: bool,
+    spectator_pressed: bool,
+    teleport_pressed: bool,
     space_pressed: bool,
     is_crouching: bool,
 }
@@ -379,6 +529,8 @@ impl InputState {
         Self {
             quick_load_pressed: false,
             quick_save_pressed: false,
+            spectator_pressed: false,
+            teleport_pressed: false,
             space_pressed: false,
             is_crouching: false,
         }
@@ -398,7 +550,7 @@ fn process_events(
     let _speed = 20.0;
     let head_rot_speed = 10.0;
 
-    let effects = Vec::new();
+    let mut effects = Vec::new();
 
     let _movement = cgmath::Vector3::new(0.0, 0.0, 0.0);
     let mut commands: Vec<Box<dyn Command>> = vec![];
@@ -434,6 +586,9 @@ fn process_events(
             // glfw::WindowEvent::Key(Key::Space, _, Action::Press, _) => {
             //     engine::audio::test_audio(audio)
             // }
+            glfw::WindowEvent::Key(Key::GraveAccent, _, Action::Press, _) => {
+                effects.push(Effect::ToggleConsole)
+            }
             glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => {
                 window.set_should_close(true)
             }
@@ -557,6 +712,7 @@ fn process_events(
     input_context.left_hand.trigger_value = f32_from_bool(hand_context.left_trigger_pressed);
     input_context.left_hand.squeeze_value = f32_from_bool(hand_context.left_squeeze_pressed);
     input_context.left_hand.a_value = f32_from_bool(hand_context.left_a_pressed);
+    input_context.right_hand.b_value = f32_from_bool(window.get_key(Key::J) == Action::Press);
     // input_context.left_hand.trigger_value = trigger_value;
     // input_context.left_hand.squeeze_value = squeeze_value;
 
@@ -584,6 +740,20 @@ fn process_events(
         }
     }
 
+    if window.get_key(Key::N) == Action::Press && is_alt_pressed {
+        input_state.spectator_pressed = true;
+        if !last_input_state.spectator_pressed {
+            effects.push(Effect::ToggleSpectatorMode);
+        }
+    }
+
+    if window.get_key(Key::T) == Action::Press && is_alt_pressed {
+        input_state.teleport_pressed = true;
+        if !last_input_state.teleport_pressed {
+            effects.push(Effect::TeleportPlayerToSpectator);
+        }
+    }
+
     input_state.is_crouching = window.get_key(Key::LeftControl) == Action::Press;
 
     if window.get_key(Key::I) == Action::Press {
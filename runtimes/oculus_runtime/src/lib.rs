@@ -318,6 +318,14 @@ fn main() {
         .create_action::<xr::Vector2f>("right_hand_thumbstick", "Right Hand Thumbstick", &[])
         .unwrap();
 
+    let left_menu = action_set
+        .create_action::<bool>("left_menu", "Left Hand Menu", &[])
+        .unwrap();
+
+    let right_b = action_set
+        .create_action::<bool>("right_b", "Right Hand B", &[])
+        .unwrap();
+
     // Bind our actions to input devices using the given profile
     // If you want to access inputs specific to a particular device you may specify a different
     // interaction profile
@@ -387,6 +395,18 @@ fn main() {
                         .string_to_path("/user/hand/right/input/thumbstick")
                         .unwrap(),
                 ),
+                xr::Binding::new(
+                    &left_menu,
+                    xr_instance
+                        .string_to_path("/user/hand/left/input/menu/click")
+                        .unwrap(),
+                ),
+                xr::Binding::new(
+                    &right_b,
+                    xr_instance
+                        .string_to_path("/user/hand/right/input/b/click")
+                        .unwrap(),
+                ),
             ],
         )
         .unwrap();
@@ -520,6 +540,24 @@ fn main() {
             .state(&session, xr::Path::NULL)
             .unwrap()
             .current_state;
+        let left_menu_value = if left_menu
+            .state(&session, xr::Path::NULL)
+            .unwrap()
+            .current_state
+        {
+            1.0
+        } else {
+            0.0
+        };
+        let right_b_value = if right_b
+            .state(&session, xr::Path::NULL)
+            .unwrap()
+            .current_state
+        {
+            1.0
+        } else {
+            0.0
+        };
         let right_trigger_value = right_trigger
             .state(&session, xr::Path::NULL)
             .unwrap()
@@ -566,12 +604,23 @@ fn main() {
             left_aim_location.pose.orientation.z,
         );
 
+        // Where the headset is in the play space, for crouching
+        let head_location = head_space
+            .locate(&stage, xr_frame_state.predicted_display_time)
+            .unwrap();
+
         let mut input_context = InputContext::default();
+        input_context.head.position = vec3(
+            head_location.pose.position.x,
+            head_location.pose.position.y,
+            head_location.pose.position.z,
+        );
         input_context.head.rotation = head_rotation;
         input_context.right_hand.rotation = head_rotation;
         input_context.right_hand.position = right_hand_position;
         input_context.right_hand.trigger_value = right_trigger_value;
         input_context.right_hand.squeeze_value = right_squeeze_value;
+        input_context.right_hand.b_value = right_b_value;
         input_context.right_hand.thumbstick =
             vec2(-right_thumbstick_value.x, right_thumbstick_value.y);
 
@@ -579,6 +628,7 @@ fn main() {
         input_context.left_hand.position = left_hand_position;
         input_context.left_hand.trigger_value = left_trigger_value;
         input_context.left_hand.squeeze_value = left_squeeze_value;
+        input_context.left_hand.menu_value = left_menu_value;
         input_context.left_hand.thumbstick =
             vec2(-left_thumbstick_value.x, left_thumbstick_value.y);
         game.update(&time_context, &input_context, vec![]);
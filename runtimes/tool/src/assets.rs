@@ -0,0 +1,420 @@
+///
+/// assets
+///
+/// Commands to inspect the game's asset archives (.crf files) - list what's available across the
+/// search order, find out which archive provides an asset, and extract or convert assets to
+/// formats that can be opened in other tools. None of these need a window.
+///
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use clap::Subcommand;
+use dark::importers::{RAW_TEXTURE_IMPORTER, SONG_IMPORTER, STRINGS_IMPORTER};
+use engine::{
+    assets::{
+        asset_cache::AssetCache,
+        asset_paths::{AbstractAssetPath, AssetListing},
+    },
+    texture_format::PixelFormat,
+};
+
+use crate::{export, BASE_PATH};
+
+#[derive(Subcommand, Debug)]
+pub enum AssetsCommand {
+    /// List assets from every archive and folder, in search order
+    List {
+        /// Only list assets matching the pattern - supports '*' and '?' wildcards, ie '*.pcx'
+        pattern: Option<String>,
+    },
+    /// Show which archive provides an asset, and which archives it shadows
+    Which {
+        /// Asset name, ie 'assassin.bin'
+        name: String,
+    },
+    /// Extract assets as-is
+    Extract {
+        /// Asset names or patterns, ie 'song1.sng' or 'fam/station/*.pcx'
+        #[arg(required = true)]
+        patterns: Vec<String>,
+
+        /// Folder to write the extracted assets to
+        #[arg(short, long, default_value = "extract")]
+        output: PathBuf,
+    },
+    /// Convert assets: PCX/GIF to PNG, models (.bin) to glTF, songs (.sng) and strings (.str)
+    /// to JSON
+    Convert {
+        /// Asset names or patterns, ie 'song1.sng' or 'fam/station/*.pcx'
+        #[arg(required = true)]
+        patterns: Vec<String>,
+
+        /// Folder to write the converted assets to
+        #[arg(short, long, default_value = "convert")]
+        output: PathBuf,
+    },
+}
+
+pub fn run(command: AssetsCommand) {
+    let asset_paths = shock2vr::default_asset_paths();
+
+    let result = match command {
+        AssetsCommand::List { pattern } => {
+            list(asset_paths.as_ref(), pattern.as_deref());
+            Ok(())
+        }
+        AssetsCommand::Which { name } => which(asset_paths.as_ref(), &name),
+        AssetsCommand::Extract { patterns, output } => {
+            extract(asset_paths.as_ref(), &patterns, &output)
+        }
+        AssetsCommand::Convert { patterns, output } => {
+            let names = resolve_patterns(asset_paths.as_ref(), &patterns);
+            let mut asset_cache = AssetCache::new(BASE_PATH.to_owned(), asset_paths);
+            convert(&mut asset_cache, &names, &output)
+        }
+    };
+
+    if let Err(err) = result {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn list(asset_paths: &dyn AbstractAssetPath, pattern: Option<&str>) {
+    let listings = asset_paths.list_assets(BASE_PATH.to_owned());
+
+    let mut count = 0;
+    let mut current_source = None;
+    for AssetListing { source, asset_name } in listings {
+        if let Some(pattern) = pattern {
+            if !matches_pattern(&asset_name, pattern) {
+                continue;
+            }
+        }
+
+        if current_source.as_ref() != Some(&source) {
+            println!("{source}:");
+            current_source = Some(source);
+        }
+        println!("  {asset_name}");
+        count += 1;
+    }
+
+    println!("{count} assets.");
+}
+
+fn which(asset_paths: &dyn AbstractAssetPath, name: &str) -> io::Result<()> {
+    let asset_name = name.to_ascii_lowercase();
+    let sources = asset_paths.sources_of(BASE_PATH.to_owned(), asset_name);
+
+    let (winner, shadowed) = sources
+        .split_first()
+        .ok_or_else(|| not_found(&format!("No archive provides {name}")))?;
+
+    println!("{name} is loaded from {winner}");
+    for source in shadowed {
+        println!("  shadows {source}");
+    }
+    Ok(())
+}
+
+fn extract(
+    asset_paths: &dyn AbstractAssetPath,
+    patterns: &[String],
+    output_folder: &Path,
+) -> io::Result<()> {
+    let names = resolve_patterns(asset_paths, patterns);
+    if names.is_empty() {
+        return Err(not_found("No assets match"));
+    }
+
+    for name in &names {
+        let reader = asset_paths
+            .get_reader(BASE_PATH.to_owned(), name.to_owned())
+            .ok_or_else(|| not_found(&format!("Unable to read {name}")))?;
+
+        let mut bytes = Vec::new();
+        reader.borrow_mut().read_to_end(&mut bytes)?;
+
+        let path = join_asset_name(output_folder, name)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, bytes)?;
+        println!("Extracted {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn convert(asset_cache: &mut AssetCache, names: &[String], output_folder: &Path) -> io::Result<()> {
+    if names.is_empty() {
+        return Err(not_found("No assets match"));
+    }
+
+    for name in names {
+        let extension = Path::new(name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        let path = match extension.as_str() {
+            "pcx" | "gif" | "png" | "jpg" | "jpeg" => {
+                convert_texture(asset_cache, name, output_folder)?
+            }
+            "bin" => {
+                // Models are written to their own folder, since they come with textures
+                let model_folder = output_folder.join(file_stem(name));
+                export::model::export(asset_cache, name, &[], &model_folder)?;
+                model_folder
+            }
+            "sng" => {
+                let song = asset_cache
+                    .get_opt(&SONG_IMPORTER, name)
+                    .ok_or_else(|| not_found(&format!("Unable to load song {name}")))?;
+                write_json(output_folder, name, &*song)?
+            }
+            "str" => {
+                let strings = asset_cache
+                    .get_opt(&STRINGS_IMPORTER, name)
+                    .ok_or_else(|| not_found(&format!("Unable to load strings {name}")))?;
+
+                // Sort the keys, so the output is stable across runs
+                let sorted: BTreeMap<&String, &String> = strings.iter().collect();
+                write_json(output_folder, name, &sorted)?
+            }
+            _ => {
+                println!("No converter for {name}, skipping.");
+                continue;
+            }
+        };
+
+        println!("Converted {name} to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn convert_texture(
+    asset_cache: &mut AssetCache,
+    name: &str,
+    output_folder: &Path,
+) -> io::Result<PathBuf> {
+    let raw_texture = asset_cache
+        .get_opt(&RAW_TEXTURE_IMPORTER, name)
+        .ok_or_else(|| not_found(&format!("Unable to load texture {name}")))?;
+
+    let path = output_path(output_folder, name, "png")?;
+    let color_type = match raw_texture.format {
+        PixelFormat::RGB => image::ColorType::Rgb8,
+        PixelFormat::RGBA => image::ColorType::Rgba8,
+    };
+    image::save_buffer(
+        &path,
+        &raw_texture.bytes,
+        raw_texture.width,
+        raw_texture.height,
+        color_type,
+    )
+    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+
+    Ok(path)
+}
+
+fn write_json<T: serde::Serialize + ?Sized>(
+    output_folder: &Path,
+    name: &str,
+    value: &T,
+) -> io::Result<PathBuf> {
+    let path = output_path(output_folder, name, "json")?;
+    fs::write(&path, serde_json::to_string_pretty(value)?)?;
+    Ok(path)
+}
+
+///
+/// resolve_patterns
+///
+/// Expand wildcard patterns against the listing of all assets. Names without wildcards are
+/// passed through as-is, since collapsed archive paths mean an asset can be loaded by a name
+/// that isn't in the listing.
+fn resolve_patterns(asset_paths: &dyn AbstractAssetPath, patterns: &[String]) -> Vec<String> {
+    let listings = asset_paths.list_assets(BASE_PATH.to_owned());
+
+    let mut seen = HashSet::new();
+    let mut names = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.to_ascii_lowercase();
+        if !pattern.contains(['*', '?']) {
+            if seen.insert(pattern.clone()) {
+                names.push(pattern);
+            }
+            continue;
+        }
+
+        for listing in &listings {
+            if matches_pattern(&listing.asset_name, &pattern)
+                && seen.insert(listing.asset_name.clone())
+            {
+                names.push(listing.asset_name.clone());
+            }
+        }
+    }
+    names
+}
+
+///
+/// matches_pattern
+///
+/// Case-insensitive wildcard match, where '*' matches any run of characters and '?' matches
+/// a single character
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name: Vec<char> = name.to_ascii_lowercase().chars().collect();
+    let pattern: Vec<char> = pattern.to_ascii_lowercase().chars().collect();
+
+    // Greedy match, backtracking to the last '*' on a mismatch
+    let (mut n, mut p) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            n += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            last_star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = last_star {
+            p = star_p + 1;
+            n = star_n + 1;
+            last_star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+fn output_path(output_folder: &Path, name: &str, extension: &str) -> io::Result<PathBuf> {
+    let path = join_asset_name(output_folder, name)?.with_extension(extension);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(path)
+}
+
+///
+/// join_asset_name
+///
+/// Path of an asset within the output folder. Asset names come from the command line and from
+/// archive listings, so names that are absolute or climb out with '..' are rejected, rather than
+/// written outside the output folder.
+fn join_asset_name(output_folder: &Path, name: &str) -> io::Result<PathBuf> {
+    let is_contained = Path::new(name)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+
+    if !is_contained {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Asset name {name} would be written outside of the output folder"),
+        ));
+    }
+
+    Ok(output_folder.join(name))
+}
+
+fn file_stem(name: &str) -> String {
+    Path::new(name)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.to_owned())
+}
+
+fn not_found(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use engine::assets::asset_paths::ReadableAndSeekable;
+
+    use super::*;
+
+    struct ListedAssets(Vec<&'static str>);
+
+    impl AbstractAssetPath for ListedAssets {
+        fn exists(&self, _base_path: String, asset_name: String) -> bool {
+            self.0.contains(&asset_name.as_str())
+        }
+
+        fn get_reader(
+            &self,
+            _base_path: String,
+            _asset_name: String,
+        ) -> Option<RefCell<Box<dyn ReadableAndSeekable>>> {
+            None
+        }
+
+        fn describe(&self) -> String {
+            "test".to_owned()
+        }
+
+        fn list_assets(&self, _base_path: String) -> Vec<AssetListing> {
+            self.0
+                .iter()
+                .map(|asset_name| AssetListing {
+                    source: self.describe(),
+                    asset_name: asset_name.to_string(),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("fam/station/wall.pcx", "*.pcx"));
+        assert!(matches_pattern("fam/station/wall.pcx", "FAM/*/WALL.PCX"));
+        assert!(matches_pattern("song1.sng", "song?.sng"));
+        assert!(matches_pattern("song1.sng", "song*"));
+        assert!(matches_pattern("song1.sng", "song1.sng*"));
+        assert!(!matches_pattern("song10.sng", "song?.sng"));
+        assert!(!matches_pattern("song1.sng", "*.pcx"));
+        assert!(!matches_pattern("song1.sng", "song1"));
+    }
+
+    #[test]
+    fn test_resolve_patterns_expands_wildcards_once() {
+        // Given
+        let asset_paths = ListedAssets(vec!["song1.sng", "song2.sng", "wall.pcx"]);
+        let patterns = vec![
+            "SONG*".to_owned(),
+            "song?.sng".to_owned(),
+            "Missing.bin".to_owned(),
+            "missing.bin".to_owned(),
+        ];
+
+        // When
+        let names = resolve_patterns(&asset_paths, &patterns);
+
+        // Then - duplicates are dropped, and plain names pass through lowercased
+        assert_eq!(names, vec!["song1.sng", "song2.sng", "missing.bin"]);
+    }
+
+    #[test]
+    fn test_asset_names_stay_in_the_output_folder() {
+        let output_folder = Path::new("extract");
+
+        assert_eq!(
+            join_asset_name(output_folder, "fam/station/wall.pcx").unwrap(),
+            output_folder.join("fam/station/wall.pcx")
+        );
+        assert!(join_asset_name(output_folder, "../wall.pcx").is_err());
+        assert!(join_asset_name(output_folder, "fam/../../wall.pcx").is_err());
+        assert!(join_asset_name(output_folder, "/etc/wall.pcx").is_err());
+    }
+}
@@ -0,0 +1,235 @@
+///
+/// coverage
+///
+/// Report which scripts are referenced by the missions, and how far along each one is in being
+/// ported - so we can prioritize the scripts that matter most.
+///
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use dark::properties::PropScripts;
+use serde::Serialize;
+use shipyard::{Get, View};
+use shock2vr::{script_coverage, ScriptCoverage};
+
+use crate::{
+    inspect::{load_mission_objects, read_gamesys},
+    BASE_PATH,
+};
+
+#[derive(Args, Debug)]
+pub struct CoverageOptions {
+    /// File to write the full report to, as JSON
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Default, Serialize)]
+struct Counts {
+    implemented: usize,
+    noop: usize,
+    unknown: usize,
+}
+
+impl Counts {
+    fn add(&mut self, coverage: ScriptCoverage) {
+        match coverage {
+            ScriptCoverage::Implemented => self.implemented += 1,
+            ScriptCoverage::Noop => self.noop += 1,
+            ScriptCoverage::Unknown => self.unknown += 1,
+        }
+    }
+
+    fn total(&self) -> usize {
+        self.implemented + self.noop + self.unknown
+    }
+}
+
+#[derive(Serialize)]
+struct ScriptUsage {
+    coverage: &'static str,
+    missions: Vec<String>,
+    object_count: usize,
+}
+
+pub fn run(options: CoverageOptions) {
+    if let Err(err) = report(&options) {
+        eprintln!("Coverage report failed: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn report(options: &CoverageOptions) -> io::Result<()> {
+    let (properties, links, links_with_data) = dark::properties::get();
+    let gamesys = read_gamesys(&properties, &links, &links_with_data)?;
+
+    // Script name -> usage, and mission -> distinct scripts
+    let mut scripts: BTreeMap<String, ScriptUsage> = BTreeMap::new();
+    let mut mission_to_scripts: BTreeMap<String, HashSet<String>> = BTreeMap::new();
+
+    for mission_name in find_missions(Path::new(BASE_PATH))? {
+        println!("Scanning {mission_name}...");
+        let mission = load_mission_objects(
+            &mission_name,
+            &gamesys,
+            &properties,
+            &links,
+            &links_with_data,
+        )?;
+
+        // Scripts include the ones inherited from archetypes
+        let object_scripts = mission.world.run(|v_scripts: View<PropScripts>| {
+            mission
+                .object_ids
+                .iter()
+                .filter_map(|id| v_scripts.get(mission.template_to_entity_id[id].0).ok())
+                .flat_map(|prop_scripts| prop_scripts.scripts.clone())
+                .map(|script| script.to_ascii_lowercase())
+                .collect::<Vec<String>>()
+        });
+
+        let mission_scripts = mission_to_scripts.entry(mission_name.clone()).or_default();
+        for script in object_scripts {
+            let usage = scripts
+                .entry(script.clone())
+                .or_insert_with(|| ScriptUsage {
+                    coverage: coverage_name(script_coverage(&script)),
+                    missions: Vec::new(),
+                    object_count: 0,
+                });
+            usage.object_count += 1;
+            if mission_scripts.insert(script) {
+                usage.missions.push(mission_name.clone());
+            }
+        }
+    }
+
+    let mut mission_counts: BTreeMap<String, Counts> = BTreeMap::new();
+    let mut deck_counts: BTreeMap<String, Counts> = BTreeMap::new();
+    let mut deck_to_scripts: HashMap<String, HashSet<String>> = HashMap::new();
+    for (mission_name, mission_scripts) in &mission_to_scripts {
+        let counts = mission_counts.entry(mission_name.clone()).or_default();
+        let deck = deck_name(mission_name);
+        let deck_scripts = deck_to_scripts.entry(deck.clone()).or_default();
+        for script in mission_scripts {
+            counts.add(script_coverage(script));
+
+            // A script used on several levels of a deck only counts once for the deck
+            if deck_scripts.insert(script.clone()) {
+                deck_counts
+                    .entry(deck.clone())
+                    .or_default()
+                    .add(script_coverage(script));
+            }
+        }
+    }
+
+    let mut total = Counts::default();
+    for script in scripts.keys() {
+        total.add(script_coverage(script));
+    }
+
+    print_counts("Mission", &mission_counts);
+    print_counts("Deck", &deck_counts);
+
+    // Most-used first, so the top of each list is what to port next
+    let mut by_usage = scripts.iter().collect::<Vec<_>>();
+    by_usage.sort_by(|(a_name, a), (b_name, b)| {
+        b.object_count
+            .cmp(&a.object_count)
+            .then_with(|| a_name.cmp(b_name))
+    });
+    for (label, coverage) in [
+        ("Unknown", ScriptCoverage::Unknown),
+        ("Noop", ScriptCoverage::Noop),
+    ] {
+        println!();
+        println!("{label} scripts:");
+        for (name, usage) in by_usage
+            .iter()
+            .filter(|(name, _)| script_coverage(name) == coverage)
+        {
+            println!(
+                "  {name:<28} {:>5} objects  {}",
+                usage.object_count,
+                usage.missions.join(", ")
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{} scripts referenced: {} implemented, {} noop, {} unknown",
+        total.total(),
+        total.implemented,
+        total.noop,
+        total.unknown
+    );
+
+    if let Some(path) = &options.output {
+        let document = serde_json::json!({
+            "total": total,
+            "missions": mission_counts,
+            "decks": deck_counts,
+            "scripts": scripts,
+        });
+        fs::write(path, serde_json::to_string_pretty(&document)?)?;
+        println!("Wrote {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn print_counts(label: &str, counts: &BTreeMap<String, Counts>) {
+    println!();
+    println!(
+        "{label:<12} {:>11} {:>6} {:>8} {:>6}",
+        "implemented", "noop", "unknown", "total"
+    );
+    for (name, counts) in counts {
+        println!(
+            "{name:<12} {:>11} {:>6} {:>8} {:>6}",
+            counts.implemented,
+            counts.noop,
+            counts.unknown,
+            counts.total()
+        );
+    }
+}
+
+fn find_missions(data_path: &Path) -> io::Result<Vec<String>> {
+    let mut missions = fs::read_dir(data_path)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|file_name| file_name.to_ascii_lowercase().ends_with(".mis"))
+        .collect::<Vec<String>>();
+    missions.sort();
+    Ok(missions)
+}
+
+///
+/// deck_name
+///
+/// The deck a mission belongs to - the mission name without its level number, ie 'medsci2.mis'
+/// is on the 'medsci' deck
+fn deck_name(mission_name: &str) -> String {
+    let stem = mission_name
+        .split('.')
+        .next()
+        .unwrap_or(mission_name)
+        .to_ascii_lowercase();
+    stem.trim_end_matches(|c: char| c.is_ascii_digit())
+        .to_owned()
+}
+
+fn coverage_name(coverage: ScriptCoverage) -> &'static str {
+    match coverage {
+        ScriptCoverage::Implemented => "implemented",
+        ScriptCoverage::Noop => "noop",
+        ScriptCoverage::Unknown => "unknown",
+    }
+}
@@ -0,0 +1,393 @@
+///
+/// gltf_builder.rs
+///
+/// A minimal glTF 2.0 writer - accumulates buffer data, accessors, and scene nodes, and writes
+/// them out as a .gltf (json) + .bin pair. Images are referenced by uri, so the caller is
+/// responsible for writing them alongside.
+///
+use std::{collections::HashMap, fs, io, path::Path};
+
+use cgmath::{InnerSpace, Matrix3, Matrix4, Quaternion, SquareMatrix, Vector2, Vector3, Vector4};
+use serde_json::{json, Value};
+
+// Component types
+const FLOAT: u32 = 5126;
+const UNSIGNED_SHORT: u32 = 5123;
+
+// Buffer view targets
+const ARRAY_BUFFER: u32 = 34962;
+
+pub struct GltfNode {
+    pub name: String,
+    pub translation: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    pub scale: Vector3<f32>,
+    pub mesh: Option<usize>,
+    pub skin: Option<usize>,
+    pub children: Vec<usize>,
+    pub extras: Option<Value>,
+}
+
+impl GltfNode {
+    pub fn new(name: &str) -> GltfNode {
+        GltfNode {
+            name: name.to_owned(),
+            translation: Vector3::new(0.0, 0.0, 0.0),
+            rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            scale: Vector3::new(1.0, 1.0, 1.0),
+            mesh: None,
+            skin: None,
+            children: Vec::new(),
+            extras: None,
+        }
+    }
+
+    pub fn with_transform(mut self, transform: Matrix4<f32>) -> GltfNode {
+        let (translation, rotation, scale) = decompose(transform);
+        self.translation = translation;
+        self.rotation = rotation;
+        self.scale = scale;
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut node = json!({
+            "name": self.name,
+            "translation": vec3_to_array(self.translation),
+            "rotation": quat_to_array(self.rotation),
+            "scale": vec3_to_array(self.scale),
+        });
+
+        if let Some(mesh) = self.mesh {
+            node["mesh"] = json!(mesh);
+        }
+
+        if let Some(skin) = self.skin {
+            node["skin"] = json!(skin);
+        }
+
+        if !self.children.is_empty() {
+            node["children"] = json!(self.children);
+        }
+
+        if let Some(extras) = &self.extras {
+            node["extras"] = extras.clone();
+        }
+
+        node
+    }
+}
+
+pub struct GltfBuilder {
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    images: Vec<Value>,
+    textures: Vec<Value>,
+    uri_to_texture: HashMap<String, usize>,
+    materials: Vec<Value>,
+    meshes: Vec<Value>,
+    nodes: Vec<GltfNode>,
+    skins: Vec<Value>,
+    animations: Vec<Value>,
+    root_nodes: Vec<usize>,
+}
+
+impl GltfBuilder {
+    pub fn new() -> GltfBuilder {
+        GltfBuilder {
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            images: Vec::new(),
+            textures: Vec::new(),
+            uri_to_texture: HashMap::new(),
+            materials: Vec::new(),
+            meshes: Vec::new(),
+            nodes: Vec::new(),
+            skins: Vec::new(),
+            animations: Vec::new(),
+            root_nodes: Vec::new(),
+        }
+    }
+
+    pub fn add_positions(&mut self, positions: &[Vector3<f32>]) -> usize {
+        // glTF requires bounds on position accessors
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for position in positions {
+            for (axis, value) in [position.x, position.y, position.z].iter().enumerate() {
+                min[axis] = min[axis].min(*value);
+                max[axis] = max[axis].max(*value);
+            }
+        }
+
+        let accessor = self.add_vec3(positions);
+        if !positions.is_empty() {
+            self.accessors[accessor]["min"] = json!(min);
+            self.accessors[accessor]["max"] = json!(max);
+        }
+        accessor
+    }
+
+    pub fn add_vec3(&mut self, values: &[Vector3<f32>]) -> usize {
+        let floats: Vec<f32> = values.iter().flat_map(|v| [v.x, v.y, v.z]).collect();
+        self.add_accessor(&floats_to_bytes(&floats), values.len(), "VEC3", FLOAT, true)
+    }
+
+    pub fn add_vec2(&mut self, values: &[Vector2<f32>]) -> usize {
+        let floats: Vec<f32> = values.iter().flat_map(|v| [v.x, v.y]).collect();
+        self.add_accessor(&floats_to_bytes(&floats), values.len(), "VEC2", FLOAT, true)
+    }
+
+    pub fn add_vec4(&mut self, values: &[Vector4<f32>]) -> usize {
+        let floats: Vec<f32> = values.iter().flat_map(|v| [v.x, v.y, v.z, v.w]).collect();
+        self.add_accessor(&floats_to_bytes(&floats), values.len(), "VEC4", FLOAT, true)
+    }
+
+    pub fn add_quaternions(&mut self, values: &[Quaternion<f32>]) -> usize {
+        let floats: Vec<f32> = values.iter().flat_map(|q| quat_to_array(*q)).collect();
+        self.add_accessor(
+            &floats_to_bytes(&floats),
+            values.len(),
+            "VEC4",
+            FLOAT,
+            false,
+        )
+    }
+
+    pub fn add_joints(&mut self, values: &[[u16; 4]]) -> usize {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flat_map(|joints| joints.iter().flat_map(|j| j.to_le_bytes()))
+            .collect();
+        self.add_accessor(&bytes, values.len(), "VEC4", UNSIGNED_SHORT, true)
+    }
+
+    pub fn add_times(&mut self, values: &[f32]) -> usize {
+        let accessor = self.add_accessor(
+            &floats_to_bytes(values),
+            values.len(),
+            "SCALAR",
+            FLOAT,
+            false,
+        );
+
+        // Animation input accessors require bounds
+        if let (Some(first), Some(last)) = (values.first(), values.last()) {
+            self.accessors[accessor]["min"] = json!([first]);
+            self.accessors[accessor]["max"] = json!([last]);
+        }
+        accessor
+    }
+
+    pub fn add_matrices(&mut self, values: &[Matrix4<f32>]) -> usize {
+        // Column-major, which matches both cgmath and glTF
+        let floats: Vec<f32> = values
+            .iter()
+            .flat_map(|m| {
+                let columns: &[[f32; 4]; 4] = m.as_ref();
+                columns.iter().flatten().copied().collect::<Vec<f32>>()
+            })
+            .collect();
+        self.add_accessor(
+            &floats_to_bytes(&floats),
+            values.len(),
+            "MAT4",
+            FLOAT,
+            false,
+        )
+    }
+
+    ///
+    /// add_texture
+    ///
+    /// Add a texture for the image at the uri (relative to the .gltf file). Textures are shared
+    /// across materials, so adding the same uri twice returns the same texture.
+    pub fn add_texture(&mut self, uri: &str) -> usize {
+        if let Some(texture) = self.uri_to_texture.get(uri) {
+            return *texture;
+        }
+
+        let image = self.images.len();
+        self.images.push(json!({ "uri": uri }));
+
+        let texture = self.textures.len();
+        self.textures.push(json!({ "source": image }));
+        self.uri_to_texture.insert(uri.to_owned(), texture);
+        texture
+    }
+
+    pub fn add_material(&mut self, material: Value) -> usize {
+        self.materials.push(material);
+        self.materials.len() - 1
+    }
+
+    pub fn add_mesh(&mut self, name: &str, primitives: Vec<Value>) -> usize {
+        self.meshes.push(json!({
+            "name": name,
+            "primitives": primitives,
+        }));
+        self.meshes.len() - 1
+    }
+
+    pub fn add_skin(&mut self, joints: &[usize], inverse_bind_matrices: usize) -> usize {
+        self.skins.push(json!({
+            "joints": joints,
+            "inverseBindMatrices": inverse_bind_matrices,
+        }));
+        self.skins.len() - 1
+    }
+
+    pub fn add_animation(&mut self, name: &str, samplers: Vec<Value>, channels: Vec<Value>) {
+        self.animations.push(json!({
+            "name": name,
+            "samplers": samplers,
+            "channels": channels,
+        }));
+    }
+
+    pub fn add_node(&mut self, node: GltfNode) -> usize {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+    }
+
+    pub fn node_mut(&mut self, node: usize) -> &mut GltfNode {
+        &mut self.nodes[node]
+    }
+
+    pub fn add_root_node(&mut self, node: GltfNode) -> usize {
+        let idx = self.add_node(node);
+        self.root_nodes.push(idx);
+        idx
+    }
+
+    pub fn add_child(&mut self, parent: usize, node: GltfNode) -> usize {
+        let idx = self.add_node(node);
+        self.nodes[parent].children.push(idx);
+        idx
+    }
+
+    ///
+    /// write
+    ///
+    /// Write `<name>.gltf` and `<name>.bin` to the output folder
+    pub fn write(self, output_folder: &Path, name: &str) -> io::Result<()> {
+        fs::create_dir_all(output_folder)?;
+
+        let bin_name = format!("{name}.bin");
+        fs::write(output_folder.join(&bin_name), &self.buffer)?;
+
+        let mut document = json!({
+            "asset": {
+                "version": "2.0",
+                "generator": "ss2tool",
+            },
+            "scene": 0,
+            "scenes": [{ "nodes": self.root_nodes }],
+            "nodes": self.nodes.iter().map(|node| node.to_json()).collect::<Vec<Value>>(),
+            "buffers": [{
+                "uri": bin_name,
+                "byteLength": self.buffer.len(),
+            }],
+            "bufferViews": self.buffer_views,
+            "accessors": self.accessors,
+        });
+
+        // glTF doesn't allow empty arrays, so only include the sections that have entries
+        let optional_sections = [
+            ("images", self.images),
+            ("textures", self.textures),
+            ("materials", self.materials),
+            ("meshes", self.meshes),
+            ("skins", self.skins),
+            ("animations", self.animations),
+        ];
+        for (key, values) in optional_sections {
+            if !values.is_empty() {
+                document[key] = json!(values);
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&document)?;
+        fs::write(output_folder.join(format!("{name}.gltf")), json)
+    }
+
+    fn add_accessor(
+        &mut self,
+        bytes: &[u8],
+        count: usize,
+        accessor_type: &str,
+        component_type: u32,
+        is_vertex_attribute: bool,
+    ) -> usize {
+        // Keep every buffer view 4-byte aligned
+        while self.buffer.len() % 4 != 0 {
+            self.buffer.push(0);
+        }
+
+        let mut buffer_view = json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+        });
+        if is_vertex_attribute {
+            buffer_view["target"] = json!(ARRAY_BUFFER);
+        }
+        self.buffer.extend_from_slice(bytes);
+        self.buffer_views.push(buffer_view);
+
+        self.accessors.push(json!({
+            "bufferView": self.buffer_views.len() - 1,
+            "componentType": component_type,
+            "count": count,
+            "type": accessor_type,
+        }));
+        self.accessors.len() - 1
+    }
+}
+
+///
+/// decompose
+///
+/// Split an affine transform into translation, rotation, and scale - glTF nodes that are
+/// animated must use TRS rather than a matrix.
+pub fn decompose(transform: Matrix4<f32>) -> (Vector3<f32>, Quaternion<f32>, Vector3<f32>) {
+    let translation = transform.w.truncate();
+    let mut scale = Vector3::new(
+        transform.x.truncate().magnitude(),
+        transform.y.truncate().magnitude(),
+        transform.z.truncate().magnitude(),
+    );
+
+    // A mirrored transform has a negative determinant - fold that into the scale
+    if transform.determinant() < 0.0 {
+        scale.x = -scale.x;
+    }
+
+    let safe_div = |v: Vector3<f32>, s: f32| if s.abs() > f32::EPSILON { v / s } else { v };
+    let rotation_matrix = Matrix3::from_cols(
+        safe_div(transform.x.truncate(), scale.x),
+        safe_div(transform.y.truncate(), scale.y),
+        safe_div(transform.z.truncate(), scale.z),
+    );
+
+    (
+        translation,
+        Quaternion::from(rotation_matrix).normalize(),
+        scale,
+    )
+}
+
+fn floats_to_bytes(floats: &[f32]) -> Vec<u8> {
+    floats.iter().flat_map(|f| f.to_le_bytes()).collect()
+}
+
+fn vec3_to_array(v: Vector3<f32>) -> [f32; 3] {
+    [v.x, v.y, v.z]
+}
+
+// glTF stores quaternions as (x, y, z, w)
+fn quat_to_array(q: Quaternion<f32>) -> [f32; 4] {
+    [q.v.x, q.v.y, q.v.z, q.s]
+}
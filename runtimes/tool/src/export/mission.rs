@@ -0,0 +1,271 @@
+///
+/// mission.rs
+///
+/// Export a mission's level geometry - grouped by texture, with a second uv set for the lightmap
+/// atlas - along with a node for each object placed in the level.
+///
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    path::Path,
+};
+
+use cgmath::{vec2, vec3, Deg, Matrix4, Quaternion, Rotation3, Vector2, Vector3};
+use dark::{
+    mission::SystemShock2Level,
+    properties::{
+        PropAI, PropModelName, PropPosition, PropScale, PropSymName, PropTemplateId, RenderType,
+    },
+    ss2_entity_info,
+};
+use engine::{assets::asset_cache::AssetCache, scene::VertexPositionTextureLightmapAtlas};
+use serde_json::json;
+use shipyard::{Get, IntoIter, IntoWithId, View, World};
+
+use crate::resource_path;
+
+use super::{
+    add_material, export_texture,
+    gltf_builder::{GltfBuilder, GltfNode},
+    model, read_gamesys, sanitize_name,
+};
+
+// Size of the lightmap atlas, in pixels - must match the size used when loading the level
+const LIGHTMAP_ATLAS_SIZE: f32 = 4096.0;
+
+struct Placement {
+    name: String,
+    template_id: i32,
+    model_name: String,
+    transform: Matrix4<f32>,
+}
+
+pub fn export(
+    asset_cache: &mut AssetCache,
+    mission_name: &str,
+    output_folder: &Path,
+) -> io::Result<()> {
+    let (properties, links, links_with_data) = dark::properties::get();
+    let gamesys = read_gamesys()?;
+
+    // Textures are only uploaded to the GPU when first drawn, so loading the level doesn't
+    // need a GL context
+    println!("Loading mission {mission_name}...");
+    let mut level = {
+        let mut reader = BufReader::new(File::open(resource_path(mission_name))?);
+        dark::mission::read(
+            asset_cache,
+            &mut reader,
+            &gamesys,
+            &links,
+            &links_with_data,
+            &properties,
+        )
+    };
+
+    let mut builder = GltfBuilder::new();
+    let name = sanitize_name(mission_name);
+
+    let lightmap_uri = "lightmaps.png";
+    std::fs::create_dir_all(output_folder)?;
+    level
+        .lightmap_atlas
+        .save(&output_folder.join(lightmap_uri).to_string_lossy());
+
+    let mut level_node = GltfNode::new(&name);
+    level_node.mesh = Some(add_level_mesh(
+        &mut builder,
+        asset_cache,
+        &level,
+        lightmap_uri,
+        output_folder,
+    )?);
+    level_node.extras = Some(json!({ "mission": mission_name }));
+    builder.add_root_node(level_node);
+
+    let placements = read_placements(&level, &gamesys);
+    println!("Exporting {} object placements...", placements.len());
+
+    let objects_node = builder.add_root_node(GltfNode::new("objects"));
+    let mut model_to_mesh: HashMap<String, Option<usize>> = HashMap::new();
+    for placement in placements {
+        let mesh = match model_to_mesh.get(&placement.model_name) {
+            Some(mesh) => *mesh,
+            None => {
+                let mesh = model::add_bind_pose_mesh(
+                    &mut builder,
+                    asset_cache,
+                    &placement.model_name,
+                    output_folder,
+                )?;
+                model_to_mesh.insert(placement.model_name.clone(), mesh);
+                mesh
+            }
+        };
+
+        let mut node = GltfNode::new(&placement.name).with_transform(placement.transform);
+        node.mesh = mesh;
+        node.extras = Some(json!({
+            "template_id": placement.template_id,
+            "model": placement.model_name,
+        }));
+        builder.add_child(objects_node, node);
+    }
+
+    builder.write(output_folder, &name)
+}
+
+fn add_level_mesh(
+    builder: &mut GltfBuilder,
+    asset_cache: &mut AssetCache,
+    level: &SystemShock2Level,
+    lightmap_uri: &str,
+    output_folder: &Path,
+) -> io::Result<usize> {
+    let mut texture_to_vertices: HashMap<u16, Vec<&VertexPositionTextureLightmapAtlas>> =
+        HashMap::new();
+    for geometry in &level.all_geometry {
+        // Skip empty texture
+        if geometry.texture_idx == 0 {
+            continue;
+        }
+
+        texture_to_vertices
+            .entry(geometry.texture_idx)
+            .or_default()
+            .extend(geometry.verts.iter());
+    }
+
+    // Sort, so the export is stable across runs
+    let mut texture_to_vertices = texture_to_vertices.into_iter().collect::<Vec<_>>();
+    texture_to_vertices.sort_by_key(|(texture_idx, _)| *texture_idx);
+
+    let mut primitives = Vec::new();
+    for (texture_idx, vertices) in texture_to_vertices {
+        let tex_info = &level.textures.0[texture_idx as usize];
+        let texture_name = format!(
+            "{}/{}.PCX",
+            tex_info.family.to_uppercase(),
+            tex_info.texture_filename
+        );
+
+        let texture_uri = if tex_info.texture_filename == "null" {
+            None
+        } else {
+            export_texture(asset_cache, &texture_name, output_folder)?
+        };
+
+        let is_fullbright = tex_info.render_type == RenderType::FullBright;
+        let material = add_material(
+            builder,
+            &texture_name,
+            texture_uri.as_deref(),
+            if is_fullbright {
+                None
+            } else {
+                Some(lightmap_uri)
+            },
+            0.0,
+            json!({
+                "family": tex_info.family,
+                "texture": tex_info.texture_filename,
+                "surface_material": tex_info.material,
+                "fullbright": is_fullbright,
+            }),
+        );
+
+        let positions: Vec<Vector3<f32>> = vertices.iter().map(|v| v.position).collect();
+        let uvs: Vec<Vector2<f32>> = vertices.iter().map(|v| v.uv).collect();
+        let lightmap_uvs: Vec<Vector2<f32>> =
+            vertices.iter().map(|v| lightmap_atlas_uv(v)).collect();
+
+        primitives.push(json!({
+            "attributes": {
+                "POSITION": builder.add_positions(&positions),
+                "TEXCOORD_0": builder.add_vec2(&uvs),
+                "TEXCOORD_1": builder.add_vec2(&lightmap_uvs),
+            },
+            "material": material,
+        }));
+    }
+
+    Ok(builder.add_mesh("level", primitives))
+}
+
+///
+/// lightmap_atlas_uv
+///
+/// Map the vertex's lightmap uv into the atlas - the same calculation as the lightmap shader,
+/// but clamped rather than wrapped, since it's evaluated per-vertex instead of per-pixel.
+fn lightmap_atlas_uv(vertex: &VertexPositionTextureLightmapAtlas) -> Vector2<f32> {
+    let half_pixel = 0.5 / LIGHTMAP_ATLAS_SIZE;
+    let full_pixel = half_pixel * 2.0;
+    let atlas = vertex.lightmap_atlas;
+    let width = atlas.z - full_pixel;
+    let height = atlas.w - full_pixel;
+
+    vec2(
+        vertex.lightmap_uv.x.clamp(0.0, 1.0) * width + atlas.x + half_pixel,
+        vertex.lightmap_uv.y.clamp(0.0, 1.0) * height + atlas.y + half_pixel,
+    )
+}
+
+fn read_placements(level: &SystemShock2Level, gamesys: &dark::Gamesys) -> Vec<Placement> {
+    let entity_info = ss2_entity_info::merge_with_gamesys(&level.entity_info, gamesys);
+
+    // Only initialize the objects in the mission - not every archetype in the gamesys
+    let mut world = World::new();
+    entity_info.initialize_world_with_entities(&mut world, level.obj_map.clone(), |id| {
+        level.entity_info.entity_to_properties.contains_key(&id)
+    });
+
+    let mut placements = world.run(
+        |v_position: View<PropPosition>,
+         v_model_name: View<PropModelName>,
+         v_scale: View<PropScale>,
+         v_sym_name: View<PropSymName>,
+         v_template_id: View<PropTemplateId>,
+         v_ai: View<PropAI>| {
+            (&v_position, &v_model_name, &v_template_id)
+                .iter()
+                .with_id()
+                .map(|(entity_id, (position, model_name, template_id))| {
+                    // Same adjustment as when populating the mission - AI models face 90
+                    // degrees off from their position
+                    let rotation = match v_ai.get(entity_id) {
+                        Ok(ai)
+                            if !ai.0.eq_ignore_ascii_case("turret")
+                                && !ai.0.eq_ignore_ascii_case("camera") =>
+                        {
+                            position.rotation * Quaternion::from_angle_y(Deg(-90.0))
+                        }
+                        _ => position.rotation,
+                    };
+
+                    let scale = match v_scale.get(entity_id) {
+                        Ok(scale) => vec3(scale.0.x.abs(), scale.0.y.abs(), scale.0.z.abs()),
+                        Err(_) => vec3(1.0, 1.0, 1.0),
+                    };
+
+                    let name = match v_sym_name.get(entity_id) {
+                        Ok(sym_name) => format!("{} ({})", sym_name.0, template_id.template_id),
+                        Err(_) => format!("{} ({})", model_name.0, template_id.template_id),
+                    };
+
+                    Placement {
+                        name,
+                        template_id: template_id.template_id,
+                        model_name: format!("{}.bin", model_name.0),
+                        transform: Matrix4::from_translation(position.position)
+                            * Matrix4::from(rotation)
+                            * Matrix4::from_nonuniform_scale(scale.x, scale.y, scale.z),
+                    }
+                })
+                .collect::<Vec<Placement>>()
+        },
+    );
+
+    placements.sort_by_key(|placement| placement.template_id);
+    placements
+}
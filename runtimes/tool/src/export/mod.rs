@@ -0,0 +1,201 @@
+///
+/// export
+///
+/// Commands to export missions and models to glTF 2.0 - so levels can be reviewed in tools like
+/// Blender, and used as a starting point for modded assets.
+///
+mod gltf_builder;
+mod mission;
+pub mod model;
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+};
+
+use clap::Subcommand;
+use dark::{gamesys, importers::RAW_TEXTURE_IMPORTER, Gamesys};
+use engine::{assets::asset_cache::AssetCache, texture_format::PixelFormat};
+use serde_json::{json, Value};
+
+use gltf_builder::GltfBuilder;
+
+use crate::{resource_path, BASE_PATH};
+
+#[derive(Subcommand, Debug)]
+pub enum ExportCommand {
+    /// Export a mission's level geometry, lightmap atlas, and object placements
+    Mission {
+        /// Mission file to export, ie 'earth.mis'
+        mission: String,
+
+        /// Folder to write the .gltf, .bin, and textures to
+        #[arg(short, long, default_value = "export")]
+        output: PathBuf,
+    },
+    /// Export a model (.bin), with its skeleton and animation clips
+    Model {
+        /// Model file to export, ie 'assassin.bin'
+        model: String,
+
+        /// Motion to include as an animation clip, ie 'ogsshot1' - can be repeated. Defaults to
+        /// every motion in the motion database for the model's creature type
+        #[arg(short, long)]
+        animation: Vec<String>,
+
+        /// Folder to write the .gltf, .bin, and textures to
+        #[arg(short, long, default_value = "export")]
+        output: PathBuf,
+    },
+}
+
+pub fn run(command: ExportCommand) {
+    let mut asset_cache = AssetCache::new(BASE_PATH.to_owned(), shock2vr::default_asset_paths());
+
+    let result = match command {
+        ExportCommand::Mission { mission, output } => {
+            mission::export(&mut asset_cache, &mission, &output)
+        }
+        ExportCommand::Model {
+            model,
+            animation,
+            output,
+        } => model::export(&mut asset_cache, &model, &animation, &output),
+    };
+
+    match result {
+        Ok(()) => println!("Export complete."),
+        Err(err) => {
+            eprintln!("Export failed: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+///
+/// read_gamesys
+///
+/// Load the archetypes from shock2.gam - used to find the properties of objects placed in a
+/// mission, and the creature type of a model.
+fn read_gamesys() -> io::Result<Gamesys> {
+    let (properties, links, links_with_data) = dark::properties::get();
+    let mut reader = BufReader::new(File::open(resource_path("shock2.gam"))?);
+    Ok(gamesys::read(
+        &mut reader,
+        &links,
+        &links_with_data,
+        &properties,
+    ))
+}
+
+///
+/// export_texture
+///
+/// Write the texture as a png to the 'textures' folder, returning the uri relative to the
+/// .gltf file - or None if the texture couldn't be loaded.
+fn export_texture(
+    asset_cache: &mut AssetCache,
+    texture_name: &str,
+    output_folder: &Path,
+) -> io::Result<Option<String>> {
+    // Materials without an image (ie, solid colors) have no texture to export
+    let has_image_extension = Path::new(texture_name)
+        .extension()
+        .and_then(|ext| {
+            engine::texture_format::extension_to_format(ext.to_string_lossy().to_string())
+        })
+        .is_some();
+    if !has_image_extension {
+        return Ok(None);
+    }
+
+    let raw_texture = match asset_cache.get_opt(&RAW_TEXTURE_IMPORTER, texture_name) {
+        Some(raw_texture) => raw_texture,
+        None => return Ok(None),
+    };
+
+    let uri = format!("textures/{}.png", sanitize_name(texture_name));
+    let path = output_folder.join(&uri);
+    if !path.exists() {
+        fs::create_dir_all(output_folder.join("textures"))?;
+        let color_type = match raw_texture.format {
+            PixelFormat::RGB => image::ColorType::Rgb8,
+            PixelFormat::RGBA => image::ColorType::Rgba8,
+        };
+        image::save_buffer(
+            &path,
+            &raw_texture.bytes,
+            raw_texture.width,
+            raw_texture.height,
+            color_type,
+        )
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    }
+
+    Ok(Some(uri))
+}
+
+///
+/// add_material
+///
+/// Add a material using the exported texture. Materials are double-sided, since the winding
+/// order of the original geometry isn't consistent.
+fn add_material(
+    builder: &mut GltfBuilder,
+    name: &str,
+    texture_uri: Option<&str>,
+    lightmap_uri: Option<&str>,
+    transparency: f32,
+    extras: Value,
+) -> usize {
+    let mut pbr = json!({
+        "baseColorFactor": [1.0, 1.0, 1.0, 1.0 - transparency],
+        "metallicFactor": 0.0,
+        "roughnessFactor": 1.0,
+    });
+    if let Some(uri) = texture_uri {
+        pbr["baseColorTexture"] = json!({ "index": builder.add_texture(uri) });
+    }
+
+    let mut material = json!({
+        "name": name,
+        "doubleSided": true,
+        "pbrMetallicRoughness": pbr,
+        "extras": extras,
+    });
+
+    if transparency > 0.01 {
+        material["alphaMode"] = json!("BLEND");
+    }
+
+    // There's no standard lightmap slot in glTF - occlusion is the closest fit, and the
+    // second uv set lines up with the lightmap atlas
+    if let Some(uri) = lightmap_uri {
+        material["occlusionTexture"] = json!({
+            "index": builder.add_texture(uri),
+            "texCoord": 1,
+        });
+    }
+
+    builder.add_material(material)
+}
+
+fn sanitize_name(name: &str) -> String {
+    let without_extension = match name.rfind('.') {
+        Some(idx) => &name[..idx],
+        None => name,
+    };
+
+    without_extension
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,431 @@
+///
+/// model.rs
+///
+/// Export a model - either a static object (.bin with sub-objects) or an AI mesh with its
+/// skeleton (.cal) - along with animation clips from the motion database.
+///
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+};
+
+use cgmath::{point3, vec4, EuclideanSpace, Matrix4, SquareMatrix, Transform, Vector3};
+use dark::{
+    importers::{SystemShockContentModel, ANIMATION_CLIP_IMPORTER, MODEL_DATA_IMPORTER},
+    motion::{AnimationClip, MotionDB},
+    properties::{PropCreature, PropModelName},
+    ss2_bin_ai_loader,
+    ss2_bin_obj_loader::{self, SystemShock2MeshMaterial},
+    ss2_skeleton::Skeleton,
+};
+use engine::{assets::asset_cache::AssetCache, scene::VertexPositionTextureSkinned};
+use num::ToPrimitive;
+use serde_json::{json, Value};
+use shipyard::{IntoIter, View, World};
+
+use crate::resource_path;
+
+use super::{
+    add_material, export_texture,
+    gltf_builder::{decompose, GltfBuilder, GltfNode},
+    read_gamesys, sanitize_name,
+};
+
+// A set of triangles sharing a material
+struct ModelPart {
+    material_name: String,
+    transparency: f32,
+    vertices: Vec<VertexPositionTextureSkinned>,
+}
+
+pub fn export(
+    asset_cache: &mut AssetCache,
+    model_name: &str,
+    animation_names: &[String],
+    output_folder: &Path,
+) -> io::Result<()> {
+    let model_file = with_bin_extension(model_name);
+    let (parts, skeleton) = read_model(asset_cache, &model_file).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unable to load model {model_file}"),
+        )
+    })?;
+
+    let mut builder = GltfBuilder::new();
+    let name = sanitize_name(&model_file);
+
+    // Static models (a single sub-object) have nothing to animate
+    if skeleton.bone_count() <= 1 {
+        if !animation_names.is_empty() {
+            println!("{model_file} has no skeleton, so animations will be skipped.");
+        }
+
+        let mesh = add_mesh(
+            &mut builder,
+            asset_cache,
+            &name,
+            &parts,
+            &skeleton,
+            None,
+            output_folder,
+        )?;
+        let mut node = GltfNode::new(&name);
+        node.mesh = Some(mesh);
+        builder.add_root_node(node);
+        return builder.write(output_folder, &name);
+    }
+
+    let root = builder.add_root_node(GltfNode::new(&name));
+
+    // Create a node per joint, and then hook up the hierarchy - parents aren't guaranteed to
+    // come before their children
+    let bones = skeleton.bones();
+    let mut joint_to_node = HashMap::new();
+    let mut joint_to_skin_index = HashMap::new();
+    let mut joint_nodes = Vec::new();
+    for (skin_index, bone) in bones.iter().enumerate() {
+        let joint_name = format!("joint_{}", bone.joint_id);
+        let node =
+            builder.add_node(GltfNode::new(&joint_name).with_transform(bone.local_transform));
+        joint_to_node.insert(bone.joint_id, node);
+        joint_to_skin_index.insert(bone.joint_id, skin_index as u16);
+        joint_nodes.push(node);
+    }
+
+    for bone in bones {
+        let node = joint_to_node[&bone.joint_id];
+        let parent = bone
+            .parent_id
+            .and_then(|parent_id| joint_to_node.get(&parent_id))
+            .copied()
+            .unwrap_or(root);
+        builder.node_mut(parent).children.push(node);
+    }
+
+    let inverse_bind_matrices: Vec<Matrix4<f32>> = bones
+        .iter()
+        .map(|bone| {
+            skeleton
+                .global_transform(&bone.joint_id)
+                .invert()
+                .unwrap_or(Matrix4::identity())
+        })
+        .collect();
+    let inverse_bind_matrices = builder.add_matrices(&inverse_bind_matrices);
+    let skin = builder.add_skin(&joint_nodes, inverse_bind_matrices);
+
+    let mesh = add_mesh(
+        &mut builder,
+        asset_cache,
+        &name,
+        &parts,
+        &skeleton,
+        Some(&joint_to_skin_index),
+        output_folder,
+    )?;
+
+    // Per the glTF spec, the transform of a skinned mesh node is ignored - the joints position it
+    let mut mesh_node = GltfNode::new(&format!("{name}_mesh"));
+    mesh_node.mesh = Some(mesh);
+    mesh_node.skin = Some(skin);
+    builder.add_child(root, mesh_node);
+
+    let animation_names = if animation_names.is_empty() {
+        creature_motions(&model_file)?
+    } else {
+        animation_names.to_vec()
+    };
+
+    for animation_name in &animation_names {
+        let clip_name = format!("{}_.mc", animation_name.to_ascii_lowercase());
+        match asset_cache.get_opt(&ANIMATION_CLIP_IMPORTER, &clip_name) {
+            Some(clip) => add_animation(
+                &mut builder,
+                animation_name,
+                &clip,
+                &skeleton,
+                &joint_to_node,
+            ),
+            None => println!("Unable to load animation {animation_name}, skipping."),
+        }
+    }
+
+    builder.write(output_folder, &name)
+}
+
+///
+/// creature_motions
+///
+/// All the motions in the motion database for the creature type of the model - found from the
+/// archetypes in the gamesys that use it.
+fn creature_motions(model_file: &str) -> io::Result<Vec<String>> {
+    let gamesys = read_gamesys()?;
+    let mut world = World::new();
+    gamesys
+        .entity_info
+        .initialize_world_with_entities(&mut world, HashMap::new(), |_| true);
+
+    let model_name = sanitize_name(model_file);
+    let maybe_creature_type = world.run(
+        |v_model_name: View<PropModelName>, v_creature: View<PropCreature>| {
+            (&v_model_name, &v_creature)
+                .iter()
+                .find(|(name, _)| name.0.eq_ignore_ascii_case(&model_name))
+                .map(|(_, creature)| creature.0)
+        },
+    );
+
+    let actor_type = match maybe_creature_type.and_then(shock2vr::get_creature_definition) {
+        Some(creature_definition) => creature_definition.actor_type.to_u32().unwrap(),
+        None => {
+            println!("No creature uses {model_file}, so there are no animations to export.");
+            return Ok(Vec::new());
+        }
+    };
+
+    let mut reader = BufReader::new(File::open(resource_path("motiondb.bin"))?);
+    let motiondb = MotionDB::read(&mut reader);
+    let animation_names = motiondb.animation_names(actor_type);
+    println!(
+        "Exporting {} animations from the motion database...",
+        animation_names.len()
+    );
+    Ok(animation_names)
+}
+
+///
+/// add_bind_pose_mesh
+///
+/// Add the model as an unskinned mesh, posed by its skeleton - used for object placements in
+/// mission exports. Returns None if the model couldn't be loaded.
+pub fn add_bind_pose_mesh(
+    builder: &mut GltfBuilder,
+    asset_cache: &mut AssetCache,
+    model_name: &str,
+    output_folder: &Path,
+) -> io::Result<Option<usize>> {
+    match read_model(asset_cache, model_name) {
+        Some((parts, skeleton)) => {
+            let name = sanitize_name(model_name);
+            let mesh = add_mesh(
+                builder,
+                asset_cache,
+                &name,
+                &parts,
+                &skeleton,
+                None,
+                output_folder,
+            )?;
+            Ok(Some(mesh))
+        }
+        None => Ok(None),
+    }
+}
+
+fn read_model(
+    asset_cache: &mut AssetCache,
+    model_name: &str,
+) -> Option<(Vec<ModelPart>, Skeleton)> {
+    let content = asset_cache.get_opt(&MODEL_DATA_IMPORTER, model_name)?;
+
+    let ret = match &*content {
+        SystemShockContentModel::Obj(obj) => {
+            let slot_to_material: HashMap<u16, &SystemShock2MeshMaterial> = obj
+                .materials
+                .iter()
+                .map(|material| (material.slot_num as u16, material))
+                .collect();
+
+            let mut slot_to_vertices = ss2_bin_obj_loader::to_vertices(obj)
+                .into_iter()
+                .filter(|(_, vertices)| !vertices.is_empty())
+                .collect::<Vec<_>>();
+            slot_to_vertices.sort_by_key(|(slot, _)| *slot);
+
+            let parts = slot_to_vertices
+                .into_iter()
+                .map(|(slot, vertices)| {
+                    let (material_name, transparency) = match slot_to_material.get(&slot) {
+                        Some(material) => (material.name.clone(), material.transparency),
+                        None => (format!("slot_{slot}"), 0.0),
+                    };
+                    ModelPart {
+                        material_name,
+                        transparency,
+                        vertices,
+                    }
+                })
+                .collect();
+
+            (parts, ss2_bin_obj_loader::create_skeleton(obj))
+        }
+        SystemShockContentModel::Mesh(mesh, skeleton) => {
+            let (material_to_vertices, _hit_boxes) = ss2_bin_ai_loader::to_vertices(mesh, skeleton);
+            let parts = material_to_vertices
+                .into_iter()
+                .filter(|(_, vertices)| !vertices.is_empty())
+                .map(|(material_name, vertices)| ModelPart {
+                    material_name,
+                    transparency: 0.0,
+                    vertices,
+                })
+                .collect();
+
+            (parts, skeleton.as_ref().clone())
+        }
+    };
+
+    Some(ret)
+}
+
+///
+/// add_mesh
+///
+/// Add a primitive per model part. Vertices are stored relative to their joint, so they are
+/// moved into the bind pose - and, when `joint_to_skin_index` is provided, bound to the skin.
+fn add_mesh(
+    builder: &mut GltfBuilder,
+    asset_cache: &mut AssetCache,
+    name: &str,
+    parts: &[ModelPart],
+    skeleton: &Skeleton,
+    joint_to_skin_index: Option<&HashMap<u32, u16>>,
+    output_folder: &Path,
+) -> io::Result<usize> {
+    let mut primitives = Vec::new();
+    for part in parts {
+        let texture_uri = export_texture(asset_cache, &part.material_name, output_folder)?;
+        let material = add_material(
+            builder,
+            &part.material_name,
+            texture_uri.as_deref(),
+            None,
+            part.transparency,
+            json!({ "texture": part.material_name }),
+        );
+
+        let positions: Vec<Vector3<f32>> = part
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let joint_transform = skeleton.global_transform(&vertex.bone_indices[0]);
+                let position = point3(vertex.position.x, vertex.position.y, vertex.position.z);
+                joint_transform.transform_point(position).to_vec()
+            })
+            .collect();
+        let uvs: Vec<_> = part.vertices.iter().map(|vertex| vertex.uv).collect();
+
+        let mut attributes = json!({
+            "POSITION": builder.add_positions(&positions),
+            "TEXCOORD_0": builder.add_vec2(&uvs),
+        });
+
+        if let Some(joint_to_skin_index) = joint_to_skin_index {
+            // Each vertex is rigidly bound to a single joint
+            let joints: Vec<[u16; 4]> = part
+                .vertices
+                .iter()
+                .map(|vertex| {
+                    let skin_index = joint_to_skin_index
+                        .get(&vertex.bone_indices[0])
+                        .copied()
+                        .unwrap_or(0);
+                    [skin_index, 0, 0, 0]
+                })
+                .collect();
+            let weights = vec![vec4(1.0, 0.0, 0.0, 0.0); part.vertices.len()];
+
+            attributes["JOINTS_0"] = json!(builder.add_joints(&joints));
+            attributes["WEIGHTS_0"] = json!(builder.add_vec4(&weights));
+        }
+
+        primitives.push(json!({
+            "attributes": attributes,
+            "material": material,
+        }));
+    }
+
+    Ok(builder.add_mesh(name, primitives))
+}
+
+///
+/// add_animation
+///
+/// Sample the clip's joint transforms at each frame, as translation and rotation channels on
+/// the joint nodes.
+fn add_animation(
+    builder: &mut GltfBuilder,
+    name: &str,
+    clip: &AnimationClip,
+    skeleton: &Skeleton,
+    joint_to_node: &HashMap<u32, usize>,
+) {
+    let frame_time = clip.time_per_frame.as_secs_f32();
+
+    // Sort, so the export is stable across runs
+    let mut joint_to_frames = clip.joint_to_frame.iter().collect::<Vec<_>>();
+    joint_to_frames.sort_by_key(|(joint_id, _)| **joint_id);
+
+    let mut samplers: Vec<Value> = Vec::new();
+    let mut channels: Vec<Value> = Vec::new();
+    for (joint_id, frames) in joint_to_frames {
+        let (node, bone) = match (
+            joint_to_node.get(joint_id),
+            skeleton
+                .bones()
+                .iter()
+                .find(|bone| bone.joint_id == *joint_id),
+        ) {
+            (Some(node), Some(bone)) => (*node, bone),
+            _ => continue,
+        };
+
+        if frames.is_empty() {
+            continue;
+        }
+
+        let times: Vec<f32> = (0..frames.len()).map(|i| i as f32 * frame_time).collect();
+        let (translations, rotations): (Vec<_>, Vec<_>) = frames
+            .iter()
+            .map(|frame| {
+                let (translation, rotation, _scale) = decompose(bone.local_transform * *frame);
+                (translation, rotation)
+            })
+            .unzip();
+
+        let input = builder.add_times(&times);
+        let channel_outputs = [
+            ("translation", builder.add_vec3(&translations)),
+            ("rotation", builder.add_quaternions(&rotations)),
+        ];
+        for (path, output) in channel_outputs {
+            channels.push(json!({
+                "sampler": samplers.len(),
+                "target": { "node": node, "path": path },
+            }));
+            samplers.push(json!({
+                "input": input,
+                "output": output,
+                "interpolation": "LINEAR",
+            }));
+        }
+    }
+
+    if channels.is_empty() {
+        println!("Animation {name} doesn't affect any joints in the model, skipping.");
+        return;
+    }
+
+    builder.add_animation(name, samplers, channels);
+}
+
+fn with_bin_extension(model_name: &str) -> String {
+    let mut path = PathBuf::from(model_name);
+    if path.extension().is_none() {
+        path.set_extension("bin");
+    }
+    path.to_string_lossy().to_string()
+}
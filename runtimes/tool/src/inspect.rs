@@ -0,0 +1,302 @@
+///
+/// inspect
+///
+/// Dump every object in a mission as JSON - its archetype chain, the properties it ends up with
+/// after inheritance, its links, and its scripts. Useful when porting scripts, since it shows
+/// exactly what an object looks like to the game.
+///
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{self, File},
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+use dark::{
+    gamesys,
+    properties::{
+        LinkDefinition, LinkDefinitionWithData, Links, PropScripts, PropSymName,
+        PropertyDefinition, TemplateLinks, WrappedEntityId,
+    },
+    ss2_entity_info::{self, SystemShock2EntityInfo},
+    Gamesys,
+};
+use serde_json::{json, Value};
+use shipyard::{Get, View, World};
+
+use crate::resource_path;
+
+#[derive(Args, Debug)]
+pub struct InspectOptions {
+    /// Mission file to inspect, ie 'medsci1.mis'
+    mission: String,
+
+    /// Only include objects that inherit from this archetype, ie 'Door'
+    #[arg(short, long)]
+    archetype: Option<String>,
+
+    /// Only include objects that have this script, ie 'TrapTeleporter'
+    #[arg(short, long)]
+    script: Option<String>,
+
+    /// File to write the JSON to - defaults to the mission name, with a .json extension
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+pub fn run(options: InspectOptions) {
+    match inspect(&options) {
+        Ok(path) => println!("Wrote {}", path.display()),
+        Err(err) => {
+            eprintln!("Inspect failed: {err}");
+            std::process::exit(1);
+        }
+    }
+}
+
+///
+/// MissionObjects
+///
+/// The concrete objects in a mission, initialized into a world along with their archetypes -
+/// so their properties reflect inheritance, the same as in game
+pub struct MissionObjects {
+    pub world: World,
+    pub entity_info: SystemShock2EntityInfo,
+    pub obj_map: HashMap<i32, String>,
+    pub object_ids: Vec<i32>,
+    pub template_to_entity_id: HashMap<i32, WrappedEntityId>,
+}
+
+pub type PropertyDefinitions = Vec<Box<dyn PropertyDefinition<BufReader<File>>>>;
+
+pub fn load_mission_objects(
+    mission_name: &str,
+    gamesys: &Gamesys,
+    properties: &PropertyDefinitions,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+) -> io::Result<MissionObjects> {
+    let (obj_map, mission_entity_info) = {
+        let mut reader = BufReader::new(File::open(resource_path(mission_name))?);
+        dark::mission::read_entities(&mut reader, links, links_with_data, properties)
+    };
+    let entity_info = ss2_entity_info::merge_with_gamesys(&mission_entity_info, gamesys);
+
+    // Concrete objects have positive ids - negative ids are archetypes
+    let mut object_ids = mission_entity_info
+        .entity_to_properties
+        .keys()
+        .copied()
+        .filter(|id| *id > 0)
+        .collect::<Vec<i32>>();
+    object_ids.sort();
+
+    // Initialize the archetypes too, so their names can be looked up
+    let hierarchy = ss2_entity_info::get_hierarchy(&entity_info);
+    let mut to_initialize = HashSet::new();
+    for id in &object_ids {
+        to_initialize.insert(*id);
+        to_initialize.extend(ss2_entity_info::get_ancestors(hierarchy, id));
+    }
+
+    let mut world = World::new();
+    let template_to_entity_id = entity_info
+        .initialize_world_with_entities(&mut world, obj_map.clone(), |id| {
+            to_initialize.contains(&id)
+        })
+        .into_iter()
+        .map(|(id, entity_id)| (id, WrappedEntityId(entity_id)))
+        .collect();
+
+    Ok(MissionObjects {
+        world,
+        entity_info,
+        obj_map,
+        object_ids,
+        template_to_entity_id,
+    })
+}
+
+pub fn read_gamesys(
+    properties: &PropertyDefinitions,
+    links: &Vec<Box<dyn LinkDefinition>>,
+    links_with_data: &Vec<Box<dyn LinkDefinitionWithData>>,
+) -> io::Result<Gamesys> {
+    let mut reader = BufReader::new(File::open(resource_path("shock2.gam"))?);
+    Ok(gamesys::read(
+        &mut reader,
+        links,
+        links_with_data,
+        properties,
+    ))
+}
+
+fn inspect(options: &InspectOptions) -> io::Result<PathBuf> {
+    let (properties, links, links_with_data) = dark::properties::get();
+    let gamesys = read_gamesys(&properties, &links, &links_with_data)?;
+    let mission = load_mission_objects(
+        &options.mission,
+        &gamesys,
+        &properties,
+        &links,
+        &links_with_data,
+    )?;
+
+    let entity_to_properties = serialize_properties(&mission.world, &properties);
+    let mut objects = Vec::new();
+    for id in &mission.object_ids {
+        let object = describe_object(&mission, &entity_to_properties, *id);
+        if matches_filters(&object, options) {
+            objects.push(object);
+        }
+    }
+
+    println!("Found {} matching objects.", objects.len());
+    let document = json!({
+        "mission": options.mission,
+        "objects": objects,
+    });
+
+    let path = match &options.output {
+        Some(path) => path.clone(),
+        None => Path::new(&options.mission).with_extension("json"),
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(&document)?)?;
+    Ok(path)
+}
+
+///
+/// serialize_properties
+///
+/// Serialize every property in the world, keyed by entity and then by property name
+/// (ie, 'P$Scripts')
+fn serialize_properties(
+    world: &World,
+    properties: &PropertyDefinitions,
+) -> HashMap<u64, BTreeMap<String, Value>> {
+    let mut entity_to_properties: HashMap<u64, BTreeMap<String, Value>> = HashMap::new();
+    for property in properties {
+        for (entity, value) in property.serialize(world) {
+            entity_to_properties
+                .entry(entity)
+                .or_default()
+                .insert(property.name(), value);
+        }
+    }
+    entity_to_properties
+}
+
+fn describe_object(
+    mission: &MissionObjects,
+    entity_to_properties: &HashMap<u64, BTreeMap<String, Value>>,
+    template_id: i32,
+) -> Value {
+    let MissionObjects {
+        world,
+        entity_info,
+        obj_map,
+        template_to_entity_id,
+        ..
+    } = mission;
+    let entity_id = template_to_entity_id[&template_id].0;
+
+    let name_of = |id: i32| -> Option<String> {
+        if let Some(name) = obj_map.get(&id) {
+            return Some(name.to_owned());
+        }
+        let entity_id = template_to_entity_id.get(&id)?;
+        world.run(|v_sym_name: View<PropSymName>| {
+            v_sym_name
+                .get(entity_id.0)
+                .ok()
+                .map(|sym_name| sym_name.0.clone())
+        })
+    };
+
+    let hierarchy = ss2_entity_info::get_hierarchy(entity_info);
+    let ancestors = ss2_entity_info::get_ancestors(hierarchy, &template_id);
+    let archetypes = ancestors
+        .iter()
+        .map(|id| json!({ "id": id, "name": name_of(*id) }))
+        .collect::<Vec<Value>>();
+
+    let scripts = world.run(|v_scripts: View<PropScripts>| {
+        v_scripts
+            .get(entity_id)
+            .map(|scripts| scripts.scripts.clone())
+            .unwrap_or_default()
+    });
+
+    // Links are inherited from archetypes, same as when the mission is populated
+    let mut template_links = TemplateLinks::empty();
+    for id in ancestors.iter().chain([template_id].iter()) {
+        if let Some(links) = entity_info.template_to_links.get(id) {
+            template_links = TemplateLinks::merge(&template_links, links);
+        }
+    }
+    let links = Links::from_template_links(&template_links, template_to_entity_id)
+        .to_links
+        .iter()
+        .map(|to_link| {
+            json!({
+                "to_template_id": to_link.to_template_id,
+                "to_name": name_of(to_link.to_template_id),
+                "link": to_link.link,
+            })
+        })
+        .collect::<Vec<Value>>();
+
+    json!({
+        "id": template_id,
+        "name": name_of(template_id),
+        "archetypes": archetypes,
+        "scripts": scripts,
+        "properties": entity_to_properties.get(&entity_id.inner()).cloned().unwrap_or_default(),
+        "links": links,
+    })
+}
+
+///
+/// matches_filters
+///
+/// An object matches the archetype filter if it, or any of its archetypes, has that name - and
+/// the script filter if the script is in its (inherited) scripts. Both are case-insensitive.
+fn matches_filters(object: &Value, options: &InspectOptions) -> bool {
+    let name_matches = |value: &Value, expected: &str| {
+        value
+            .as_str()
+            .map(|name| name.eq_ignore_ascii_case(expected))
+            .unwrap_or(false)
+    };
+
+    if let Some(archetype) = &options.archetype {
+        let is_match = name_matches(&object["name"], archetype)
+            || object["archetypes"]
+                .as_array()
+                .map(|archetypes| {
+                    archetypes
+                        .iter()
+                        .any(|archetype_json| name_matches(&archetype_json["name"], archetype))
+                })
+                .unwrap_or(false);
+        if !is_match {
+            return false;
+        }
+    }
+
+    if let Some(script) = &options.script {
+        let is_match = object["scripts"]
+            .as_array()
+            .map(|scripts| scripts.iter().any(|name| name_matches(name, script)))
+            .unwrap_or(false);
+        if !is_match {
+            return false;
+        }
+    }
+
+    true
+}
@@ -1,4 +1,14 @@
 extern crate glfw;
+mod assets;
+mod coverage;
+mod export;
+mod inspect;
+
+use assets::AssetsCommand;
+use clap::{Parser, Subcommand};
+use coverage::CoverageOptions;
+use export::ExportCommand;
+use inspect::InspectOptions;
 use engine_ffmpeg::VideoPlayer;
 use glfw::GlfwReceiver;
 
@@ -153,7 +163,42 @@ extern crate ffmpeg_next as ffmpeg;
 use ffmpeg::format::{input, Pixel};
 use ffmpeg::media::Type;
 use ffmpeg::util::frame::video::Video;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<ToolCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+enum ToolCommand {
+    /// Open the model and video viewer (the default, when no command is given)
+    View,
+    /// Export missions and models to glTF 2.0
+    #[command(subcommand)]
+    Export(ExportCommand),
+    /// List, extract, and convert assets from the game's archives
+    #[command(subcommand)]
+    Assets(AssetsCommand),
+    /// Dump a mission's objects - archetypes, properties, links, and scripts - as JSON
+    Inspect(InspectOptions),
+    /// Report which scripts the missions use, and which of them are implemented
+    Coverage(CoverageOptions),
+}
+
 pub fn main() {
+    let args = Args::parse();
+    match args.command {
+        None | Some(ToolCommand::View) => run_viewer(),
+        Some(ToolCommand::Export(command)) => export::run(command),
+        Some(ToolCommand::Assets(command)) => assets::run(command),
+        Some(ToolCommand::Inspect(options)) => inspect::run(options),
+        Some(ToolCommand::Coverage(options)) => coverage::run(options),
+    }
+}
+
+fn run_viewer() {
     // glfw: initialize and configure
     // ------------------------------
 
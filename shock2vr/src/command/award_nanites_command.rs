@@ -0,0 +1,24 @@
+use shipyard::World;
+
+use crate::scripts::Effect;
+
+use super::Command;
+// AwardNanitesCommand
+#[derive(Debug)]
+pub struct AwardNanitesCommand {
+    amount: u32,
+}
+
+impl AwardNanitesCommand {
+    pub fn new(amount: u32) -> AwardNanitesCommand {
+        AwardNanitesCommand { amount }
+    }
+}
+
+impl Command for AwardNanitesCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::AwardNanites {
+            amount: self.amount,
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+
+use cgmath::{vec3, InnerSpace, Matrix4, Quaternion, Rad, Rotation, Rotation3, Vector3};
+use dark::SCALE_FACTOR;
+use tracing::info;
+
+use crate::input_context::InputContext;
+
+use super::{Command, CommandRegistry};
+
+// Number of output lines kept, and shown on the panel
+const MAX_OUTPUT_LINES: usize = 12;
+
+// Number of recently run commands kept for quick selection
+const MAX_QUICK_COMMANDS: usize = 10;
+
+// Quick commands available before anything has been typed - in VR, there's no keyboard to type
+// with
+const DEFAULT_QUICK_COMMANDS: [&str; 10] = [
+    "help",
+    "toggle portals",
+    "toggle physics",
+    "toggle draw",
+    "toggle particles",
+    "locomotion smooth",
+    "locomotion comfort",
+    "locomotion teleport",
+    "headcollision pushback",
+    "headcollision fade",
+];
+
+// How far in front of the player the panel opens
+const PANEL_DISTANCE: f32 = 1.0;
+const PANEL_HEIGHT: f32 = 3.5 / SCALE_FACTOR;
+
+// Analog values past this count as pressed
+const PRESS_THRESHOLD: f32 = 0.5;
+
+///
+/// Console
+///
+/// The developer console - lines are parsed by the command registry, and the resulting commands
+/// run on the next update. Lines come from stdin on desktop. In VR, the console is a floating
+/// panel, toggled with the menu button, where the left thumbstick picks a quick command and the
+/// left trigger runs it.
+pub struct Console {
+    registry: CommandRegistry,
+    output: VecDeque<String>,
+    quick_commands: Vec<String>,
+    selected_quick_command: usize,
+    is_visible: bool,
+    panel_transform: Option<Matrix4<f32>>,
+    pending_commands: Vec<Box<dyn Command>>,
+
+    was_menu_pressed: bool,
+    was_trigger_pressed: bool,
+    last_thumbstick_direction: i32,
+}
+
+impl Console {
+    pub fn new(registry: CommandRegistry) -> Console {
+        Console {
+            registry,
+            output: VecDeque::new(),
+            quick_commands: DEFAULT_QUICK_COMMANDS
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            selected_quick_command: 0,
+            is_visible: false,
+            panel_transform: None,
+            pending_commands: Vec::new(),
+
+            was_menu_pressed: false,
+            was_trigger_pressed: false,
+            last_thumbstick_direction: 0,
+        }
+    }
+
+    ///
+    /// execute
+    ///
+    /// Parse and queue a line, printing errors to the console
+    pub fn execute(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+
+        self.print(format!("> {line}"));
+        if line.eq_ignore_ascii_case("help") {
+            let usages: Vec<&str> = self.registry.usages().collect();
+            for usage in usages {
+                self.print(format!("  {usage}"));
+            }
+            return;
+        }
+
+        match self.registry.parse(line) {
+            Ok(command) => {
+                self.pending_commands.push(command);
+                self.remember(line);
+            }
+            Err(err) => self.print(err),
+        }
+    }
+
+    pub fn print(&mut self, line: String) {
+        info!("console: {line}");
+        self.output.push_back(line);
+        while self.output.len() > MAX_OUTPUT_LINES {
+            self.output.pop_front();
+        }
+    }
+
+    pub fn take_commands(&mut self) -> Vec<Box<dyn Command>> {
+        std::mem::take(&mut self.pending_commands)
+    }
+
+    pub fn output(&self) -> impl Iterator<Item = &String> {
+        self.output.iter()
+    }
+
+    pub fn quick_commands(&self) -> &[String] {
+        &self.quick_commands
+    }
+
+    pub fn selected_quick_command(&self) -> usize {
+        self.selected_quick_command
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+
+    pub fn toggle_visible(&mut self) {
+        self.is_visible = !self.is_visible;
+        // Re-open in front of wherever the player is now
+        self.panel_transform = None;
+    }
+
+    pub fn panel_transform(&self) -> Option<Matrix4<f32>> {
+        self.panel_transform
+    }
+
+    ///
+    /// update
+    ///
+    /// Handle the VR controls, and place the panel in front of the player when it opens
+    pub fn update(
+        &mut self,
+        input_context: &InputContext,
+        player_position: Vector3<f32>,
+        player_rotation: Quaternion<f32>,
+    ) {
+        let is_menu_pressed = input_context.left_hand.menu_value > PRESS_THRESHOLD;
+        if is_menu_pressed && !self.was_menu_pressed {
+            self.toggle_visible();
+        }
+        self.was_menu_pressed = is_menu_pressed;
+
+        if !self.is_visible {
+            self.was_trigger_pressed = false;
+            self.last_thumbstick_direction = 0;
+            return;
+        }
+
+        if self.panel_transform.is_none() {
+            self.panel_transform = Some(panel_transform(
+                player_position,
+                player_rotation * input_context.head.rotation,
+            ));
+        }
+
+        let thumbstick_y = input_context.left_hand.thumbstick.y;
+        let direction = if thumbstick_y > PRESS_THRESHOLD {
+            -1
+        } else if thumbstick_y < -PRESS_THRESHOLD {
+            1
+        } else {
+            0
+        };
+        if direction != 0 && direction != self.last_thumbstick_direction {
+            let count = self.quick_commands.len() as i32;
+            self.selected_quick_command =
+                (self.selected_quick_command as i32 + direction).rem_euclid(count) as usize;
+        }
+        self.last_thumbstick_direction = direction;
+
+        let is_trigger_pressed = input_context.left_hand.trigger_value > PRESS_THRESHOLD;
+        if is_trigger_pressed && !self.was_trigger_pressed {
+            let line = self.quick_commands[self.selected_quick_command].clone();
+            self.execute(&line);
+        }
+        self.was_trigger_pressed = is_trigger_pressed;
+    }
+
+    // Keep successful commands at the top of the quick commands, most recent first
+    fn remember(&mut self, line: &str) {
+        self.quick_commands.retain(|c| c != line);
+        self.quick_commands.insert(0, line.to_owned());
+        self.quick_commands.truncate(MAX_QUICK_COMMANDS);
+        self.selected_quick_command = 0;
+    }
+}
+
+// The panel faces the player, upright, in the direction they are looking
+pub(crate) fn panel_transform(
+    player_position: Vector3<f32>,
+    look_rotation: Quaternion<f32>,
+) -> Matrix4<f32> {
+    let forward = look_rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
+    let flat_forward = vec3(forward.x, 0.0, forward.z);
+    let flat_forward = if flat_forward.magnitude2() > 0.0001 {
+        flat_forward.normalize()
+    } else {
+        vec3(0.0, 0.0, -1.0)
+    };
+
+    let yaw = Quaternion::from_angle_y(Rad(f32::atan2(-flat_forward.x, -flat_forward.z)));
+    let position = player_position + vec3(0.0, PANEL_HEIGHT, 0.0) + flat_forward * PANEL_DISTANCE;
+    Matrix4::from_translation(position) * Matrix4::from(yaw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execute_queues_commands_and_remembers_them() {
+        // Given
+        let mut console = Console::new(CommandRegistry::default());
+
+        // When
+        console.execute("toggle draw");
+        console.execute("kill");
+
+        // Then
+        assert_eq!(console.take_commands().len(), 1);
+        assert!(console.take_commands().is_empty());
+        assert_eq!(console.quick_commands()[0], "toggle draw");
+        assert_eq!(
+            console
+                .quick_commands()
+                .iter()
+                .filter(|c| *c == "toggle draw")
+                .count(),
+            1
+        );
+        let output: Vec<&String> = console.output().collect();
+        assert_eq!(output[0], "> toggle draw");
+        assert!(output[2].starts_with("missing arguments"));
+    }
+
+    #[test]
+    fn test_vr_controls_select_and_run_quick_commands() {
+        // Given - the console opened with the menu button
+        let mut console = Console::new(CommandRegistry::default());
+        let position = vec3(0.0, 0.0, 0.0);
+        let rotation = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        let mut input = InputContext::default();
+        input.left_hand.menu_value = 1.0;
+        console.update(&input, position, rotation);
+
+        // When - pushing the thumbstick down, and pulling the trigger
+        input.left_hand.menu_value = 0.0;
+        input.left_hand.thumbstick.y = -1.0;
+        console.update(&input, position, rotation);
+        input.left_hand.thumbstick.y = 0.0;
+        input.left_hand.trigger_value = 1.0;
+        console.update(&input, position, rotation);
+
+        // Then - the second default, 'toggle portals', is queued
+        assert!(console.is_visible());
+        assert!(console.panel_transform().is_some());
+        assert_eq!(console.take_commands().len(), 1);
+        assert_eq!(console.quick_commands()[0], "toggle portals");
+    }
+}
@@ -0,0 +1,29 @@
+use shipyard::World;
+use tracing::warn;
+
+use crate::scripts::Effect;
+
+use super::{resolve_template_id, Command};
+// GiveItemCommand
+#[derive(Debug)]
+pub struct GiveItemCommand {
+    item: String,
+}
+
+impl GiveItemCommand {
+    pub fn new(item: String) -> GiveItemCommand {
+        GiveItemCommand { item }
+    }
+}
+
+impl Command for GiveItemCommand {
+    fn execute(&self, world: &World) -> Effect {
+        match resolve_template_id(world, &self.item) {
+            Some(template_id) => Effect::GiveItem { template_id },
+            None => {
+                warn!("give: unknown item {}", self.item);
+                Effect::NoEffect
+            }
+        }
+    }
+}
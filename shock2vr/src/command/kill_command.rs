@@ -0,0 +1,29 @@
+use shipyard::World;
+use tracing::warn;
+
+use crate::scripts::Effect;
+
+use super::{resolve_entity, Command};
+// KillCommand
+#[derive(Debug)]
+pub struct KillCommand {
+    target: String,
+}
+
+impl KillCommand {
+    pub fn new(target: String) -> KillCommand {
+        KillCommand { target }
+    }
+}
+
+impl Command for KillCommand {
+    fn execute(&self, world: &World) -> Effect {
+        match resolve_entity(world, &self.target) {
+            Some(entity_id) => Effect::SlayEntity { entity_id },
+            None => {
+                warn!("kill: unknown target {}", self.target);
+                Effect::NoEffect
+            }
+        }
+    }
+}
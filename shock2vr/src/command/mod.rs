@@ -1,18 +1,76 @@
+mod award_nanites_command;
+mod console;
+mod give_item_command;
+mod kill_command;
 mod move_inventory_command;
+mod registry;
+mod send_message_command;
+mod set_head_collision_command;
+mod set_locomotion_command;
+mod set_quest_bit_command;
+mod spawn_entity_command;
 mod spawn_item_command;
+mod teleport_player_command;
+mod toggle_debug_flag_command;
 
+pub use award_nanites_command::*;
+pub use console::*;
+pub use give_item_command::*;
+pub use kill_command::*;
 pub use move_inventory_command::*;
-use shipyard::World;
+pub use registry::*;
+pub use send_message_command::*;
+pub use set_head_collision_command::*;
+pub use set_locomotion_command::*;
+pub use set_quest_bit_command::*;
+use shipyard::{EntityId, UniqueView, World};
+pub use spawn_entity_command::*;
 pub use spawn_item_command::*;
+pub use teleport_player_command::*;
+pub use toggle_debug_flag_command::*;
 
 use std::fmt;
 
-use crate::scripts::Effect;
+use crate::{
+    mission::{GlobalEntityMetadata, GlobalTemplateIdMap},
+    scripts::{script_util::get_first_entity_by_name, Effect},
+};
 
 pub trait Command: fmt::Debug {
     fn execute(&self, world: &World) -> Effect;
 }
 
+///
+/// resolve_template_id
+///
+/// The template id for an archetype - either the id itself, like '-22', or the name, like
+/// 'Laser Pistol'
+pub(crate) fn resolve_template_id(world: &World, archetype: &str) -> Option<i32> {
+    if let Ok(template_id) = archetype.parse::<i32>() {
+        return Some(template_id);
+    }
+
+    let metadata = world.borrow::<UniqueView<GlobalEntityMetadata>>().ok()?;
+    metadata
+        .0
+        .get(&archetype.to_ascii_lowercase())
+        .map(|m| m.template_id)
+}
+
+///
+/// resolve_entity
+///
+/// The entity for a target - either the template id of an object in the mission, or its symbolic
+/// name
+pub(crate) fn resolve_entity(world: &World, target: &str) -> Option<EntityId> {
+    if let Ok(template_id) = target.parse::<i32>() {
+        let template_id_map = world.borrow::<UniqueView<GlobalTemplateIdMap>>().ok()?;
+        return template_id_map.0.get(&template_id).map(|e| e.0);
+    }
+
+    get_first_entity_by_name(world, target)
+}
+
 // SaveCommand
 #[derive(Debug)]
 pub struct SaveCommand {}
@@ -50,19 +108,22 @@ impl Command for LoadCommand {
 }
 
 #[derive(Debug)]
-pub struct TransitionLevelCommand {}
+pub struct TransitionLevelCommand {
+    level_file: String,
+    loc: Option<i32>,
+}
 
 impl TransitionLevelCommand {
-    pub fn new() -> TransitionLevelCommand {
-        TransitionLevelCommand {}
+    pub fn new(level_file: String, loc: Option<i32>) -> TransitionLevelCommand {
+        TransitionLevelCommand { level_file, loc }
     }
 }
 
 impl Command for TransitionLevelCommand {
     fn execute(&self, _world: &World) -> Effect {
         Effect::GlobalEffect(crate::scripts::GlobalEffect::TransitionLevel {
-            level_file: "medsci1.mis".to_owned(),
-            loc: None,
+            level_file: self.level_file.clone(),
+            loc: self.loc,
         })
     }
 }
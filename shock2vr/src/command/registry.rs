@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+
+use crate::{head_collision::HeadCollisionMode, locomotion::LocomotionSettings, DebugFlag};
+
+use super::{
+    parse_quest_bit_value, AwardNanitesCommand, Command, GiveItemCommand, KillCommand,
+    SendMessageCommand, SetHeadCollisionCommand, SetLocomotionCommand, SetQuestBitCommand,
+    SpawnEntityCommand, ToggleDebugFlagCommand, TransitionLevelCommand,
+};
+
+pub type ParseCommandFn = fn(&[String]) -> Result<Box<dyn Command>, String>;
+
+struct CommandDefinition {
+    usage: &'static str,
+    parse: ParseCommandFn,
+}
+
+///
+/// CommandRegistry
+///
+/// Commands that can be typed into the console, by name. Each command parses its arguments into a
+/// Command, which runs on the next update like the commands from key bindings.
+pub struct CommandRegistry {
+    commands: BTreeMap<&'static str, CommandDefinition>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> CommandRegistry {
+        CommandRegistry {
+            commands: BTreeMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, name: &'static str, usage: &'static str, parse: ParseCommandFn) {
+        self.commands
+            .insert(name, CommandDefinition { usage, parse });
+    }
+
+    pub fn parse(&self, line: &str) -> Result<Box<dyn Command>, String> {
+        let tokens = tokenize(line);
+        let (name, args) = tokens
+            .split_first()
+            .ok_or_else(|| "empty command".to_owned())?;
+
+        let definition = self
+            .commands
+            .get(name.to_ascii_lowercase().as_str())
+            .ok_or_else(|| format!("unknown command: {name}"))?;
+
+        (definition.parse)(args).map_err(|err| format!("{err} - usage: {}", definition.usage))
+    }
+
+    pub fn usages(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.commands.values().map(|definition| definition.usage)
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        let mut registry = CommandRegistry::new();
+        registry.register("spawn", "spawn <archetype>", parse_spawn);
+        registry.register("level", "level <mis> [marker]", parse_level);
+        registry.register("qb", "qb set <name> <value>", parse_quest_bit);
+        registry.register("give", "give <item>", parse_give);
+        registry.register("nanites", "nanites <amount>", parse_nanites);
+        registry.register("kill", "kill <target>", parse_kill);
+        registry.register("send", "send <obj> <message>", parse_send);
+        registry.register(
+            "toggle",
+            "toggle <physics|draw|portals|particles>",
+            parse_toggle,
+        );
+        registry.register(
+            "locomotion",
+            "locomotion <smooth|comfort|teleport>",
+            parse_locomotion,
+        );
+        registry.register(
+            "headcollision",
+            "headcollision <pushback|fade>",
+            parse_head_collision,
+        );
+        registry
+    }
+}
+
+///
+/// tokenize
+///
+/// Split a line on whitespace - double quotes group names with spaces, like "Laser Pistol"
+pub fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_token = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_token = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_token {
+                    tokens.push(std::mem::take(&mut current));
+                    has_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_token = true;
+            }
+        }
+    }
+
+    if has_token {
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn expect_args(args: &[String], min: usize, max: usize) -> Result<(), String> {
+    if args.len() < min {
+        Err("missing arguments".to_owned())
+    } else if args.len() > max {
+        Err("too many arguments".to_owned())
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_spawn(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    Ok(Box::new(SpawnEntityCommand::new(args[0].clone())))
+}
+
+fn parse_level(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 2)?;
+    let level_file = if args[0].to_ascii_lowercase().ends_with(".mis") {
+        args[0].clone()
+    } else {
+        format!("{}.mis", args[0])
+    };
+
+    let loc = match args.get(1) {
+        None => None,
+        Some(marker) => Some(
+            marker
+                .parse::<i32>()
+                .map_err(|_| format!("invalid marker: {marker}"))?,
+        ),
+    };
+
+    Ok(Box::new(TransitionLevelCommand::new(level_file, loc)))
+}
+
+fn parse_quest_bit(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 3, 3)?;
+    if !args[0].eq_ignore_ascii_case("set") {
+        return Err(format!("unknown qb command: {}", args[0]));
+    }
+
+    let value = parse_quest_bit_value(&args[2])
+        .ok_or_else(|| format!("invalid quest bit value: {}", args[2]))?;
+    Ok(Box::new(SetQuestBitCommand::new(args[1].clone(), value)))
+}
+
+fn parse_give(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    Ok(Box::new(GiveItemCommand::new(args[0].clone())))
+}
+
+fn parse_nanites(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    let amount = args[0]
+        .parse::<u32>()
+        .map_err(|_| format!("invalid amount: {}", args[0]))?;
+    Ok(Box::new(AwardNanitesCommand::new(amount)))
+}
+
+fn parse_kill(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    Ok(Box::new(KillCommand::new(args[0].clone())))
+}
+
+fn parse_send(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 2, 2)?;
+    Ok(Box::new(SendMessageCommand::new(
+        args[0].clone(),
+        args[1].clone(),
+    )))
+}
+
+fn parse_toggle(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    let flag =
+        DebugFlag::from_name(&args[0]).ok_or_else(|| format!("unknown debug flag: {}", args[0]))?;
+    Ok(Box::new(ToggleDebugFlagCommand::new(flag)))
+}
+
+fn parse_locomotion(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    let settings = LocomotionSettings::from_preset(&args[0])
+        .ok_or_else(|| format!("unknown locomotion preset: {}", args[0]))?;
+    Ok(Box::new(SetLocomotionCommand::new(settings)))
+}
+
+fn parse_head_collision(args: &[String]) -> Result<Box<dyn Command>, String> {
+    expect_args(args, 1, 1)?;
+    let mode = HeadCollisionMode::from_name(&args[0])
+        .ok_or_else(|| format!("unknown head collision mode: {}", args[0]))?;
+    Ok(Box::new(SetHeadCollisionCommand::new(mode)))
+}
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::QuestBitValue;
+    use shipyard::World;
+
+    use super::*;
+    use crate::scripts::{Effect, GlobalEffect};
+
+    #[test]
+    fn test_tokenize_groups_quoted_names() {
+        // Given
+        let line = r#"  give "Laser Pistol"   now"#;
+
+        // When
+        let tokens = tokenize(line);
+
+        // Then
+        assert_eq!(tokens, vec!["give", "Laser Pistol", "now"]);
+    }
+
+    #[test]
+    fn test_parse_reports_unknown_commands_and_usage() {
+        // Given
+        let registry = CommandRegistry::default();
+
+        // When
+        let unknown = registry.parse("fly away").unwrap_err();
+        let missing_args = registry.parse("send door").unwrap_err();
+
+        // Then
+        assert_eq!(unknown, "unknown command: fly");
+        assert_eq!(
+            missing_args,
+            "missing arguments - usage: send <obj> <message>"
+        );
+    }
+
+    #[test]
+    fn test_parse_quest_bit_command() {
+        // Given
+        let registry = CommandRegistry::default();
+        let world = World::new();
+
+        // When
+        let effect = registry
+            .parse("qb set note_read complete")
+            .unwrap()
+            .execute(&world);
+
+        // Then
+        assert!(matches!(
+            effect,
+            Effect::SetQuestBit { quest_bit_name, quest_bit_value }
+                if quest_bit_name == "note_read" && quest_bit_value == QuestBitValue::COMPLETE
+        ));
+    }
+
+    #[test]
+    fn test_parse_level_command_with_marker() {
+        // Given
+        let registry = CommandRegistry::default();
+        let world = World::new();
+
+        // When
+        let effect = registry.parse("LEVEL medsci2 12").unwrap().execute(&world);
+
+        // Then
+        assert!(matches!(
+            effect,
+            Effect::GlobalEffect(GlobalEffect::TransitionLevel { level_file, loc: Some(12) })
+                if level_file == "medsci2.mis"
+        ));
+    }
+
+    #[test]
+    fn test_parse_toggle_command() {
+        // Given
+        let registry = CommandRegistry::default();
+        let world = World::new();
+
+        // When
+        let effect = registry.parse("toggle portals").unwrap().execute(&world);
+        let unknown = registry.parse("toggle fog");
+
+        // Then
+        assert!(matches!(
+            effect,
+            Effect::GlobalEffect(GlobalEffect::ToggleDebugFlag(DebugFlag::Portals))
+        ));
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_parse_head_collision_command() {
+        // Given
+        let registry = CommandRegistry::default();
+        let world = World::new();
+
+        // When
+        let effect = registry
+            .parse("headcollision fade")
+            .unwrap()
+            .execute(&world);
+        let unknown = registry.parse("headcollision bounce");
+
+        // Then
+        assert!(matches!(
+            effect,
+            Effect::GlobalEffect(GlobalEffect::SetHeadCollision(HeadCollisionMode::Fade))
+        ));
+        assert!(unknown.is_err());
+    }
+}
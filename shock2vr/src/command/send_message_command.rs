@@ -0,0 +1,65 @@
+use shipyard::{EntityId, UniqueView, World};
+use tracing::warn;
+
+use crate::{
+    scripts::{Effect, Message, MessagePayload},
+    PlayerInfo,
+};
+
+use super::{resolve_entity, Command};
+// SendMessageCommand
+#[derive(Debug)]
+pub struct SendMessageCommand {
+    target: String,
+    message: String,
+}
+
+impl SendMessageCommand {
+    pub fn new(target: String, message: String) -> SendMessageCommand {
+        SendMessageCommand { target, message }
+    }
+}
+
+///
+/// message_payload
+///
+/// The payload for a message name. Names that aren't built-in messages are sent as AI signals,
+/// the way the dark engine passes arbitrary messages to scripts.
+fn message_payload(message: &str, player_entity_id: EntityId) -> MessagePayload {
+    match message.to_ascii_lowercase().as_str() {
+        "frob" => MessagePayload::Frob,
+        "turnon" => MessagePayload::TurnOn {
+            from: player_entity_id,
+        },
+        "turnoff" => MessagePayload::TurnOff {
+            from: player_entity_id,
+        },
+        "slay" => MessagePayload::Slay,
+        "recharge" => MessagePayload::Recharge,
+        "hold" => MessagePayload::Hold,
+        "drop" => MessagePayload::Drop,
+        "triggerpull" => MessagePayload::TriggerPull,
+        "triggerrelease" => MessagePayload::TriggerRelease,
+        _ => MessagePayload::Signal {
+            name: message.to_owned(),
+        },
+    }
+}
+
+impl Command for SendMessageCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let maybe_entity_id = resolve_entity(world, &self.target);
+        if maybe_entity_id.is_none() {
+            warn!("send: unknown target {}", self.target);
+            return Effect::NoEffect;
+        }
+
+        let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+        Effect::Send {
+            msg: Message {
+                to: maybe_entity_id.unwrap(),
+                payload: message_payload(&self.message, player_entity_id),
+            },
+        }
+    }
+}
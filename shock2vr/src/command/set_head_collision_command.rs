@@ -0,0 +1,25 @@
+use shipyard::World;
+
+use crate::{
+    head_collision::HeadCollisionMode,
+    scripts::{Effect, GlobalEffect},
+};
+
+use super::Command;
+// SetHeadCollisionCommand
+#[derive(Debug)]
+pub struct SetHeadCollisionCommand {
+    mode: HeadCollisionMode,
+}
+
+impl SetHeadCollisionCommand {
+    pub fn new(mode: HeadCollisionMode) -> SetHeadCollisionCommand {
+        SetHeadCollisionCommand { mode }
+    }
+}
+
+impl Command for SetHeadCollisionCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::SetHeadCollision(self.mode))
+    }
+}
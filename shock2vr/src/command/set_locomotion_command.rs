@@ -0,0 +1,25 @@
+use shipyard::World;
+
+use crate::{
+    locomotion::LocomotionSettings,
+    scripts::{Effect, GlobalEffect},
+};
+
+use super::Command;
+// SetLocomotionCommand
+#[derive(Debug)]
+pub struct SetLocomotionCommand {
+    settings: LocomotionSettings,
+}
+
+impl SetLocomotionCommand {
+    pub fn new(settings: LocomotionSettings) -> SetLocomotionCommand {
+        SetLocomotionCommand { settings }
+    }
+}
+
+impl Command for SetLocomotionCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::SetLocomotion(self.settings.clone()))
+    }
+}
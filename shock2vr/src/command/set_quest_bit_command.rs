@@ -0,0 +1,43 @@
+use dark::properties::QuestBitValue;
+use shipyard::World;
+
+use crate::scripts::Effect;
+
+use super::Command;
+// SetQuestBitCommand
+#[derive(Debug)]
+pub struct SetQuestBitCommand {
+    quest_bit_name: String,
+    quest_bit_value: QuestBitValue,
+}
+
+impl SetQuestBitCommand {
+    pub fn new(quest_bit_name: String, quest_bit_value: QuestBitValue) -> SetQuestBitCommand {
+        SetQuestBitCommand {
+            quest_bit_name,
+            quest_bit_value,
+        }
+    }
+}
+
+///
+/// parse_quest_bit_value
+///
+/// Either the raw value, or the name of the state - 'unknown', 'incomplete' or 'complete'
+pub fn parse_quest_bit_value(value: &str) -> Option<QuestBitValue> {
+    match value.to_ascii_lowercase().as_str() {
+        "unknown" => Some(QuestBitValue::UNKNOWN),
+        "incomplete" => Some(QuestBitValue::INCOMPLETE),
+        "complete" => Some(QuestBitValue::COMPLETE),
+        _ => value.parse::<u32>().ok().and_then(QuestBitValue::from_bits),
+    }
+}
+
+impl Command for SetQuestBitCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::SetQuestBit {
+            quest_bit_name: self.quest_bit_name.clone(),
+            quest_bit_value: self.quest_bit_value,
+        }
+    }
+}
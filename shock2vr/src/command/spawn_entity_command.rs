@@ -0,0 +1,43 @@
+use cgmath::{vec3, Matrix4, SquareMatrix};
+
+use dark::SCALE_FACTOR;
+use shipyard::{UniqueView, World};
+use tracing::warn;
+
+use crate::{
+    mission::entity_creator::CreateEntityOptions, scripts::Effect, util::vec3_to_point3, PlayerInfo,
+};
+
+use super::{resolve_template_id, Command};
+// SpawnEntityCommand
+#[derive(Debug)]
+pub struct SpawnEntityCommand {
+    archetype: String,
+}
+
+impl SpawnEntityCommand {
+    pub fn new(archetype: String) -> SpawnEntityCommand {
+        SpawnEntityCommand { archetype }
+    }
+}
+
+impl Command for SpawnEntityCommand {
+    fn execute(&self, world: &World) -> Effect {
+        let maybe_template_id = resolve_template_id(world, &self.archetype);
+        if maybe_template_id.is_none() {
+            warn!("spawn: unknown archetype {}", self.archetype);
+            return Effect::NoEffect;
+        }
+
+        let player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+        let forward = player.rotation * vec3(0.0, 2.5 / SCALE_FACTOR, -5.0 / SCALE_FACTOR);
+
+        Effect::CreateEntity {
+            template_id: maybe_template_id.unwrap(),
+            position: vec3_to_point3(player.pos + forward),
+            orientation: player.rotation,
+            root_transform: Matrix4::identity(),
+            options: CreateEntityOptions::default(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use cgmath::Vector3;
+use shipyard::World;
+
+use crate::scripts::Effect;
+
+use super::Command;
+// TeleportPlayerCommand
+#[derive(Debug)]
+pub struct TeleportPlayerCommand {
+    position: Vector3<f32>,
+}
+
+impl TeleportPlayerCommand {
+    pub fn new(position: Vector3<f32>) -> TeleportPlayerCommand {
+        TeleportPlayerCommand { position }
+    }
+}
+
+impl Command for TeleportPlayerCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::SetPlayerPosition {
+            position: self.position,
+            is_teleport: true,
+        }
+    }
+}
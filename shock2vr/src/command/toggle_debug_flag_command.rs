@@ -0,0 +1,25 @@
+use shipyard::World;
+
+use crate::{
+    scripts::{Effect, GlobalEffect},
+    DebugFlag,
+};
+
+use super::Command;
+// ToggleDebugFlagCommand
+#[derive(Debug)]
+pub struct ToggleDebugFlagCommand {
+    flag: DebugFlag,
+}
+
+impl ToggleDebugFlagCommand {
+    pub fn new(flag: DebugFlag) -> ToggleDebugFlagCommand {
+        ToggleDebugFlagCommand { flag }
+    }
+}
+
+impl Command for ToggleDebugFlagCommand {
+    fn execute(&self, _world: &World) -> Effect {
+        Effect::GlobalEffect(GlobalEffect::ToggleDebugFlag(self.flag))
+    }
+}
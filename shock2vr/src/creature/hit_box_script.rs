@@ -37,12 +37,29 @@ impl Script for HitBoxScript {
         msg: &MessagePayload,
     ) -> Effect {
         match msg {
-            MessagePayload::Damage { amount } => Effect::Send {
-                msg: Message {
-                    to: self.parent_entity_id,
-                    payload: MessagePayload::Damage { amount: *amount },
-                },
-            },
+            MessagePayload::Damage {
+                amount,
+                damage_type,
+                source,
+                hit,
+            } => {
+                let multiplier = self.hit_box_type.damage_multiplier();
+                if multiplier <= 0.0 {
+                    return Effect::NoEffect;
+                }
+
+                Effect::Send {
+                    msg: Message {
+                        to: self.parent_entity_id,
+                        payload: MessagePayload::Damage {
+                            amount: amount * multiplier,
+                            damage_type: *damage_type,
+                            source: *source,
+                            hit: *hit,
+                        },
+                    },
+                }
+            }
             _ => Effect::NoEffect,
         }
     }
@@ -33,6 +33,22 @@ pub enum HitBoxType {
     NoDamage,
 }
 
+impl HitBoxType {
+    ///
+    /// damage_multiplier
+    ///
+    /// Scale for damage landing on this part of the body - headshots hurt the most
+    pub fn damage_multiplier(&self) -> f32 {
+        match self {
+            HitBoxType::Head => 2.0,
+            HitBoxType::Body => 1.0,
+            HitBoxType::Limb => 0.75,
+            HitBoxType::Extremity => 0.5,
+            HitBoxType::NoDamage => 0.0,
+        }
+    }
+}
+
 pub struct HitBoxManager {
     // Map entity to all the corresponding entities for their joints
     pub hit_boxes: HashMap<EntityId, HashMap<JointId, EntityId>>,
@@ -1,6 +1,8 @@
 mod creature_definitions;
 mod hit_box_script;
 mod hit_boxes;
+mod speech;
 
 pub use creature_definitions::*;
 pub use hit_boxes::*;
+pub use speech::*;
@@ -0,0 +1,194 @@
+use std::{collections::HashMap, time::Duration};
+
+use dark::{
+    gamesys::Gamesys,
+    importers::AUDIO_IMPORTER,
+    properties::{PropPosition, PropSpeechVoice},
+    EnvSoundQuery,
+};
+use engine::{
+    assets::asset_cache::AssetCache,
+    audio::{self, AudioContext, AudioHandle},
+};
+use shipyard::{EntityId, Get, UniqueView, View, World};
+use tracing::info;
+
+use crate::time::Time;
+
+// Minimum time between non-critical lines for a single voice, so a group of
+// creatures sharing a voice doesn't talk over each other
+const VOICE_COOLDOWN: Duration = Duration::from_secs(5);
+
+// Concepts that should always be heard, regardless of cooldowns or current speech
+const INTERRUPTING_CONCEPTS: [&str; 2] = ["comdieloud", "comdiesoft"];
+
+// What to do with a new line of speech
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SpeechAction {
+    // Drop it - the speaker is mid-line, or the voice has spoken too recently
+    Ignore,
+    Play,
+    // Cut off whatever the speaker is saying, and play it
+    Interrupt,
+}
+
+///
+/// SpeechManager
+///
+/// Resolves speech concepts (ie, 'spotplayer') for a creature into a sample via the speech database,
+/// and plays it positionally from the creature - following it as it moves.
+pub struct SpeechManager {
+    speaker_to_handle: HashMap<EntityId, AudioHandle>,
+    voice_to_next_available_time: HashMap<String, Duration>,
+}
+
+impl SpeechManager {
+    pub fn new() -> SpeechManager {
+        SpeechManager {
+            speaker_to_handle: HashMap::new(),
+            voice_to_next_available_time: HashMap::new(),
+        }
+    }
+
+    pub fn speak(
+        &mut self,
+        world: &World,
+        gamesys: &Gamesys,
+        asset_cache: &mut AssetCache,
+        audio_context: &mut AudioContext<EntityId, String>,
+        entity_id: EntityId,
+        concept: &str,
+    ) {
+        let (voice, position) = {
+            let v_voice = world.borrow::<View<PropSpeechVoice>>().unwrap();
+            let v_position = world.borrow::<View<PropPosition>>().unwrap();
+
+            match (v_voice.get(entity_id), v_position.get(entity_id)) {
+                (Ok(voice), Ok(position)) => (voice.0.to_ascii_lowercase(), position.position),
+                _ => return,
+            }
+        };
+
+        let now = world.borrow::<UniqueView<Time>>().unwrap().total;
+        let is_speaking = self
+            .speaker_to_handle
+            .get(&entity_id)
+            .map_or(false, |handle| audio::is_playing(audio_context, handle));
+
+        if self.action(&voice, concept, now, is_speaking) == SpeechAction::Ignore {
+            return;
+        }
+
+        let maybe_sample = gamesys.get_random_speech(&voice, concept, &EnvSoundQuery::new());
+
+        if let Some(sample) = maybe_sample {
+            if let Some(previous_handle) = self.speaker_to_handle.remove(&entity_id) {
+                audio::stop_audio(audio_context, previous_handle);
+            }
+
+            info!(
+                "speech - entity: {:?} voice: {} concept: {} sample: {}",
+                entity_id, voice, concept, sample
+            );
+
+            let audio_clip = asset_cache.get(&AUDIO_IMPORTER, &format!("{sample}.wav"));
+            let handle = AudioHandle::new();
+            audio::play_spatial_audio(audio_context, position, handle.clone(), None, audio_clip);
+
+            self.speaker_to_handle.insert(entity_id, handle);
+            self.voice_to_next_available_time
+                .insert(voice, now + VOICE_COOLDOWN);
+        }
+    }
+
+    ///
+    /// update
+    ///
+    /// Keep the lines being spoken at their speakers, and forget the ones that have finished
+    pub fn update(&mut self, world: &World, audio_context: &mut AudioContext<EntityId, String>) {
+        let v_position = world.borrow::<View<PropPosition>>().unwrap();
+
+        self.speaker_to_handle
+            .retain(|_, handle| audio::is_playing(audio_context, handle));
+        for (entity_id, handle) in &self.speaker_to_handle {
+            if let Ok(position) = v_position.get(*entity_id) {
+                audio::set_emitter_position(audio_context, handle, position.position);
+            }
+        }
+    }
+
+    pub fn remove_speaker(&mut self, entity_id: EntityId) {
+        self.speaker_to_handle.remove(&entity_id);
+    }
+
+    // Only one line at a time per speaker, and a voice can't chatter constantly - except for the
+    // lines that always need to be heard, like death barks, which cut off anything else
+    fn action(&self, voice: &str, concept: &str, now: Duration, is_speaking: bool) -> SpeechAction {
+        if INTERRUPTING_CONCEPTS.contains(&concept) {
+            return if is_speaking {
+                SpeechAction::Interrupt
+            } else {
+                SpeechAction::Play
+            };
+        }
+
+        let is_cooling_down = self
+            .voice_to_next_available_time
+            .get(voice)
+            .map_or(false, |next_available_time| now < *next_available_time);
+
+        if is_speaking || is_cooling_down {
+            SpeechAction::Ignore
+        } else {
+            SpeechAction::Play
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_voice_cooldown() {
+        // Given - a hybrid that just spotted the player
+        let mut speech = SpeechManager::new();
+        let start = Duration::from_secs(10);
+        speech
+            .voice_to_next_available_time
+            .insert("hybrid".to_owned(), start + VOICE_COOLDOWN);
+
+        // When
+        let soon_after = start + Duration::from_secs(1);
+        let same_voice = speech.action("hybrid", "spotplayer", soon_after, false);
+        let other_voice = speech.action("robot", "spotplayer", soon_after, false);
+        let after_cooldown = speech.action("hybrid", "spotplayer", start + VOICE_COOLDOWN, false);
+
+        // Then - other hybrids stay quiet for a while, but other voices don't have to
+        assert_eq!(same_voice, SpeechAction::Ignore);
+        assert_eq!(other_voice, SpeechAction::Play);
+        assert_eq!(after_cooldown, SpeechAction::Play);
+    }
+
+    #[test]
+    fn test_death_barks_cut_off_current_speech() {
+        // Given - a hybrid mid-line, with its voice cooling down
+        let mut speech = SpeechManager::new();
+        let now = Duration::from_secs(10);
+        speech
+            .voice_to_next_available_time
+            .insert("hybrid".to_owned(), now + VOICE_COOLDOWN);
+
+        // When
+        let chatter = speech.action("hybrid", "spotplayer", now, true);
+        let loud_death = speech.action("hybrid", "comdieloud", now, true);
+        let soft_death = speech.action("hybrid", "comdiesoft", now, true);
+        let quiet_death = speech.action("hybrid", "comdiesoft", now, false);
+
+        // Then
+        assert_eq!(chatter, SpeechAction::Ignore);
+        assert_eq!(loud_death, SpeechAction::Interrupt);
+        assert_eq!(soft_death, SpeechAction::Interrupt);
+        assert_eq!(quiet_death, SpeechAction::Play);
+    }
+}
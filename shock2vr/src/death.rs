@@ -0,0 +1,195 @@
+// Death
+//
+// What happens once the player's hit points run out, and there's no resurrection station to
+// rebuild them. Input stops, and the view closes in, red, while the player goes down. Then a game
+// over panel offers to load the last save, or restart the level - picked with either thumbstick,
+// and chosen with either trigger.
+
+use cgmath::{vec3, Matrix4, Quaternion, SquareMatrix, Vector3};
+use engine::scene::SceneObject;
+
+use crate::{command::panel_transform, input_context::InputContext, locomotion::ring_vertices};
+
+// Nanites it costs to be rebuilt at a resurrection station
+pub const RESURRECTION_NANITE_COST: u32 = 10;
+
+// How long the view takes to close in, before the game over panel
+const DEATH_SEQUENCE_IN_SECONDS: f32 = 3.0;
+
+// How far in front of the eye the red is drawn, and how wide the opening in it starts
+const FADE_DISTANCE: f32 = 0.12;
+const FADE_MAX_OPENING: f32 = 0.2;
+const FADE_OUTER_RADIUS: f32 = 1.0;
+
+// Analog values past this count as pressed
+const PRESS_THRESHOLD: f32 = 0.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathChoice {
+    LoadLastSave,
+    RestartLevel,
+}
+
+impl DeathChoice {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DeathChoice::LoadLastSave => "Load last save",
+            DeathChoice::RestartLevel => "Restart level",
+        }
+    }
+}
+
+pub struct Death {
+    time_in_seconds: f32,
+    choices: Vec<DeathChoice>,
+    selected_choice: usize,
+    panel_transform: Option<Matrix4<f32>>,
+
+    was_trigger_pressed: bool,
+    last_thumbstick_direction: i32,
+}
+
+impl Death {
+    pub fn new(has_save: bool) -> Death {
+        let choices = if has_save {
+            vec![DeathChoice::LoadLastSave, DeathChoice::RestartLevel]
+        } else {
+            vec![DeathChoice::RestartLevel]
+        };
+
+        Death {
+            time_in_seconds: 0.0,
+            choices,
+            selected_choice: 0,
+            panel_transform: None,
+
+            was_trigger_pressed: false,
+            last_thumbstick_direction: 0,
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.time_in_seconds >= DEATH_SEQUENCE_IN_SECONDS
+    }
+
+    pub fn choices(&self) -> &[DeathChoice] {
+        &self.choices
+    }
+
+    pub fn selected_choice(&self) -> usize {
+        self.selected_choice
+    }
+
+    pub fn panel_transform(&self) -> Option<Matrix4<f32>> {
+        self.panel_transform
+    }
+
+    ///
+    /// update
+    ///
+    /// Play out the death sequence, then handle the game over panel. Returns the choice, once the
+    /// player has made one.
+    pub fn update(
+        &mut self,
+        input_context: &InputContext,
+        player_position: Vector3<f32>,
+        player_rotation: Quaternion<f32>,
+        delta_time: f32,
+    ) -> Option<DeathChoice> {
+        self.time_in_seconds += delta_time;
+
+        // Still tracked during the sequence, so holding the trigger while dying doesn't pick
+        let is_trigger_pressed = input_context.left_hand.trigger_value > PRESS_THRESHOLD
+            || input_context.right_hand.trigger_value > PRESS_THRESHOLD;
+        let is_new_press = is_trigger_pressed && !self.was_trigger_pressed;
+        self.was_trigger_pressed = is_trigger_pressed;
+
+        if !self.is_game_over() {
+            return None;
+        }
+
+        if self.panel_transform.is_none() {
+            self.panel_transform = Some(panel_transform(
+                player_position,
+                player_rotation * input_context.head.rotation,
+            ));
+        }
+
+        let thumbstick_y =
+            input_context.left_hand.thumbstick.y + input_context.right_hand.thumbstick.y;
+        let direction = if thumbstick_y > PRESS_THRESHOLD {
+            -1
+        } else if thumbstick_y < -PRESS_THRESHOLD {
+            1
+        } else {
+            0
+        };
+        if direction != 0 && direction != self.last_thumbstick_direction {
+            let count = self.choices.len() as i32;
+            self.selected_choice =
+                (self.selected_choice as i32 + direction).rem_euclid(count) as usize;
+        }
+        self.last_thumbstick_direction = direction;
+
+        if is_new_press {
+            Some(self.choices[self.selected_choice])
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// render_fade
+    ///
+    /// Red, closing in around the eye during the death sequence
+    pub fn render_fade(&self, view: Matrix4<f32>) -> Option<SceneObject> {
+        if self.is_game_over() {
+            return None;
+        }
+
+        let eye_transform = view.invert()?;
+        let amount = self.time_in_seconds / DEATH_SEQUENCE_IN_SECONDS;
+        let opening = FADE_MAX_OPENING * (1.0 - amount);
+
+        let mut fade = SceneObject::new(
+            engine::scene::color_material::create(vec3(0.4, 0.0, 0.0)),
+            Box::new(engine::scene::mesh::create(ring_vertices(
+                opening,
+                FADE_OUTER_RADIUS,
+            ))),
+        );
+        fade.set_transform(
+            eye_transform * Matrix4::from_translation(vec3(0.0, 0.0, -FADE_DISTANCE)),
+        );
+        Some(fade)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choice_waits_for_the_game_over_panel() {
+        // Given - the player died holding the trigger
+        let mut death = Death::new(true);
+        let mut input_context = InputContext::default();
+        input_context.right_hand.trigger_value = 1.0;
+        let position = vec3(0.0, 0.0, 0.0);
+        let rotation = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+
+        // When
+        let while_dying = death.update(&input_context, position, rotation, 1.0);
+        let still_held = death.update(&input_context, position, rotation, 5.0);
+        input_context.right_hand.trigger_value = 0.0;
+        death.update(&input_context, position, rotation, 0.1);
+        input_context.right_hand.thumbstick.y = -1.0;
+        input_context.right_hand.trigger_value = 1.0;
+        let chosen = death.update(&input_context, position, rotation, 0.1);
+
+        // Then
+        assert_eq!(while_dying, None);
+        assert_eq!(still_held, None);
+        assert_eq!(chosen, Some(DeathChoice::RestartLevel));
+    }
+}
@@ -0,0 +1,175 @@
+// Head collision
+//
+// In VR, the player can physically lean through walls - only the body collides as it moves. Each
+// frame, the head is sphere-cast against the level, from above the body out to where the headset
+// is. When the head goes into a wall, the player is either pushed back out of it, or the view fades
+// to black - so walls and doors can't be peeked through, into areas the portal culling has hidden.
+
+use cgmath::{
+    point3, vec3, InnerSpace, Matrix4, Quaternion, Rotation, SquareMatrix, Vector3, Zero,
+};
+use engine::scene::SceneObject;
+
+use crate::{
+    locomotion::ring_vertices,
+    physics::{InternalCollisionGroups, PhysicsWorld},
+};
+
+// Size of the head, for the sphere cast
+const HEAD_RADIUS: f32 = 0.15;
+
+// How far the head can go into a wall before the view is completely black
+const FADE_DEPTH: f32 = 0.1;
+
+// How quickly the view fades back in, once the head is out of the wall, per second
+const FADE_IN_SPEED: f32 = 4.0;
+
+// How far in front of the eye the fade is drawn - past the near plane, but in front of the
+// comfort vignette
+const FADE_DISTANCE: f32 = 0.12;
+
+// Radius of the opening in the fade, while the head is barely in the wall
+const FADE_MAX_OPENING: f32 = 0.16;
+const FADE_OUTER_RADIUS: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeadCollisionMode {
+    // Move the player back, so the head is out of the wall
+    PushBack,
+    // Fade the view to black, the further the head is in the wall
+    Fade,
+}
+
+impl HeadCollisionMode {
+    pub fn from_name(name: &str) -> Option<HeadCollisionMode> {
+        match name.to_ascii_lowercase().as_str() {
+            "pushback" | "push_back" => Some(HeadCollisionMode::PushBack),
+            "fade" => Some(HeadCollisionMode::Fade),
+            _ => None,
+        }
+    }
+}
+
+pub struct HeadCollision {
+    mode: HeadCollisionMode,
+    fade_amount: f32,
+}
+
+impl HeadCollision {
+    pub fn new(mode: HeadCollisionMode) -> HeadCollision {
+        HeadCollision {
+            mode,
+            fade_amount: 0.0,
+        }
+    }
+
+    pub fn set_mode(&mut self, mode: HeadCollisionMode) {
+        self.mode = mode;
+    }
+
+    ///
+    /// update
+    ///
+    /// Check the head, at a position relative to the floor of the play space, against the level.
+    /// Returns how far to move the player to push the head back out of a wall.
+    pub fn update(
+        &mut self,
+        head_position: Vector3<f32>,
+        player_position: Vector3<f32>,
+        player_rotation: Quaternion<f32>,
+        physics: &PhysicsWorld,
+        delta_time: f32,
+    ) -> Vector3<f32> {
+        // The head is cast out sideways, at its own height - it only goes through walls by leaning
+        let start = player_position + vec3(0.0, head_position.y, 0.0);
+        let head = player_position + player_rotation.rotate_vector(head_position);
+        let lean = head - start;
+        let lean_distance = lean.magnitude();
+
+        let depth = if lean_distance > f32::EPSILON {
+            physics
+                .sphere_cast(
+                    point3(start.x, start.y, start.z),
+                    lean,
+                    HEAD_RADIUS,
+                    lean_distance,
+                    InternalCollisionGroups::WORLD | InternalCollisionGroups::ENTITY,
+                )
+                .map_or(0.0, |distance| lean_distance - distance)
+        } else {
+            0.0
+        };
+
+        match self.mode {
+            HeadCollisionMode::PushBack => {
+                self.fade_amount = 0.0;
+                if depth > 0.0 {
+                    -lean / lean_distance * depth
+                } else {
+                    Vector3::zero()
+                }
+            }
+            HeadCollisionMode::Fade => {
+                self.fade_amount = fade_towards(self.fade_amount, depth / FADE_DEPTH, delta_time);
+                Vector3::zero()
+            }
+        }
+    }
+
+    ///
+    /// render_fade
+    ///
+    /// Black, closing in around the eye while the head is in a wall
+    pub fn render_fade(&self, view: Matrix4<f32>) -> Option<SceneObject> {
+        if self.fade_amount < 0.01 {
+            return None;
+        }
+
+        let eye_transform = view.invert()?;
+        let opening = FADE_MAX_OPENING * (1.0 - self.fade_amount);
+
+        let mut fade = SceneObject::new(
+            engine::scene::color_material::create(vec3(0.0, 0.0, 0.0)),
+            Box::new(engine::scene::mesh::create(ring_vertices(
+                opening,
+                FADE_OUTER_RADIUS,
+            ))),
+        );
+        fade.set_transform(
+            eye_transform * Matrix4::from_translation(vec3(0.0, 0.0, -FADE_DISTANCE)),
+        );
+        Some(fade)
+    }
+}
+
+///
+/// fade_towards
+///
+/// The next fade amount - it goes dark right away, so nothing is seen through the wall, and fades
+/// back in gradually
+fn fade_towards(current: f32, target: f32, delta_time: f32) -> f32 {
+    let target = target.clamp(0.0, 1.0);
+    if target >= current {
+        target
+    } else {
+        (current - FADE_IN_SPEED * delta_time).max(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fade_is_immediate_but_recovers_gradually() {
+        // When
+        let leaning_in = fade_towards(0.0, 0.5, 0.1);
+        let deep_in = fade_towards(leaning_in, 3.0, 0.1);
+        let backing_out = fade_towards(deep_in, 0.0, 0.1);
+
+        // Then
+        assert_eq!(leaning_in, 0.5);
+        assert_eq!(deep_in, 1.0);
+        assert!((backing_out - 0.6).abs() < 0.001);
+    }
+}
@@ -0,0 +1,249 @@
+// Holsters
+//
+// Slots on the player's body - the hips, the shoulders, and the chest - for putting away a held
+// weapon or tool, and grabbing it back later. Releasing a held item with the hand at an empty slot
+// holsters it, and squeezing at a full slot takes the item back out. The body follows the headset:
+// the slots hang below the head, turned to face wherever the player is looking, but kept upright.
+
+use std::collections::HashMap;
+
+use cgmath::{vec3, Deg, InnerSpace, Matrix4, Quaternion, Rad, Rotation, Rotation3, Vector3};
+use engine::scene::SceneObject;
+use serde::{Deserialize, Serialize};
+use shipyard::EntityId;
+
+use crate::input_context::Head;
+
+// How close a hand needs to be to a slot, to put something in it or take it back out
+const SLOT_REACH: f32 = 0.15;
+
+// Slots are shown once a hand gets this close, so they don't clutter the view otherwise
+const SLOT_VISIBLE_DISTANCE: f32 = 0.35;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum HolsterSlot {
+    LeftHip,
+    RightHip,
+    LeftShoulder,
+    RightShoulder,
+    Chest,
+}
+
+impl HolsterSlot {
+    pub const ALL: [HolsterSlot; 5] = [
+        HolsterSlot::LeftHip,
+        HolsterSlot::RightHip,
+        HolsterSlot::LeftShoulder,
+        HolsterSlot::RightShoulder,
+        HolsterSlot::Chest,
+    ];
+
+    // Where the slot is, relative to the head, for a player facing forward
+    fn offset(&self) -> Vector3<f32> {
+        match self {
+            HolsterSlot::LeftHip => vec3(-0.2, -0.65, 0.0),
+            HolsterSlot::RightHip => vec3(0.2, -0.65, 0.0),
+            HolsterSlot::LeftShoulder => vec3(-0.18, -0.2, 0.12),
+            HolsterSlot::RightShoulder => vec3(0.18, -0.2, 0.12),
+            HolsterSlot::Chest => vec3(0.0, -0.4, -0.15),
+        }
+    }
+
+    // How an item sits in the slot - pointing down at the hips, up over the shoulders, and across
+    // the chest
+    fn rotation(&self) -> Quaternion<f32> {
+        match self {
+            HolsterSlot::LeftHip | HolsterSlot::RightHip => Quaternion::from_angle_x(Deg(-90.0)),
+            HolsterSlot::LeftShoulder | HolsterSlot::RightShoulder => {
+                Quaternion::from_angle_x(Deg(90.0))
+            }
+            HolsterSlot::Chest => Quaternion::from_angle_y(Deg(90.0)),
+        }
+    }
+}
+
+pub struct Holsters {
+    head_position: Vector3<f32>,
+    body_yaw: Rad<f32>,
+    contents: HashMap<HolsterSlot, EntityId>,
+}
+
+impl Holsters {
+    pub fn new(contents: HashMap<HolsterSlot, EntityId>) -> Holsters {
+        Holsters {
+            head_position: vec3(0.0, 0.0, 0.0),
+            body_yaw: Rad(0.0),
+            contents,
+        }
+    }
+
+    ///
+    /// update
+    ///
+    /// Move the slots along with the player's head, at a position relative to the floor of the
+    /// play space
+    pub fn update(
+        &mut self,
+        player_position: Vector3<f32>,
+        player_rotation: Quaternion<f32>,
+        head: &Head,
+    ) {
+        self.head_position = player_position + player_rotation.rotate_vector(head.position);
+
+        // Looking straight up or down, there's no heading to go by - so keep the last one
+        let forward = (player_rotation * head.rotation).rotate_vector(vec3(0.0, 0.0, -1.0));
+        if vec3(forward.x, 0.0, forward.z).magnitude() > 0.1 {
+            self.body_yaw = Rad(f32::atan2(-forward.x, -forward.z));
+        }
+    }
+
+    pub fn contents(&self) -> &HashMap<HolsterSlot, EntityId> {
+        &self.contents
+    }
+
+    ///
+    /// slot_transform
+    ///
+    /// Where an item in the slot goes, and how it's turned, in world space
+    pub fn slot_transform(&self, slot: HolsterSlot) -> (Vector3<f32>, Quaternion<f32>) {
+        let body_rotation = Quaternion::from_angle_y(self.body_yaw);
+        let position = self.head_position + body_rotation.rotate_vector(slot.offset());
+        (position, body_rotation * slot.rotation())
+    }
+
+    ///
+    /// empty_slot_near
+    ///
+    /// The closest empty slot in reach of the hand, to holster a held item in
+    pub fn empty_slot_near(&self, hand_position: Vector3<f32>) -> Option<HolsterSlot> {
+        self.closest_slot_in_reach(hand_position, |slot| !self.contents.contains_key(slot))
+    }
+
+    ///
+    /// holstered_item_near
+    ///
+    /// The closest full slot in reach of the hand, and the item in it
+    pub fn holstered_item_near(
+        &self,
+        hand_position: Vector3<f32>,
+    ) -> Option<(HolsterSlot, EntityId)> {
+        self.closest_slot_in_reach(hand_position, |slot| self.contents.contains_key(slot))
+            .map(|slot| (slot, self.contents[&slot]))
+    }
+
+    pub fn holster(&mut self, slot: HolsterSlot, entity_id: EntityId) {
+        self.contents.insert(slot, entity_id);
+    }
+
+    pub fn take(&mut self, slot: HolsterSlot) -> Option<EntityId> {
+        self.contents.remove(&slot)
+    }
+
+    pub fn remove_entity(&mut self, entity_id: EntityId) {
+        self.contents.retain(|_, holstered| *holstered != entity_id);
+    }
+
+    ///
+    /// render
+    ///
+    /// Markers for the slots the hands are close to - green when empty, yellow when full
+    pub fn render(&self, hand_positions: &[Vector3<f32>]) -> Vec<SceneObject> {
+        HolsterSlot::ALL
+            .iter()
+            .filter_map(|slot| {
+                let (position, rotation) = self.slot_transform(*slot);
+                let is_hand_near = hand_positions
+                    .iter()
+                    .any(|hand| (hand - position).magnitude() < SLOT_VISIBLE_DISTANCE);
+                if !is_hand_near {
+                    return None;
+                }
+
+                let color = if self.contents.contains_key(slot) {
+                    vec3(1.0, 1.0, 0.0)
+                } else {
+                    vec3(0.0, 1.0, 0.0)
+                };
+                let mut marker = SceneObject::new(
+                    engine::scene::color_material::create(color),
+                    Box::new(engine::scene::cube::create()),
+                );
+                marker.set_transform(
+                    Matrix4::from_translation(position)
+                        * Matrix4::from(rotation)
+                        * Matrix4::from_scale(0.03),
+                );
+                Some(marker)
+            })
+            .collect()
+    }
+
+    fn closest_slot_in_reach(
+        &self,
+        hand_position: Vector3<f32>,
+        predicate: impl Fn(&HolsterSlot) -> bool,
+    ) -> Option<HolsterSlot> {
+        HolsterSlot::ALL
+            .iter()
+            .filter(|slot| predicate(slot))
+            .map(|slot| {
+                let (position, _) = self.slot_transform(*slot);
+                (*slot, (hand_position - position).magnitude())
+            })
+            .filter(|(_, distance)| *distance < SLOT_REACH)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(slot, _)| slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slots_turn_with_the_head_but_stay_upright() {
+        // Given - a player looking to their left, and down at the floor
+        let mut holsters = Holsters::new(HashMap::new());
+        let head = Head {
+            position: vec3(0.0, 1.6, 0.0),
+            rotation: Quaternion::from_angle_y(Deg(90.0)) * Quaternion::from_angle_x(Deg(-60.0)),
+        };
+
+        // When
+        holsters.update(
+            vec3(1.0, 0.0, 0.0),
+            Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            &head,
+        );
+        let (right_hip, _) = holsters.slot_transform(HolsterSlot::RightHip);
+
+        // Then - the slots turn to face the same way, but don't tip forward
+        assert!((right_hip - vec3(1.0, 0.95, -0.2)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn test_hand_finds_closest_slot_in_reach() {
+        // Given
+        let mut holsters = Holsters::new(HashMap::new());
+        holsters.update(
+            vec3(0.0, 0.0, 0.0),
+            Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            &Head::default(),
+        );
+        let (left_hip, _) = holsters.slot_transform(HolsterSlot::LeftHip);
+        let entity_id = shipyard::World::new().add_entity(());
+
+        // When
+        let before = holsters.empty_slot_near(left_hip + vec3(0.05, 0.0, 0.0));
+        holsters.holster(HolsterSlot::LeftHip, entity_id);
+        let after = holsters.empty_slot_near(left_hip);
+        let holstered = holsters.holstered_item_near(left_hip);
+        let far_away = holsters.holstered_item_near(left_hip + vec3(0.0, 0.5, 0.0));
+
+        // Then
+        assert_eq!(before, Some(HolsterSlot::LeftHip));
+        assert_eq!(after, None);
+        assert_eq!(holstered, Some((HolsterSlot::LeftHip, entity_id)));
+        assert_eq!(far_away, None);
+    }
+}
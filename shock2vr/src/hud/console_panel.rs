@@ -0,0 +1,31 @@
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+
+use crate::command::Console;
+
+use super::draw_text_panel;
+
+///
+/// draw_console_panel
+///
+/// The floating console panel - output at the top, and the quick commands below it, with the
+/// selected one marked
+pub fn draw_console_panel(asset_cache: &mut AssetCache, console: &Console) -> Vec<SceneObject> {
+    let maybe_transform = console.panel_transform();
+    if maybe_transform.is_none() {
+        return vec![];
+    }
+    let transform = maybe_transform.unwrap();
+
+    let mut lines: Vec<String> = console.output().cloned().collect();
+    lines.push("-----".to_owned());
+    for (idx, command) in console.quick_commands().iter().enumerate() {
+        let marker = if idx == console.selected_quick_command() {
+            ">"
+        } else {
+            " "
+        };
+        lines.push(format!("{marker} {command}"));
+    }
+
+    draw_text_panel(asset_cache, transform, &lines)
+}
@@ -0,0 +1,29 @@
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+
+use crate::death::Death;
+
+use super::draw_text_panel;
+
+///
+/// draw_game_over_panel
+///
+/// The panel shown once the player has died - what to do next, with the selected choice marked
+pub fn draw_game_over_panel(asset_cache: &mut AssetCache, death: &Death) -> Vec<SceneObject> {
+    let maybe_transform = death.panel_transform();
+    if maybe_transform.is_none() {
+        return vec![];
+    }
+    let transform = maybe_transform.unwrap();
+
+    let mut lines = vec!["GAME OVER".to_owned(), "-----".to_owned()];
+    for (idx, choice) in death.choices().iter().enumerate() {
+        let marker = if idx == death.selected_choice() {
+            ">"
+        } else {
+            " "
+        };
+        lines.push(format!("{marker} {}", choice.label()));
+    }
+
+    draw_text_panel(asset_cache, transform, &lines)
+}
@@ -0,0 +1,96 @@
+use cgmath::{vec3, Deg, Matrix4, Vector3};
+use dark::importers::FONT_IMPORTER;
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+
+use crate::player_health::PlayerHealth;
+
+// Size of the bars, in world units - small enough to fit on the back of the hand
+const BAR_WIDTH: f32 = 0.08;
+const BAR_HEIGHT: f32 = 0.008;
+const BAR_SPACING: f32 = 0.012;
+
+// World space text is sized for the console panel, at arm's length
+const TEXT_SCALE: f32 = 0.25;
+
+///
+/// draw_health_indicator
+///
+/// Hit points and armor, as bars floating over the back of the left hand
+pub fn draw_health_indicator(
+    asset_cache: &mut AssetCache,
+    player_health: &PlayerHealth,
+    left_hand_transform: Matrix4<f32>,
+) -> Vec<SceneObject> {
+    let transform = left_hand_transform
+        * Matrix4::from_translation(vec3(0.0, 0.03, 0.04))
+        * Matrix4::from_angle_x(Deg(-90.0));
+
+    let health_amount = player_health.hit_points() / player_health.max_hit_points();
+    let armor_amount = player_health.armor() / player_health.max_armor();
+
+    let mut ret = Vec::new();
+    ret.extend(draw_bar(
+        transform,
+        BAR_SPACING / 2.0,
+        health_amount,
+        health_color(health_amount),
+    ));
+    ret.extend(draw_bar(
+        transform,
+        -BAR_SPACING / 2.0,
+        armor_amount,
+        vec3(0.2, 0.4, 1.0),
+    ));
+
+    let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon");
+    let label = format!("{}", player_health.hit_points().ceil() as i32);
+    let mut text = SceneObject::world_space_text(&label, font, 0.0);
+    // World space text starts at a height of 1.0
+    text.set_transform(
+        transform
+            * Matrix4::from_translation(vec3(BAR_WIDTH / 2.0 + 0.005, BAR_SPACING, 0.001))
+            * Matrix4::from_scale(TEXT_SCALE)
+            * Matrix4::from_translation(vec3(0.0, -1.0, 0.0)),
+    );
+    ret.push(text);
+
+    ret
+}
+
+// A bar, filled from the left by the given amount, on a dark background
+fn draw_bar(transform: Matrix4<f32>, y: f32, amount: f32, color: Vector3<f32>) -> Vec<SceneObject> {
+    let amount = amount.clamp(0.0, 1.0);
+
+    let mut background = SceneObject::new(
+        engine::scene::color_material::create(vec3(0.05, 0.05, 0.05)),
+        Box::new(engine::scene::quad::create()),
+    );
+    background.set_transform(
+        transform
+            * Matrix4::from_translation(vec3(0.0, y, 0.0))
+            * Matrix4::from_nonuniform_scale(BAR_WIDTH, BAR_HEIGHT, 1.0),
+    );
+
+    if amount <= 0.0 {
+        return vec![background];
+    }
+
+    let fill_width = BAR_WIDTH * amount;
+    let mut fill = SceneObject::new(
+        engine::scene::color_material::create(color),
+        Box::new(engine::scene::quad::create()),
+    );
+    fill.set_transform(
+        transform
+            * Matrix4::from_translation(vec3((fill_width - BAR_WIDTH) / 2.0, y, 0.001))
+            * Matrix4::from_nonuniform_scale(fill_width, BAR_HEIGHT, 1.0),
+    );
+
+    vec![background, fill]
+}
+
+// Green while healthy, turning red as the player gets hurt
+fn health_color(health_amount: f32) -> Vector3<f32> {
+    let amount = health_amount.clamp(0.0, 1.0);
+    vec3(1.0 - amount, amount, 0.0)
+}
@@ -1,2 +1,10 @@
+mod console_panel;
+mod game_over_panel;
+mod health_indicator;
 mod item_outline;
+mod text_panel;
+pub use console_panel::*;
+pub use game_over_panel::*;
+pub use health_indicator::*;
 pub use item_outline::*;
+pub use text_panel::*;
@@ -0,0 +1,47 @@
+use cgmath::{vec3, Matrix4};
+use dark::importers::FONT_IMPORTER;
+use engine::{assets::asset_cache::AssetCache, scene::SceneObject};
+
+// Size of the panel, in world units
+const PANEL_WIDTH: f32 = 1.2;
+const LINE_HEIGHT: f32 = 0.05;
+const PADDING: f32 = 0.03;
+
+///
+/// draw_text_panel
+///
+/// Lines of text on a dark, floating panel, sized to fit them
+pub fn draw_text_panel(
+    asset_cache: &mut AssetCache,
+    transform: Matrix4<f32>,
+    lines: &[String],
+) -> Vec<SceneObject> {
+    let panel_height = lines.len() as f32 * LINE_HEIGHT + PADDING * 2.0;
+    let mut background = SceneObject::new(
+        engine::scene::color_material::create(vec3(0.05, 0.05, 0.1)),
+        Box::new(engine::scene::quad::create()),
+    );
+    background
+        .set_transform(transform * Matrix4::from_nonuniform_scale(PANEL_WIDTH, panel_height, 1.0));
+
+    let font = asset_cache.get(&FONT_IMPORTER, "mainfont.fon");
+    let left = -PANEL_WIDTH / 2.0 + PADDING;
+    let top = panel_height / 2.0 - PADDING;
+
+    let mut ret = vec![background];
+    for (idx, line) in lines.iter().enumerate() {
+        // Only draw characters the font has
+        let printable: String = line
+            .chars()
+            .filter(|c| c.is_ascii_graphic() || *c == ' ')
+            .collect();
+
+        // World space text starts at a height of 1.0
+        let y = top - (idx + 1) as f32 * LINE_HEIGHT - 1.0;
+        let mut text = SceneObject::world_space_text(&printable, font.clone(), 0.0);
+        text.set_transform(transform * Matrix4::from_translation(vec3(left, y, 0.01)));
+        ret.push(text);
+    }
+
+    ret
+}
@@ -3,7 +3,10 @@
 // For Oculus / VR, this is a fairly direct mapping from the standard motion controllers.
 // For desktop / PC runtime, the mapping is a bit more interesting..
 
-use cgmath::{Quaternion, Vector2, Vector3, Zero};
+use cgmath::{vec3, Quaternion, Vector2, Vector3, Zero};
+
+// Head height, in meters, for runtimes that don't track the head - an average standing player
+const DEFAULT_HEAD_HEIGHT: f32 = 1.6;
 
 #[derive(Debug)]
 pub struct InputContext {
@@ -24,16 +27,34 @@ impl InputContext {
             right_hand: Hand::default(),
         }
     }
+
+    ///
+    /// without_buttons
+    ///
+    /// The same head and hand poses, with nothing pressed - for when the player can't act
+    pub fn without_buttons(&self) -> InputContext {
+        InputContext {
+            head: Head {
+                position: self.head.position,
+                rotation: self.head.rotation,
+            },
+            left_hand: self.left_hand.without_buttons(),
+            right_hand: self.right_hand.without_buttons(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Head {
+    // Position relative to the floor of the play space, like the hands
+    pub position: Vector3<f32>,
     pub rotation: Quaternion<f32>,
 }
 
 impl Head {
     pub fn default() -> Head {
         Head {
+            position: vec3(0.0, DEFAULT_HEAD_HEIGHT, 0.0),
             rotation: Quaternion {
                 v: Vector3::zero(),
                 s: 1.0,
@@ -51,6 +72,8 @@ pub struct Hand {
     pub trigger_value: f32,
     pub squeeze_value: f32,
     pub a_value: f32,
+    pub b_value: f32,
+    pub menu_value: f32,
 }
 
 impl Hand {
@@ -65,6 +88,16 @@ impl Hand {
             trigger_value: 0.0,
             squeeze_value: 0.0,
             a_value: 0.0,
+            b_value: 0.0,
+            menu_value: 0.0,
+        }
+    }
+
+    fn without_buttons(&self) -> Hand {
+        Hand {
+            position: self.position,
+            rotation: self.rotation,
+            ..Hand::default()
         }
     }
 }
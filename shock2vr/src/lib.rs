@@ -1,25 +1,34 @@
 pub mod command;
+pub mod head_collision;
 pub mod input_context;
 pub mod inventory;
+pub mod locomotion;
 pub mod save_load;
 pub mod time;
 
 mod creature;
+mod death;
 mod gui;
+mod holster;
 mod hud;
 mod mission;
 mod physics;
+mod player_health;
 mod quest_info;
 mod runtime_props;
 mod scripts;
 mod systems;
+#[cfg(test)]
+mod test_harness;
 mod util;
 mod virtual_hand;
 mod vr_config;
 mod zip_asset_path;
 
+pub use creature::get_creature_definition;
 pub use mission::visibility_engine::CullingInfo;
 pub use mission::SpawnLocation;
+pub use scripts::{script_coverage, ScriptCoverage};
 
 use std::{
     collections::{HashMap, HashSet},
@@ -28,8 +37,8 @@ use std::{
     rc::Rc,
 };
 
-use cgmath::{vec3, InnerSpace, Matrix4, Quaternion, Rad, Rotation, Rotation3, Vector2, Vector3};
-use command::Command;
+use cgmath::{vec3, InnerSpace, Matrix4, Quaternion, Rotation, Vector2, Vector3};
+use command::{Command, CommandRegistry, Console};
 use dark::{
     gamesys,
     importers::{AUDIO_IMPORTER, FONT_IMPORTER, STRINGS_IMPORTER},
@@ -41,7 +50,10 @@ use dark::{
     SCALE_FACTOR,
 };
 use engine::{
-    assets::{asset_cache::AssetCache, asset_paths::AssetPath},
+    assets::{
+        asset_cache::AssetCache,
+        asset_paths::{AbstractAssetPath, AssetPath},
+    },
     audio::{AudioClip, AudioContext},
     file_system::FileSystem,
     profile,
@@ -50,6 +62,7 @@ use engine::{
 use std::time::Instant;
 
 use mission::entity_populator::{EntityPopulator, MissionEntityPopulator, SaveFileEntityPopulator};
+use player_health::PlayerHealth;
 use quest_info::QuestInfo;
 
 use save_load::{EntitySaveData, GlobalData, HeldItemSaveData, SaveData};
@@ -62,8 +75,13 @@ use tracing::{info, span, trace, warn, Level};
 use zip_asset_path::ZipAssetPath;
 
 use crate::{
+    death::{Death, DeathChoice, RESURRECTION_NANITE_COST},
+    head_collision::{HeadCollision, HeadCollisionMode},
+    locomotion::{Locomotion, LocomotionSettings, LOCOMOTION_SETTINGS_FILE},
     mission::{GlobalContext, Mission, PlayerInfo},
-    scripts::{Effect, Message, MessagePayload},
+    physics::PlayerControllerInput,
+    player_health::fall_damage,
+    scripts::{DamageType, Effect, Message, MessagePayload},
     util::log_entities_with_link,
 };
 
@@ -77,6 +95,63 @@ pub fn resource_path(str: &str) -> String {
     format!("{BASE_PATH}/{str}")
 }
 
+///
+/// default_asset_paths
+///
+/// The folders and resource archives (.crf) that assets are loaded from, in priority order
+pub fn default_asset_paths() -> Box<dyn AbstractAssetPath> {
+    AssetPath::combine(vec![
+        AssetPath::folder(resource_path("res/mesh")),
+        // AssetPath::folder(resource_path("res/mesh/txt16")),
+        AssetPath::folder(resource_path("res/obj")),
+        // AssetPath::folder(resource_path("res/obj/txt16")),
+        ZipAssetPath::new(resource_path("res/obj.crf")),
+        ZipAssetPath::new(resource_path("res/bitmap.crf")),
+        ZipAssetPath::new(resource_path("res/fam.crf")),
+        ZipAssetPath::new(resource_path("res/iface.crf")),
+        ZipAssetPath::new(resource_path("res/mesh.crf")),
+        ZipAssetPath::new(resource_path("res/motions.crf")),
+        ZipAssetPath::new(resource_path("res/objicon.crf")),
+        ZipAssetPath::new(resource_path("res/snd.crf")),
+        ZipAssetPath::new(resource_path("res/snd2.crf")),
+        ZipAssetPath::new(resource_path("res/song.crf")),
+        ZipAssetPath::new2(resource_path("res/strings.crf"), false),
+        //AssetPath::folder("../assets/"),
+        // Textures
+        // AssetPath::folder("res/bitmap".to_owned()),
+        // AssetPath::folder("res/bitmap/txt16".to_owned()),
+        //AssetPath::folder("res/fam".to_owned()),
+        // Models
+        // AssetPath::folder("res/mesh/txt16".to_owned()),
+        // AssetPath::folder("res/obj".to_owned()),
+        // AssetPath::folder("res/mesh".to_owned()),
+        // Animations
+        //AssetPath::folder("res/motions".to_owned()),
+        // Motion db
+        AssetPath::folder("".to_owned()),
+        // Audio
+        // AssetPath::folder("res/snd".to_owned()),
+        // AssetPath::folder("res/snd/amb".to_owned()),
+        // AssetPath::folder("res/snd/Assassin".to_owned()),
+        // AssetPath::folder("res/snd/BBetty".to_owned()),
+        // AssetPath::folder("res/snd/Devices".to_owned()),
+        // AssetPath::folder("res/snd/GRUB".to_owned()),
+        // AssetPath::folder("res/snd/HITS".to_owned()),
+        // AssetPath::folder("res/snd/MaintBot/english".to_owned()),
+        // AssetPath::folder("res/snd/Midwife/english".to_owned()),
+        // AssetPath::folder("res/snd/OGRUNT/english".to_owned()),
+        // AssetPath::folder("res/snd/Overlord/english".to_owned()),
+        // AssetPath::folder("res/snd/SONGS".to_owned()),
+        // AssetPath::folder("res/snd/TurrCam".to_owned()),
+        // AssetPath::folder("res/snd/Weapons".to_owned()),
+        // AssetPath::folder("res/snd2/vBriefs/ENGLISH".to_owned()),
+        // AssetPath::folder("res/snd2/vCs/ENGLISH".to_owned()),
+        // AssetPath::folder("res/snd2/vEmails/english".to_owned()),
+        // AssetPath::folder("res/snd2/vLogs/english".to_owned()),
+        // AssetPath::folder("res/snd2/vTriggers/english".to_owned()),
+    ])
+}
+
 pub struct GameOptions {
     pub mission: String,
     pub spawn_location: SpawnLocation,
@@ -86,6 +161,46 @@ pub struct GameOptions {
     pub debug_draw: bool,
     pub debug_portals: bool,
     pub experimental_features: HashSet<String>,
+    // None to use the settings last chosen in game
+    pub locomotion: Option<LocomotionSettings>,
+    pub head_collision: HeadCollisionMode,
+}
+
+impl GameOptions {
+    ///
+    /// toggle_debug_flag
+    ///
+    /// Flip a debug option at runtime, returning whether it is now enabled
+    pub fn toggle_debug_flag(&mut self, flag: DebugFlag) -> bool {
+        let value = match flag {
+            DebugFlag::Physics => &mut self.debug_physics,
+            DebugFlag::Draw => &mut self.debug_draw,
+            DebugFlag::Portals => &mut self.debug_portals,
+            DebugFlag::Particles => &mut self.render_particles,
+        };
+        *value = !*value;
+        *value
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DebugFlag {
+    Physics,
+    Draw,
+    Portals,
+    Particles,
+}
+
+impl DebugFlag {
+    pub fn from_name(name: &str) -> Option<DebugFlag> {
+        match name.to_ascii_lowercase().as_str() {
+            "physics" | "debug_physics" => Some(DebugFlag::Physics),
+            "draw" | "debug_draw" => Some(DebugFlag::Draw),
+            "portals" | "debug_portals" => Some(DebugFlag::Portals),
+            "particles" | "render_particles" => Some(DebugFlag::Particles),
+            _ => None,
+        }
+    }
 }
 
 impl Default for GameOptions {
@@ -99,6 +214,8 @@ impl Default for GameOptions {
             debug_physics: false,
             render_particles: true,
             experimental_features: HashSet::new(),
+            locomotion: None,
+            head_collision: HeadCollisionMode::PushBack,
         }
     }
 }
@@ -115,10 +232,20 @@ pub struct Game {
     // id_to_physics: HashMap<EntityId, RigidBodyHandle>,
     // scene_objects: Vec<RefCell<SceneObject>>,
     //world: World,
-    last_music_cue: Option<String>,
     last_env_sound: Option<String>,
 
     mission_to_save_data: HashMap<String, EntitySaveData>,
+
+    console: Console,
+
+    locomotion: Locomotion,
+    head_collision: HeadCollision,
+
+    // Set while the player is dead, until they choose what to do next
+    death: Option<Death>,
+    last_save_file: Option<String>,
+    // The game as it was when the player arrived on each level, by level name, to restart it from
+    restart_points: HashMap<String, SaveData>,
 }
 
 impl Game {
@@ -130,6 +257,13 @@ impl Game {
             .unwrap()
             .clone();
 
+        let current_player_health = self
+            .active_mission
+            .world
+            .borrow::<UniqueView<PlayerHealth>>()
+            .unwrap()
+            .clone();
+
         let (current_save_data, held_data) = save_load::to_save_data(&self.active_mission.world);
         println!("ALL ENTITIES: {}", &current_save_data.all_entities.len());
 
@@ -158,62 +292,94 @@ impl Game {
             &self.global_context,
             spawn_loc,
             current_quest_info,
+            current_player_health,
             populator,
             held_data,
         );
         self.active_mission = active_mission;
+        self.record_restart_point();
+    }
+
+    fn record_restart_point(&mut self) {
+        let save_data = self.build_save_data();
+        self.restart_points
+            .insert(self.active_mission.level_name.clone(), save_data);
+    }
+
+    ///
+    /// handle_player_death
+    ///
+    /// Rebuild the player at the level's resurrection station, if they've activated one and can
+    /// pay for it - otherwise, it's game over
+    fn handle_player_death(&mut self) {
+        let station = {
+            let mut quest_info = self
+                .active_mission
+                .world
+                .borrow::<UniqueViewMut<QuestInfo>>()
+                .unwrap();
+            match quest_info.resurrection_station(&self.active_mission.level_name) {
+                Some(station) if quest_info.spend_nanites(RESURRECTION_NANITE_COST) => {
+                    Some(station)
+                }
+                _ => None,
+            }
+        };
+
+        match station {
+            Some((position, rotation)) => {
+                self.console.print(format!(
+                    "Reconstructed for {RESURRECTION_NANITE_COST} nanites"
+                ));
+                self.active_mission
+                    .physics
+                    .set_player_translation(position, &mut self.active_mission.player_handle);
+
+                let mut player_info = self
+                    .active_mission
+                    .world
+                    .borrow::<UniqueViewMut<PlayerInfo>>()
+                    .unwrap();
+                player_info.rotation = rotation;
+                drop(player_info);
+
+                let mut player_health = self
+                    .active_mission
+                    .world
+                    .borrow::<UniqueViewMut<PlayerHealth>>()
+                    .unwrap();
+                *player_health = PlayerHealth::new();
+            }
+            None => {
+                self.console.print("You died".to_owned());
+                self.death = Some(Death::new(self.last_save_file.is_some()));
+            }
+        }
+    }
+
+    ///
+    /// restart_level
+    ///
+    /// Go back to how the game was when the player arrived on the current level
+    fn restart_level(&mut self) {
+        let maybe_save_data = self
+            .restart_points
+            .get(&self.active_mission.level_name)
+            .cloned();
+        if let Some(save_data) = maybe_save_data {
+            let (mission, level_map) = Self::load_from_save_data(
+                save_data,
+                &mut self.asset_cache,
+                &mut self.audio_context,
+                &self.global_context,
+            );
+            self.active_mission = mission;
+            self.mission_to_save_data = level_map;
+        }
     }
+
     pub fn init(_file_system: &Box<dyn FileSystem>, options: GameOptions) -> Game {
-        let asset_paths = AssetPath::combine(vec![
-            AssetPath::folder(resource_path("res/mesh")),
-            // AssetPath::folder(resource_path("res/mesh/txt16")),
-            AssetPath::folder(resource_path("res/obj")),
-            // AssetPath::folder(resource_path("res/obj/txt16")),
-            ZipAssetPath::new(resource_path("res/obj.crf")),
-            ZipAssetPath::new(resource_path("res/bitmap.crf")),
-            ZipAssetPath::new(resource_path("res/fam.crf")),
-            ZipAssetPath::new(resource_path("res/iface.crf")),
-            ZipAssetPath::new(resource_path("res/mesh.crf")),
-            ZipAssetPath::new(resource_path("res/motions.crf")),
-            ZipAssetPath::new(resource_path("res/objicon.crf")),
-            ZipAssetPath::new(resource_path("res/snd.crf")),
-            ZipAssetPath::new(resource_path("res/snd2.crf")),
-            ZipAssetPath::new(resource_path("res/song.crf")),
-            ZipAssetPath::new2(resource_path("res/strings.crf"), false),
-            //AssetPath::folder("../assets/"),
-            // Textures
-            // AssetPath::folder("res/bitmap".to_owned()),
-            // AssetPath::folder("res/bitmap/txt16".to_owned()),
-            //AssetPath::folder("res/fam".to_owned()),
-            // Models
-            // AssetPath::folder("res/mesh/txt16".to_owned()),
-            // AssetPath::folder("res/obj".to_owned()),
-            // AssetPath::folder("res/mesh".to_owned()),
-            // Animations
-            //AssetPath::folder("res/motions".to_owned()),
-            // Motion db
-            AssetPath::folder("".to_owned()),
-            // Audio
-            // AssetPath::folder("res/snd".to_owned()),
-            // AssetPath::folder("res/snd/amb".to_owned()),
-            // AssetPath::folder("res/snd/Assassin".to_owned()),
-            // AssetPath::folder("res/snd/BBetty".to_owned()),
-            // AssetPath::folder("res/snd/Devices".to_owned()),
-            // AssetPath::folder("res/snd/GRUB".to_owned()),
-            // AssetPath::folder("res/snd/HITS".to_owned()),
-            // AssetPath::folder("res/snd/MaintBot/english".to_owned()),
-            // AssetPath::folder("res/snd/Midwife/english".to_owned()),
-            // AssetPath::folder("res/snd/OGRUNT/english".to_owned()),
-            // AssetPath::folder("res/snd/Overlord/english".to_owned()),
-            // AssetPath::folder("res/snd/SONGS".to_owned()),
-            // AssetPath::folder("res/snd/TurrCam".to_owned()),
-            // AssetPath::folder("res/snd/Weapons".to_owned()),
-            // AssetPath::folder("res/snd2/vBriefs/ENGLISH".to_owned()),
-            // AssetPath::folder("res/snd2/vCs/ENGLISH".to_owned()),
-            // AssetPath::folder("res/snd2/vEmails/english".to_owned()),
-            // AssetPath::folder("res/snd2/vLogs/english".to_owned()),
-            // AssetPath::folder("res/snd2/vTriggers/english".to_owned()),
-        ]);
+        let asset_paths = default_asset_paths();
         // Global items
         let mut asset_cache = AssetCache::new(BASE_PATH.to_owned(), asset_paths);
 
@@ -310,6 +476,7 @@ impl Game {
                     &global_context,
                     options.spawn_location.clone(),
                     QuestInfo::new(),
+                    PlayerHealth::new(),
                     //Box::new(MissionEntityPopulator::create()),
                     Box::new(MissionEntityPopulator::create()),
                     HeldItemSaveData::empty(),
@@ -331,29 +498,81 @@ impl Game {
         // );
         // panic!();
 
-        Game {
+        let locomotion = Locomotion::new(options.locomotion.clone().unwrap_or_else(|| {
+            LocomotionSettings::load(resource_path(LOCOMOTION_SETTINGS_FILE)).unwrap_or_default()
+        }));
+        let head_collision = HeadCollision::new(options.head_collision);
+        let last_save_file = options.save_file.clone();
+
+        let mut game = Game {
             asset_cache,
             audio_context,
             active_mission,
             global_context,
-            last_music_cue: None,
             last_env_sound: None,
             options,
             mission_to_save_data,
-        }
+            console: Console::new(CommandRegistry::default()),
+            locomotion,
+            head_collision,
+            death: None,
+            last_save_file,
+            restart_points: HashMap::new(),
+        };
+        game.record_restart_point();
+        game
     }
 
     pub fn update(
         &mut self,
         time: &Time,
         input_context: &input_context::InputContext,
-        commands: Vec<Box<dyn Command>>,
+        mut commands: Vec<Box<dyn Command>>,
     ) {
         let span = span!(Level::INFO, "update");
         let _enter = span.enter();
         let delta_time = time.elapsed.as_secs_f32();
         trace!("delta_time: {}", delta_time);
 
+        let (player_position, player_rotation) = {
+            let player_info = self
+                .active_mission
+                .world
+                .borrow::<UniqueView<PlayerInfo>>()
+                .unwrap();
+            (player_info.pos, player_info.rotation)
+        };
+        self.console
+            .update(input_context, player_position, player_rotation);
+        commands.extend(self.console.take_commands());
+
+        let death_choice = self.death.as_mut().and_then(|death| {
+            death.update(input_context, player_position, player_rotation, delta_time)
+        });
+        match death_choice {
+            Some(DeathChoice::LoadLastSave) => {
+                self.death = None;
+                if let Some(file_name) = self.last_save_file.clone() {
+                    self.load_from_file(file_name);
+                }
+                return;
+            }
+            Some(DeathChoice::RestartLevel) => {
+                self.death = None;
+                self.restart_level();
+                return;
+            }
+            None => (),
+        }
+
+        // The dead can look around, but not act - and the buttons drive the console while it's open
+        let idle_input_context = input_context.without_buttons();
+        let input_context = if self.death.is_some() || self.console.is_visible() {
+            &idle_input_context
+        } else {
+            input_context
+        };
+
         let mut command_effects = Vec::new();
         for command in commands {
             let eff = command.execute(&self.active_mission.world);
@@ -367,28 +586,46 @@ impl Game {
             .unwrap()
             .clone();
 
-        let rot_speed = 2.0;
-        let additional_rotation = Quaternion::from_axis_angle(
-            vec3(0.0, 1.0, 0.0),
-            Rad(input_context.left_hand.thumbstick.x * delta_time * rot_speed),
+        let locomotion = self.locomotion.update(
+            input_context,
+            player.pos,
+            player.rotation,
+            &self.active_mission.physics,
+            &self.active_mission.player_handle,
+            delta_time,
         );
+        let new_rotation = locomotion.rotation;
+
+        if let Some(position) = locomotion.teleport_to {
+            // Not a trap teleport - tripwires along the way should still see the player
+            command_effects.push(Effect::SetPlayerPosition {
+                position,
+                is_teleport: false,
+            });
+        }
 
-        let new_rotation = player.rotation * additional_rotation;
-
-        let dir = new_rotation * input_context.head.rotation;
-        let move_thumbstick_value = input_context.right_hand.thumbstick;
-        let forward = dir.rotate_vector(vec3(
-            -delta_time * move_thumbstick_value.x * 25. / SCALE_FACTOR,
-            0.0,
-            -delta_time * move_thumbstick_value.y * 25. / SCALE_FACTOR,
-        ));
+        let head_push_back = self.head_collision.update(
+            input_context.head.position,
+            player.pos,
+            new_rotation,
+            &self.active_mission.physics,
+            delta_time,
+        );
 
         let up_value = input_context.left_hand.thumbstick.y / SCALE_FACTOR;
 
+        let controller_input = PlayerControllerInput {
+            head_height: input_context.head.position.y,
+            is_jump_pressed: input_context.right_hand.b_value > 0.5,
+            look_direction: (new_rotation * input_context.head.rotation)
+                .rotate_vector(vec3(0.0, 0.0, -1.0)),
+        };
+
         let (new_character_pos, collision_events) = profile!(
             "shock2.update.physics",
             self.active_mission.physics.update(
-                forward + vec3(0.0, up_value, 0.0),
+                locomotion.movement + head_push_back + vec3(0.0, up_value, 0.0),
+                &controller_input,
                 &mut self.active_mission.player_handle,
             )
         );
@@ -396,6 +633,21 @@ impl Game {
         // Clear forces
         self.active_mission.physics.clear_forces();
 
+        if let Some(landing_speed) = self.active_mission.player_handle.state().landing_speed {
+            let amount = fall_damage(landing_speed);
+            if amount > 0.0 {
+                self.active_mission.script_world.dispatch(Message {
+                    to: player.entity_id,
+                    payload: MessagePayload::Damage {
+                        amount,
+                        damage_type: DamageType::Standard,
+                        source: None,
+                        hit: None,
+                    },
+                });
+            }
+        }
+
         let mut player_info = self
             .active_mission
             .world
@@ -405,6 +657,7 @@ impl Game {
         player_info.rotation = new_rotation;
         player_info.left_hand_entity_id = self.active_mission.left_hand.get_held_entity();
         player_info.right_hand_entity_id = self.active_mission.right_hand.get_held_entity();
+        player_info.holstered_entity_ids = self.active_mission.holsters.contents().clone();
         drop(player_info);
 
         let mut next_env_sound = None;
@@ -465,8 +718,26 @@ impl Game {
             })
             .collect::<Vec<(EntityId, Vector3<f32>, Rc<AudioClip>)>>();
 
+        self.active_mission
+            .speech
+            .update(&self.active_mission.world, &mut self.audio_context);
         self.audio_context.update(new_character_pos, ambient_sounds);
 
+        if let Some(room) = self
+            .active_mission
+            .level
+            .room_database
+            .find_room_containing(new_character_pos)
+        {
+            self.audio_context.set_reverb(mission::reverb_for_room(room));
+        }
+
+        self.active_mission.music_director.update(
+            &self.active_mission.world,
+            time.total,
+            &mut self.audio_context,
+        );
+
         for ce in collision_events {
             info!("event: {:?}", ce);
 
@@ -564,6 +835,14 @@ impl Game {
         );
         self.active_mission = mission;
         self.mission_to_save_data = level_map;
+
+        // A save on a level that hasn't been arrived at yet restarts from the save
+        if !self
+            .restart_points
+            .contains_key(&self.active_mission.level_name)
+        {
+            self.record_restart_point();
+        }
     }
 
     fn load_from_save_data(
@@ -600,6 +879,7 @@ impl Game {
             global_context,
             spawn_loc,
             save_data.global_data.quest_info,
+            save_data.global_data.player_health,
             populator,
             save_data.global_data.held_items,
         );
@@ -631,11 +911,19 @@ impl Game {
             .unwrap()
             .clone();
 
+        let player_health = self
+            .active_mission
+            .world
+            .borrow::<UniqueView<PlayerHealth>>()
+            .unwrap()
+            .clone();
+
         let global_data = GlobalData {
             held_items,
             position,
             rotation,
             quest_info,
+            player_health,
             active_mission: self.active_mission.level_name.clone(),
         };
 
@@ -647,8 +935,14 @@ impl Game {
 
     fn handle_global_effect(&mut self, global_effect: GlobalEffect) {
         match global_effect {
-            GlobalEffect::Save { file_name } => self.save_to_file(file_name),
-            GlobalEffect::Load { file_name } => self.load_from_file(file_name),
+            GlobalEffect::Save { file_name } => {
+                self.save_to_file(file_name.clone());
+                self.last_save_file = Some(file_name);
+            }
+            GlobalEffect::Load { file_name } => {
+                self.load_from_file(file_name.clone());
+                self.last_save_file = Some(file_name);
+            }
             GlobalEffect::TransitionLevel { level_file, loc } => {
                 let spawn_loc = match loc {
                     None => SpawnLocation::MapDefault,
@@ -657,6 +951,25 @@ impl Game {
 
                 self.switch_mission(level_file, spawn_loc);
             }
+            GlobalEffect::ToggleDebugFlag(flag) => {
+                let is_enabled = self.options.toggle_debug_flag(flag);
+                self.console.print(format!("{flag:?}: {is_enabled}"));
+            }
+            GlobalEffect::PlayerDied => self.handle_player_death(),
+            GlobalEffect::SetLocomotion(settings) => {
+                self.console.print(format!("Locomotion: {settings:?}"));
+                if let Err(err) = settings.save(resource_path(LOCOMOTION_SETTINGS_FILE)) {
+                    self.console
+                        .print(format!("Unable to save locomotion settings: {err}"));
+                }
+                self.options.locomotion = Some(settings.clone());
+                self.locomotion.set_settings(settings);
+            }
+            GlobalEffect::SetHeadCollision(mode) => {
+                self.console.print(format!("Head collision: {mode:?}"));
+                self.options.head_collision = mode;
+                self.head_collision.set_mode(mode);
+            }
             GlobalEffect::TestReload => {
                 let (position, rotation) = {
                     let player_info = self
@@ -674,11 +987,48 @@ impl Game {
         }
     }
 
+    ///
+    /// console
+    ///
+    /// The developer console - runtimes feed it typed lines, and toggle its panel
+    pub fn console(&mut self) -> &mut Console {
+        &mut self.console
+    }
+
     pub fn render(&mut self) -> (Vec<SceneObject>, Vector3<f32>, Quaternion<f32>) {
-        let (scene, pos, rot) = self
+        let (mut scene, pos, rot) = self
             .active_mission
             .render(&mut self.asset_cache, &self.options);
 
+        scene.extend(self.locomotion.render());
+
+        {
+            let player_health = self
+                .active_mission
+                .world
+                .borrow::<UniqueView<PlayerHealth>>()
+                .unwrap();
+            scene.extend(hud::draw_health_indicator(
+                &mut self.asset_cache,
+                &player_health,
+                self.active_mission.left_hand.transform(),
+            ));
+        }
+
+        if let Some(death) = &self.death {
+            if death.is_game_over() {
+                // Nothing of the level is left - only what to do next
+                scene = hud::draw_game_over_panel(&mut self.asset_cache, death);
+            }
+        }
+
+        if self.console.is_visible() {
+            scene.extend(hud::draw_console_panel(
+                &mut self.asset_cache,
+                &self.console,
+            ));
+        }
+
         // let font = File::open(resource_path("res/fonts/mainfont.FON")).unwrap();
         // let mut font_reader = BufReader::new(font);
         // let font: Rc<Box<dyn engine::Font>> =
@@ -739,6 +1089,16 @@ impl Game {
         // Ramsey Recruitment Ctr
         // let text_string = "Ramsey Recruitment Ctr.";
         objs.extend(vec![hand_obj /*  text_obj_dynamic*/]);
+        objs.extend(self.locomotion.render_vignette(view));
+        objs.extend(self.head_collision.render_fade(view));
+
+        if let Some(death) = &self.death {
+            if death.is_game_over() {
+                return vec![];
+            }
+            objs.extend(death.render_fade(view));
+        }
+
         objs
     }
 
@@ -753,12 +1113,9 @@ impl Game {
     }
 
     fn update_music_cue_if_necessary(&mut self, new_cue: String) {
-        if self.last_music_cue.is_none() || !self.last_music_cue.as_ref().unwrap().eq(&new_cue) {
-            info!("updating music cue: {}", new_cue);
-            self.audio_context
-                .set_background_music_cue(new_cue.to_owned());
-            self.last_music_cue = Some(new_cue);
-        }
+        self.active_mission
+            .music_director
+            .set_room_cue(new_cue, &mut self.audio_context);
     }
 
     fn update_env_sound_if_necessary(&mut self, new_cue: String) {
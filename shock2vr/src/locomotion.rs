@@ -0,0 +1,520 @@
+// Locomotion
+//
+// Turning and moving the player from the thumbsticks. There are options for players that get
+// motion sick: snap turning, teleporting along an arc, and a vignette that narrows the view while
+// moving. The settings are kept in a file, so a player only has to pick them once.
+
+use std::{fs, io, path::Path};
+
+use cgmath::{
+    point3, vec3, Deg, InnerSpace, Matrix4, Quaternion, Rad, Rotation, Rotation3, SquareMatrix,
+    Vector3, Zero,
+};
+use dark::SCALE_FACTOR;
+use engine::scene::{SceneObject, VertexPosition};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    input_context::InputContext,
+    physics::{InternalCollisionGroups, PhysicsWorld, PlayerHandle},
+};
+
+// File the last chosen settings are kept in, under the data folder
+pub const LOCOMOTION_SETTINGS_FILE: &str = "locomotion.json";
+
+// Thumbstick values past this count as pushed, and under the release threshold count as centered
+const PRESS_THRESHOLD: f32 = 0.7;
+const RELEASE_THRESHOLD: f32 = 0.3;
+
+// Launch speed and gravity for the teleport arc, in units per second
+const TELEPORT_ARC_SPEED: f32 = 40.0 / SCALE_FACTOR;
+const TELEPORT_ARC_GRAVITY: f32 = 40.0 / SCALE_FACTOR;
+
+// The arc is traced as a series of segments, this far apart in time
+const TELEPORT_ARC_STEP_IN_SECONDS: f32 = 0.05;
+const TELEPORT_ARC_MAX_STEPS: usize = 40;
+
+// How upright a surface needs to be to teleport onto it
+const TELEPORT_MIN_FLOOR_NORMAL_Y: f32 = 0.7;
+
+// How far in front of the eye the vignette is drawn - just past the near plane
+const VIGNETTE_DISTANCE: f32 = 0.15;
+
+// Radius of the vignette opening when barely moving, and at full speed
+const VIGNETTE_MAX_OPENING: f32 = 0.2;
+const VIGNETTE_MIN_OPENING: f32 = 0.07;
+const VIGNETTE_OUTER_RADIUS: f32 = 1.0;
+const VIGNETTE_SEGMENTS: usize = 32;
+
+// How quickly the vignette fades in and out, per second
+const VIGNETTE_FADE_SPEED: f32 = 6.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MovementDirection {
+    // Move towards where the player is looking
+    Head,
+    // Move towards where the movement hand is pointing
+    Hand,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LocomotionSettings {
+    // Degrees to turn per flick of the thumbstick, or None to turn smoothly
+    pub snap_turn_angle: Option<f32>,
+    // Radians per second, when turning smoothly
+    pub smooth_turn_speed: f32,
+    // Units per second, with the thumbstick all the way forward
+    pub movement_speed: f32,
+    pub movement_direction: MovementDirection,
+    // Aim a teleport arc with the right thumbstick, instead of moving smoothly
+    pub teleport: bool,
+    // Narrow the view while moving and turning smoothly
+    pub vignette: bool,
+}
+
+impl LocomotionSettings {
+    ///
+    /// comfort
+    ///
+    /// Settings for players who get motion sick - snap turning, and the vignette while moving
+    pub fn comfort() -> LocomotionSettings {
+        LocomotionSettings {
+            snap_turn_angle: Some(30.0),
+            vignette: true,
+            ..LocomotionSettings::default()
+        }
+    }
+
+    ///
+    /// from_preset
+    ///
+    /// Settings by name - 'smooth', 'comfort', or 'teleport', which is comfort with teleporting
+    pub fn from_preset(name: &str) -> Option<LocomotionSettings> {
+        match name.to_ascii_lowercase().as_str() {
+            "smooth" => Some(LocomotionSettings::default()),
+            "comfort" => Some(LocomotionSettings::comfort()),
+            "teleport" => Some(LocomotionSettings {
+                teleport: true,
+                ..LocomotionSettings::comfort()
+            }),
+            _ => None,
+        }
+    }
+
+    ///
+    /// load
+    ///
+    /// The settings saved with `save`, or None if there aren't any, or they can't be read
+    pub fn load(path: impl AsRef<Path>) -> Option<LocomotionSettings> {
+        let json = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::from)?;
+        fs::write(path, json)
+    }
+}
+
+impl Default for LocomotionSettings {
+    fn default() -> Self {
+        Self {
+            snap_turn_angle: None,
+            smooth_turn_speed: 2.0,
+            movement_speed: 25.0 / SCALE_FACTOR,
+            movement_direction: MovementDirection::Head,
+            teleport: false,
+            vignette: false,
+        }
+    }
+}
+
+// Where the player should go this frame
+pub struct LocomotionOutput {
+    pub rotation: Quaternion<f32>,
+    pub movement: Vector3<f32>,
+    pub teleport_to: Option<Vector3<f32>>,
+}
+
+///
+/// Locomotion
+///
+/// Turns the thumbsticks into player movement, based on the locomotion settings
+pub struct Locomotion {
+    settings: LocomotionSettings,
+
+    is_snap_turn_ready: bool,
+
+    is_aiming_teleport: bool,
+    teleport_arc: Vec<Vector3<f32>>,
+    teleport_target: Option<Vector3<f32>>,
+
+    vignette_amount: f32,
+}
+
+impl Locomotion {
+    pub fn new(settings: LocomotionSettings) -> Locomotion {
+        Locomotion {
+            settings,
+            is_snap_turn_ready: true,
+            is_aiming_teleport: false,
+            teleport_arc: Vec::new(),
+            teleport_target: None,
+            vignette_amount: 0.0,
+        }
+    }
+
+    pub fn settings(&self) -> &LocomotionSettings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: LocomotionSettings) {
+        self.settings = settings;
+        self.is_aiming_teleport = false;
+        self.teleport_arc.clear();
+        self.teleport_target = None;
+    }
+
+    pub fn update(
+        &mut self,
+        input_context: &InputContext,
+        player_position: Vector3<f32>,
+        player_rotation: Quaternion<f32>,
+        physics: &PhysicsWorld,
+        player_handle: &PlayerHandle,
+        delta_time: f32,
+    ) -> LocomotionOutput {
+        let turn_value = input_context.left_hand.thumbstick.x;
+        let (turn_angle, turn_amount) = match self.settings.snap_turn_angle {
+            Some(snap_turn_angle) => (self.snap_turn(turn_value, snap_turn_angle), 0.0),
+            None => (
+                Rad(turn_value * delta_time * self.settings.smooth_turn_speed),
+                turn_value.abs(),
+            ),
+        };
+        let rotation = player_rotation * Quaternion::from_angle_y(turn_angle);
+
+        let move_value = input_context.right_hand.thumbstick;
+        let mut teleport_to = None;
+        let movement = if self.settings.teleport {
+            teleport_to = self.aim_teleport(
+                input_context,
+                player_position,
+                rotation,
+                physics,
+                player_handle,
+            );
+            Vector3::zero()
+        } else {
+            let direction_rotation = match self.settings.movement_direction {
+                MovementDirection::Head => rotation * input_context.head.rotation,
+                MovementDirection::Hand => rotation * input_context.right_hand.rotation,
+            };
+            let speed = delta_time * self.settings.movement_speed;
+            flatten(direction_rotation.rotate_vector(vec3(
+                -move_value.x * speed,
+                0.0,
+                -move_value.y * speed,
+            )))
+        };
+
+        let move_amount = if self.settings.teleport {
+            0.0
+        } else {
+            move_value.magnitude()
+        };
+        self.update_vignette(move_amount.max(turn_amount), delta_time);
+
+        LocomotionOutput {
+            rotation,
+            movement,
+            teleport_to,
+        }
+    }
+
+    ///
+    /// render
+    ///
+    /// The teleport arc, while aiming - green when there's somewhere to land, red otherwise
+    pub fn render(&self) -> Vec<SceneObject> {
+        if !self.is_aiming_teleport || self.teleport_arc.len() < 2 {
+            return Vec::new();
+        }
+
+        let color = if self.teleport_target.is_some() {
+            vec3(0.0, 1.0, 0.0)
+        } else {
+            vec3(1.0, 0.0, 0.0)
+        };
+
+        let vertices = self
+            .teleport_arc
+            .windows(2)
+            .flat_map(|segment| {
+                [
+                    VertexPosition {
+                        position: segment[0],
+                    },
+                    VertexPosition {
+                        position: segment[1],
+                    },
+                ]
+            })
+            .collect();
+
+        let mut scene_objects = vec![SceneObject::new(
+            engine::scene::color_material::create(color),
+            Box::new(engine::scene::lines_mesh::create(vertices)),
+        )];
+
+        // Mark the spot on the floor the arc lands on
+        if let (Some(_), Some(floor)) = (self.teleport_target, self.teleport_arc.last()) {
+            let mut marker = SceneObject::new(
+                engine::scene::color_material::create(color),
+                Box::new(engine::scene::cube::create()),
+            );
+            marker.set_transform(
+                Matrix4::from_translation(*floor) * Matrix4::from_nonuniform_scale(0.4, 0.02, 0.4),
+            );
+            scene_objects.push(marker);
+        }
+
+        scene_objects
+    }
+
+    ///
+    /// render_vignette
+    ///
+    /// A dark ring around the edge of the view, fixed to the eye, that closes in while moving
+    pub fn render_vignette(&self, view: Matrix4<f32>) -> Option<SceneObject> {
+        if !self.settings.vignette || self.vignette_amount < 0.01 {
+            return None;
+        }
+
+        let eye_transform = view.invert()?;
+        let opening = VIGNETTE_MAX_OPENING
+            + (VIGNETTE_MIN_OPENING - VIGNETTE_MAX_OPENING) * self.vignette_amount;
+
+        let mut vignette = SceneObject::new(
+            engine::scene::color_material::create(vec3(0.0, 0.0, 0.0)),
+            Box::new(engine::scene::mesh::create(ring_vertices(
+                opening,
+                VIGNETTE_OUTER_RADIUS,
+            ))),
+        );
+        vignette.set_transform(
+            eye_transform * Matrix4::from_translation(vec3(0.0, 0.0, -VIGNETTE_DISTANCE)),
+        );
+        Some(vignette)
+    }
+
+    fn snap_turn(&mut self, turn_value: f32, snap_turn_angle: f32) -> Rad<f32> {
+        if turn_value.abs() < RELEASE_THRESHOLD {
+            self.is_snap_turn_ready = true;
+        }
+
+        if self.is_snap_turn_ready && turn_value.abs() > PRESS_THRESHOLD {
+            self.is_snap_turn_ready = false;
+            Deg(snap_turn_angle * turn_value.signum()).into()
+        } else {
+            Rad(0.0)
+        }
+    }
+
+    // Aim while the thumbstick is pushed forward, and teleport when it is let go
+    fn aim_teleport(
+        &mut self,
+        input_context: &InputContext,
+        player_position: Vector3<f32>,
+        player_rotation: Quaternion<f32>,
+        physics: &PhysicsWorld,
+        player_handle: &PlayerHandle,
+    ) -> Option<Vector3<f32>> {
+        let aim_value = input_context.right_hand.thumbstick.y;
+
+        if aim_value > PRESS_THRESHOLD {
+            self.is_aiming_teleport = true;
+        }
+
+        if !self.is_aiming_teleport {
+            return None;
+        }
+
+        if aim_value < RELEASE_THRESHOLD {
+            self.is_aiming_teleport = false;
+            self.teleport_arc.clear();
+            return self.teleport_target.take();
+        }
+
+        let hand = &input_context.right_hand;
+        let origin = player_position + player_rotation.rotate_vector(hand.position);
+        let direction = (player_rotation * hand.rotation).rotate_vector(vec3(0.0, 0.0, -1.0));
+        let (arc, maybe_floor) = trace_teleport_arc(physics, origin, direction);
+
+        self.teleport_arc = arc;
+        self.teleport_target = maybe_floor
+            .and_then(|floor| physics.find_player_position_on_floor(floor, player_handle));
+        None
+    }
+
+    fn update_vignette(&mut self, motion_amount: f32, delta_time: f32) {
+        let target = if self.settings.vignette {
+            motion_amount.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let blend = (delta_time * VIGNETTE_FADE_SPEED).min(1.0);
+        self.vignette_amount += (target - self.vignette_amount) * blend;
+    }
+}
+
+///
+/// trace_teleport_arc
+///
+/// Follow a thrown arc from the hand until it hits the level. Returns the points along the arc,
+/// and the floor it lands on, if it lands somewhere upright enough to stand.
+fn trace_teleport_arc(
+    physics: &PhysicsWorld,
+    origin: Vector3<f32>,
+    direction: Vector3<f32>,
+) -> (Vec<Vector3<f32>>, Option<Vector3<f32>>) {
+    let mut arc = vec![origin];
+    let velocity = direction.normalize() * TELEPORT_ARC_SPEED;
+
+    for step in 1..=TELEPORT_ARC_MAX_STEPS {
+        let start = *arc.last().unwrap();
+        let end = arc_position(origin, velocity, step as f32 * TELEPORT_ARC_STEP_IN_SECONDS);
+        let segment = end - start;
+        let segment_length = segment.magnitude();
+
+        let maybe_hit = physics.ray_cast(
+            point3(start.x, start.y, start.z),
+            segment,
+            InternalCollisionGroups::WORLD,
+        );
+
+        match maybe_hit {
+            Some(hit)
+                if (hit.hit_point - point3(start.x, start.y, start.z)).magnitude()
+                    <= segment_length =>
+            {
+                let hit_position = vec3(hit.hit_point.x, hit.hit_point.y, hit.hit_point.z);
+                arc.push(hit_position);
+
+                let is_floor = hit.hit_normal.y > TELEPORT_MIN_FLOOR_NORMAL_Y;
+                return (arc, if is_floor { Some(hit_position) } else { None });
+            }
+            _ => arc.push(end),
+        }
+    }
+
+    (arc, None)
+}
+
+fn arc_position(origin: Vector3<f32>, velocity: Vector3<f32>, time: f32) -> Vector3<f32> {
+    origin + velocity * time + vec3(0.0, -0.5 * TELEPORT_ARC_GRAVITY * time * time, 0.0)
+}
+
+// Keep movement along the ground, at the same speed, even when looking up or down
+fn flatten(movement: Vector3<f32>) -> Vector3<f32> {
+    let horizontal = vec3(movement.x, 0.0, movement.z);
+    if horizontal.magnitude2() < f32::EPSILON {
+        return Vector3::zero();
+    }
+    horizontal.normalize() * movement.magnitude()
+}
+
+// Flat ring in the xy plane, facing down -z
+pub(crate) fn ring_vertices(inner_radius: f32, outer_radius: f32) -> Vec<VertexPosition> {
+    let point = |radius: f32, index: usize| {
+        let angle = (index as f32 / VIGNETTE_SEGMENTS as f32) * std::f32::consts::TAU;
+        VertexPosition {
+            position: vec3(angle.cos() * radius, angle.sin() * radius, 0.0),
+        }
+    };
+
+    let mut vertices = Vec::new();
+    for i in 0..VIGNETTE_SEGMENTS {
+        let next = i + 1;
+        vertices.extend([
+            point(inner_radius, i),
+            point(outer_radius, i),
+            point(outer_radius, next),
+            point(inner_radius, i),
+            point(outer_radius, next),
+            point(inner_radius, next),
+        ]);
+    }
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snap_turn_once_per_flick() {
+        // Given
+        let mut locomotion = Locomotion::new(LocomotionSettings::comfort());
+
+        // When - holding the thumbstick, then letting go and flicking again
+        let first = locomotion.snap_turn(1.0, 30.0);
+        let held = locomotion.snap_turn(1.0, 30.0);
+        let _released = locomotion.snap_turn(0.0, 30.0);
+        let second = locomotion.snap_turn(-1.0, 30.0);
+
+        // Then
+        assert_eq!(first, Rad::from(Deg(30.0)));
+        assert_eq!(held, Rad(0.0));
+        assert_eq!(second, Rad::from(Deg(-30.0)));
+    }
+
+    #[test]
+    fn test_vignette_follows_motion() {
+        // Given
+        let mut locomotion = Locomotion::new(LocomotionSettings::comfort());
+
+        // When
+        locomotion.update_vignette(1.0, 1.0);
+        let moving = locomotion.vignette_amount;
+        locomotion.update_vignette(0.0, 1.0);
+        let stopped = locomotion.vignette_amount;
+
+        // Then
+        assert_eq!(moving, 1.0);
+        assert_eq!(stopped, 0.0);
+    }
+
+    #[test]
+    fn test_flatten_keeps_speed_along_ground() {
+        // Given - looking down, while moving forward
+        let movement = vec3(0.0, -3.0, -4.0);
+
+        // When
+        let flat = flatten(movement);
+
+        // Then
+        assert_eq!(flat.y, 0.0);
+        assert!((flat.magnitude() - 5.0).abs() < 0.001);
+        assert!(flat.z < 0.0);
+    }
+
+    #[test]
+    fn test_settings_are_kept_between_runs() {
+        // Given
+        let path =
+            std::env::temp_dir().join(format!("shock2vr_locomotion_{}.json", std::process::id()));
+        let settings = LocomotionSettings::from_preset("teleport").unwrap();
+
+        // When
+        let before = LocomotionSettings::load(&path);
+        settings.save(&path).unwrap();
+        let after = LocomotionSettings::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        // Then
+        assert_eq!(before, None);
+        assert_eq!(after, Some(settings));
+    }
+}
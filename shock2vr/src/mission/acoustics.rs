@@ -0,0 +1,43 @@
+///
+/// acoustics.rs
+///
+/// Acoustic profiles for rooms - larger rooms get a longer, stronger reverb,
+/// so a cramped vent sounds different from a cargo bay.
+///
+/// The only input is the volume of the room's bounding box. Room brushes don't carry any
+/// material data, and the surfaces inside them aren't looked at, so a metal corridor and a
+/// carpeted office of the same size sound alike. An L-shaped or sloped room is treated as
+/// the box around it, which makes it sound a bit larger than it is. Openings to other rooms
+/// (portals) aren't taken into account either.
+///
+use std::time::Duration;
+
+use dark::mission::room::Room;
+use engine::audio::ReverbSettings;
+
+// Room volumes (in world units, cubed) that map to the driest and the most reverberant profiles
+const SMALL_ROOM_VOLUME: f32 = 50.0;
+const LARGE_ROOM_VOLUME: f32 = 20000.0;
+
+const MIN_REVERB_DELAY_MS: f32 = 20.0;
+const MAX_REVERB_DELAY_MS: f32 = 120.0;
+
+const MIN_REVERB_AMPLITUDE: f32 = 0.1;
+const MAX_REVERB_AMPLITUDE: f32 = 0.45;
+
+pub fn reverb_for_room(room: &Room) -> ReverbSettings {
+    let volume = room.volume().max(1.0);
+
+    // Perceived 'size' of a space is closer to logarithmic than linear in volume
+    let amount = ((volume.log10() - SMALL_ROOM_VOLUME.log10())
+        / (LARGE_ROOM_VOLUME.log10() - SMALL_ROOM_VOLUME.log10()))
+    .clamp(0.0, 1.0);
+
+    let delay_ms = MIN_REVERB_DELAY_MS + (MAX_REVERB_DELAY_MS - MIN_REVERB_DELAY_MS) * amount;
+    let amplitude = MIN_REVERB_AMPLITUDE + (MAX_REVERB_AMPLITUDE - MIN_REVERB_AMPLITUDE) * amount;
+
+    ReverbSettings {
+        delay: Duration::from_secs_f32(delay_ms / 1000.0),
+        amplitude,
+    }
+}
@@ -0,0 +1,149 @@
+///
+/// animated_lightmaps.rs
+///
+/// Keeps the lightmap atlas in sync with the animated lights - whenever a light's intensity changes,
+/// the lightmaps it touches are recombined and uploaded to the lightmap texture.
+///
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use dark::{
+    mission::SystemShock2Level,
+    properties::{PropAnimLight, PropTweqBlinkState, TweqAnimationState},
+};
+use engine::{
+    texture::{self, Texture},
+    texture_format::{PixelFormat, RawTextureData},
+};
+use shipyard::{Get, IntoIter, IntoWithId, View, World};
+
+// Changes in intensity smaller than this aren't visible in the lightmap
+const INTENSITY_EPSILON: f32 = 1.0 / 255.0;
+
+pub struct AnimatedLightmaps {
+    lightmap_texture: Rc<Texture>,
+    light_to_lightmaps: HashMap<i16, Vec<(usize, usize)>>,
+    light_to_intensity: HashMap<i16, f32>,
+    needs_full_refresh: bool,
+}
+
+impl AnimatedLightmaps {
+    pub fn new(level: &SystemShock2Level, lightmap_texture: Rc<Texture>) -> AnimatedLightmaps {
+        AnimatedLightmaps {
+            lightmap_texture,
+            light_to_lightmaps: level.lightmaps_by_animated_light(),
+            light_to_intensity: HashMap::new(),
+            needs_full_refresh: true,
+        }
+    }
+
+    pub fn update(&mut self, world: &World, level: &SystemShock2Level) {
+        if self.light_to_lightmaps.is_empty() {
+            return;
+        }
+
+        let intensities: HashMap<i16, f32> = world.run(
+            |v_anim_light: View<PropAnimLight>, v_tweq_blink_state: View<PropTweqBlinkState>| {
+                v_anim_light
+                    .iter()
+                    .with_id()
+                    .map(|(entity_id, light)| {
+                        // A blink tweq on the light object switches it on and off
+                        let is_blinked_off = match v_tweq_blink_state.get(entity_id) {
+                            Ok(blink_state) => {
+                                blink_state.animation_state.contains(TweqAnimationState::ON)
+                                    && !blink_state.is_lit
+                            }
+                            Err(_) => false,
+                        };
+
+                        let intensity = if is_blinked_off {
+                            0.0
+                        } else {
+                            light.intensity()
+                        };
+                        (light.light_index, intensity)
+                    })
+                    .collect()
+            },
+        );
+
+        // Lights whose objects have been destroyed go dark
+        let removed_lights: Vec<i16> = self
+            .light_to_intensity
+            .keys()
+            .filter(|light_index| !intensities.contains_key(light_index))
+            .copied()
+            .collect();
+
+        let mut changed_lights = Vec::new();
+        for (light_index, intensity) in intensities.into_iter().chain(
+            removed_lights
+                .into_iter()
+                .map(|light_index| (light_index, 0.0)),
+        ) {
+            let has_changed = match self.light_to_intensity.insert(light_index, intensity) {
+                Some(previous_intensity) => {
+                    (previous_intensity - intensity).abs() > INTENSITY_EPSILON
+                }
+                None => true,
+            };
+
+            if has_changed {
+                changed_lights.push(light_index);
+            }
+        }
+
+        let dirty_lightmaps: HashSet<(usize, usize)> = if self.needs_full_refresh {
+            self.needs_full_refresh = false;
+            self.light_to_lightmaps
+                .values()
+                .flatten()
+                .copied()
+                .collect()
+        } else {
+            changed_lights
+                .iter()
+                .filter_map(|light_index| self.light_to_lightmaps.get(light_index))
+                .flatten()
+                .copied()
+                .collect()
+        };
+
+        for (cell_idx, light_info_idx) in dirty_lightmaps {
+            self.refresh_lightmap(level, cell_idx, light_info_idx);
+        }
+    }
+
+    fn refresh_lightmap(&self, level: &SystemShock2Level, cell_idx: usize, light_info_idx: usize) {
+        let light_info = &level.cells[cell_idx].lights[light_info_idx];
+
+        // Lights without an object (or not yet seen) contribute as they were baked
+        let maybe_lightmap = light_info.combine_lightmaps(|light_index| {
+            self.light_to_intensity
+                .get(&light_index)
+                .copied()
+                .unwrap_or(1.0)
+        });
+
+        if let Some(lightmap) = maybe_lightmap {
+            let pack_result = &light_info.texture_pack_result;
+            let x = (pack_result.uv_offset_x * self.lightmap_texture.width() as f32).round();
+            let y = (pack_result.uv_offset_y * self.lightmap_texture.height() as f32).round();
+
+            texture::update_region(
+                &self.lightmap_texture,
+                x as u32,
+                y as u32,
+                &RawTextureData {
+                    width: lightmap.width(),
+                    height: lightmap.height(),
+                    bytes: lightmap.into_raw(),
+                    format: PixelFormat::RGB,
+                },
+            );
+        }
+    }
+}
@@ -232,8 +232,31 @@ pub fn create_entity_core(
         None
     };
 
-    //let output_scripts = vec![];
     // Create scripts
+    let processed_scripts = scripts_for_entity(world, entity_id);
+
+    let mut output_scripts = Vec::new();
+    for script in processed_scripts {
+        output_scripts.push(script.to_owned());
+        script_world.add_entity(entity_id, &script);
+    }
+
+    EntityCreationInfo {
+        entity_id,
+        bitmap_animation,
+        model: maybe_model,
+        rigid_body,
+        scripts: output_scripts,
+    }
+}
+
+///
+/// scripts_for_entity
+///
+/// The names of the scripts to run for the entity - the scripts from its props, along with any
+/// internal scripts needed to power its other properties
+///
+pub fn scripts_for_entity(world: &World, entity_id: EntityId) -> Vec<String> {
     let v_scripts = world
         .borrow::<View<dark::properties::PropScripts>>()
         .unwrap();
@@ -276,23 +299,27 @@ pub fn create_entity_core(
         processed_scripts.push("internal_keycard".to_owned());
     }
 
-    // ...and remove any duplicates!
-    processed_scripts.sort_unstable();
-    processed_scripts.dedup();
-
-    let mut output_scripts = Vec::new();
-    for script in processed_scripts {
-        output_scripts.push(script.to_owned());
-        script_world.add_entity(entity_id, &script);
+    // Nanite piles go straight to the player's nanite count
+    let v_sym_name = world.borrow::<View<PropSymName>>().unwrap();
+    if v_sym_name
+        .get(entity_id)
+        .map_or(false, |sym_name| sym_name.0.eq_ignore_ascii_case("nanites"))
+    {
+        processed_scripts.push("internal_nanites".to_owned());
     }
 
-    EntityCreationInfo {
-        entity_id,
-        bitmap_animation,
-        model: maybe_model,
-        rigid_body,
-        scripts: output_scripts,
+    // Anything that can be picked up can be thrown, and hurt what it hits
+    let v_frob_info = world.borrow::<View<PropFrobInfo>>().unwrap();
+    if v_frob_info.get(entity_id).map_or(false, |frob_info| {
+        frob_info.world_action.contains(FrobFlag::Move)
+    }) {
+        processed_scripts.push("internal_impact_damage".to_owned());
     }
+
+    // ...and remove any duplicates!
+    processed_scripts.sort_unstable();
+    processed_scripts.dedup();
+    processed_scripts
 }
 
 fn initialize_sym_name_from_obj_map(
@@ -772,6 +799,14 @@ pub fn create_physics_representation(
                     is_sensor,
                 )
             };
+
+            // Ladders are objects with climbable sides
+            if v_phys_attr
+                .get(entity_id)
+                .map_or(false, |phys_attr| phys_attr.climbable != 0)
+            {
+                physics.set_climbable(entity_id);
+            }
             Some(rigid_body_handle)
         } else {
             None
@@ -782,12 +817,15 @@ pub fn create_physics_representation(
 #[derive(Clone, Debug)]
 pub struct CreateEntityOptions {
     pub force_visible: bool,
+    // Entity firing the projectile being created - the projectile passes through it
+    pub launcher: Option<EntityId>,
 }
 
 impl Default for CreateEntityOptions {
     fn default() -> Self {
         CreateEntityOptions {
             force_visible: false,
+            launcher: None,
         }
     }
 }
@@ -30,46 +30,62 @@ impl EntityPopulator for MissionEntityPopulator {
         level: &SystemShock2Level,
         world: &mut World,
     ) -> HashMap<i32, WrappedEntityId> {
-        let mut template_to_entity_id = HashMap::new();
-        let mut all_entities = Vec::new();
-        for (template_id, _props) in &level.entity_info.entity_to_properties {
-            // Create the entity
-            let entity = world.add_entity(());
-            template_to_entity_id.insert(*template_id, WrappedEntityId(entity));
+        populate_from_entity_info(gamesys_entity_info, &level.entity_info, &level.obj_map, world)
+    }
+}
 
-            all_entities.push((*template_id, entity))
-        }
+///
+/// populate_from_entity_info
+///
+/// Create an entity for everything in the mission's entity info, with properties and links
+/// inherited from the merged gamesys entity info. Doesn't need the level geometry, so it can be
+/// used without loading the full level.
+///
+pub fn populate_from_entity_info(
+    gamesys_entity_info: &SystemShock2EntityInfo,
+    mission_entity_info: &SystemShock2EntityInfo,
+    obj_map: &HashMap<i32, String>,
+    world: &mut World,
+) -> HashMap<i32, WrappedEntityId> {
+    let mut template_to_entity_id = HashMap::new();
+    let mut all_entities = Vec::new();
+    for (template_id, _props) in &mission_entity_info.entity_to_properties {
+        // Create the entity
+        let entity = world.add_entity(());
+        template_to_entity_id.insert(*template_id, WrappedEntityId(entity));
 
-        // Second pass - hydrate properties
-        for (template_id, _props) in &level.entity_info.entity_to_properties {
-            let entity = template_to_entity_id.get(template_id).unwrap();
-            entity_creator::initialize_entity_with_props(
-                *template_id,
-                gamesys_entity_info,
-                world,
-                entity.0,
-                &level.obj_map,
-            );
-        }
+        all_entities.push((*template_id, entity))
+    }
 
-        // HACK: If the entity is an 'AI' entity, it is rotated 90 degrees to the left.
-        // This is a hack to fix that. Probably a bug somewhere else in the pipeline...
-        // hopefully we can find/fix the root cause and remove this hack.
-        hack_rotate_ai_entities(world);
+    // Second pass - hydrate properties
+    for (template_id, _props) in &mission_entity_info.entity_to_properties {
+        let entity = template_to_entity_id.get(template_id).unwrap();
+        entity_creator::initialize_entity_with_props(
+            *template_id,
+            gamesys_entity_info,
+            world,
+            entity.0,
+            obj_map,
+        );
+    }
 
-        // Third pass - initialize links for entity
-        for (template_id, entity_id) in all_entities {
-            entity_creator::initialize_links_for_entity(
-                template_id,
-                entity_id,
-                gamesys_entity_info,
-                &template_to_entity_id,
-                world,
-            );
-        }
+    // HACK: If the entity is an 'AI' entity, it is rotated 90 degrees to the left.
+    // This is a hack to fix that. Probably a bug somewhere else in the pipeline...
+    // hopefully we can find/fix the root cause and remove this hack.
+    hack_rotate_ai_entities(world);
 
-        template_to_entity_id
+    // Third pass - initialize links for entity
+    for (template_id, entity_id) in all_entities {
+        entity_creator::initialize_links_for_entity(
+            template_id,
+            entity_id,
+            gamesys_entity_info,
+            &template_to_entity_id,
+            world,
+        );
     }
+
+    template_to_entity_id
 }
 
 fn hack_rotate_ai_entities(world: &mut World) {
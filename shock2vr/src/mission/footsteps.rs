@@ -0,0 +1,96 @@
+///
+/// footsteps.rs
+///
+/// Helpers for footstep sounds - tracking the distance the player walks, and building the
+/// environmental sound query from the surface material and speed.
+///
+use cgmath::{vec2, InnerSpace, Vector3};
+use dark::{properties::PropClassTag, EnvSoundQuery, SCALE_FACTOR};
+use shipyard::{EntityId, Get, View, World};
+
+use crate::{scripts::Effect, time::Time};
+
+// Distance travelled between footsteps
+const STRIDE_LENGTH: f32 = 5.0 / SCALE_FACTOR;
+
+// Speed above which footsteps use the run sounds
+pub const FOOTSTEP_RUN_SPEED: f32 = 15.0 / SCALE_FACTOR;
+
+// Movement larger than this in a single frame is a teleport, not a step
+const MAX_STEP_DISTANCE: f32 = 5.0 / SCALE_FACTOR;
+
+pub struct FootstepTracker {
+    last_position: Option<Vector3<f32>>,
+    distance_since_last_step: f32,
+}
+
+impl FootstepTracker {
+    pub fn new() -> FootstepTracker {
+        FootstepTracker {
+            last_position: None,
+            distance_since_last_step: 0.0,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        entity_id: EntityId,
+        position: Vector3<f32>,
+        time: &Time,
+    ) -> Option<Effect> {
+        let last_position = self.last_position.replace(position)?;
+        let distance = vec2(position.x - last_position.x, position.z - last_position.z).magnitude();
+
+        if distance > MAX_STEP_DISTANCE {
+            self.distance_since_last_step = 0.0;
+            return None;
+        }
+
+        self.distance_since_last_step += distance;
+
+        if self.distance_since_last_step < STRIDE_LENGTH {
+            return None;
+        }
+
+        self.distance_since_last_step = 0.0;
+
+        let elapsed = time.elapsed.as_secs_f32();
+        let speed = if elapsed > 0.0 {
+            distance / elapsed
+        } else {
+            0.0
+        };
+
+        Some(Effect::PlayFootstep {
+            entity_id,
+            position,
+            is_running: speed > FOOTSTEP_RUN_SPEED,
+        })
+    }
+}
+
+///
+/// footstep_query
+///
+/// Build the environmental sound query for a footstep of the entity on the given material
+pub fn footstep_query(
+    world: &World,
+    entity_id: EntityId,
+    maybe_material: Option<&str>,
+    is_running: bool,
+) -> EnvSoundQuery {
+    let speed = if is_running { "run" } else { "walk" };
+
+    let mut tags = vec![("event", "footstep"), ("speed", speed)];
+
+    if let Some(material) = maybe_material {
+        tags.push(("material", material));
+    }
+
+    let v_class_tag = world.borrow::<View<PropClassTag>>().unwrap();
+    if let Ok(class_tag) = v_class_tag.get(entity_id) {
+        tags.append(&mut class_tag.class_tags());
+    }
+
+    EnvSoundQuery::from_tag_values(tags)
+}
@@ -1,9 +1,17 @@
+mod acoustics;
+mod animated_lightmaps;
 pub mod entity_creator;
 pub mod entity_populator;
+mod footsteps;
+mod music_director;
 mod spawn_location;
 pub mod visibility_engine;
 
+pub use acoustics::*;
+pub use animated_lightmaps::*;
 use collision::Aabb;
+pub use footsteps::*;
+pub use music_director::*;
 pub use spawn_location::*;
 pub use visibility_engine::*;
 
@@ -29,11 +37,12 @@ use dark::{
     model::Model,
     motion::{AnimationEvent, AnimationPlayer, MotionDB, MotionQuery, MotionQueryItem},
     properties::{
-        Link, LinkDefinition, LinkDefinitionWithData, Links, PhysicsModelType, PropCreature,
-        PropFrameAnimState, PropHasRefs, PropLocalPlayer, PropModelName, PropMotionActorTags,
-        PropParticleGroup, PropParticleLaunchInfo, PropPhysDimensions, PropPhysInitialVelocity,
-        PropPhysState, PropPhysType, PropPosition, PropRenderType, PropScripts, PropTeleported,
-        PropTripFlags, PropertyDefinition, RenderType, ToLink, TripFlags, WrappedEntityId,
+        Link, LinkDefinition, LinkDefinitionWithData, Links, PhysicsModelType, PropAnimLight,
+        PropCreature, PropFrameAnimState, PropHasRefs, PropLocalPlayer, PropModelName,
+        PropMotionActorTags, PropParticleGroup, PropParticleLaunchInfo, PropPhysDimensions,
+        PropPhysInitialVelocity, PropPhysState, PropPhysType, PropPosition, PropRenderType,
+        PropScripts, PropTeleported, PropTripFlags, PropTweqBlinkState, PropertyDefinition,
+        RenderType, ToLink, TripFlags, TweqAnimationState, WrappedEntityId,
     },
     ss2_entity_info::{self, SystemShock2EntityInfo},
     BitmapAnimation, SCALE_FACTOR,
@@ -55,17 +64,20 @@ use shipyard::{self, View, World};
 use tracing::{info, trace, warn};
 
 use crate::{
-    creature::{get_creature_definition, HitBoxManager},
+    creature::{get_creature_definition, HitBoxManager, SpeechManager},
     gui::GuiManager,
+    holster::{HolsterSlot, Holsters},
     hud::{draw_item_name, draw_item_outline},
     input_context::{self},
     inventory::PlayerInventoryEntity,
     mission::entity_populator::EntityPopulator,
     physics::{self, PlayerHandle},
+    player_health::PlayerHealth,
     quest_info::QuestInfo,
     runtime_props::{
-        RuntimePropDoNotSerialize, RuntimePropJointTransforms, RuntimePropProxyEntity,
-        RuntimePropTransform, RuntimePropVhots,
+        RuntimePropAIAwareness, RuntimePropDoNotSerialize, RuntimePropHeldVelocity,
+        RuntimePropJointTransforms, RuntimePropProxyEntity, RuntimePropTransform,
+        RuntimePropVhots,
     },
     save_load::HeldItemSaveData,
     scripts::{
@@ -74,7 +86,10 @@ use crate::{
         script_util::{get_all_links_with_template, get_environmental_sound_query},
         Effect, GlobalEffect, Message, MessagePayload,
     },
-    systems::{run_bitmap_animation, run_tweq, turn_off_tweqs, turn_on_tweqs},
+    systems::{
+        run_anim_light, run_bitmap_animation, run_blink_tweq, run_joint_tweq, run_rotate_tweq,
+        run_scale_tweq, run_tweq, turn_off_tweqs, turn_on_tweqs,
+    },
     time::Time,
     util::{get_email_sound_file, has_refs, resolve_proxy_entity, vec3_to_point3},
     virtual_hand::{VirtualHand, VirtualHandEffect},
@@ -103,6 +118,7 @@ pub struct PlayerInfo {
 
     pub left_hand_entity_id: Option<EntityId>,
     pub right_hand_entity_id: Option<EntityId>,
+    pub holstered_entity_ids: HashMap<HolsterSlot, EntityId>,
     pub inventory_entity_id: EntityId,
 }
 
@@ -133,6 +149,12 @@ pub struct GlobalEntityMetadata(pub HashMap<String, EntityMetadata>);
 pub struct GlobalTemplateIdMap(pub HashMap<i32, WrappedEntityId>);
 
 impl EffectQueue {
+    pub fn new() -> EffectQueue {
+        EffectQueue {
+            effects: Vec::new(),
+        }
+    }
+
     pub fn push(&mut self, effect: Effect) {
         self.effects.push(effect);
     }
@@ -148,6 +170,10 @@ pub struct Mission {
     pub level_name: String,
     pub gui: GuiManager,
     pub hit_boxes: HitBoxManager,
+    pub speech: SpeechManager,
+    pub music_director: MusicDirector,
+    pub footsteps: FootstepTracker,
+    pub animated_lightmaps: AnimatedLightmaps,
     pub debug_lines: Vec<DebugLine>,
     pub entity_info: SystemShock2EntityInfo,
     pub physics: PhysicsWorld,
@@ -165,6 +191,7 @@ pub struct Mission {
     pub level: SystemShock2Level,
     pub left_hand: VirtualHand,
     pub right_hand: VirtualHand,
+    pub holsters: Holsters,
     pub visibility_engine: Box<dyn VisibilityEngine>,
 }
 
@@ -184,6 +211,7 @@ impl Mission {
         global_context: &GlobalContext,
         spawn_loc: SpawnLocation,
         quest_info: QuestInfo,
+        player_health: PlayerHealth,
         entity_populator: Box<dyn EntityPopulator>,
         held_item_save_data: HeldItemSaveData,
     ) -> Mission {
@@ -206,7 +234,9 @@ impl Mission {
             links_with_data,
             properties,
         );
-        let mut scene = dark::mission::to_scene(&level, asset_cache);
+        let lightmap_texture = level.lightmap_atlas.generate_textures()[0].clone();
+        let animated_lightmaps = AnimatedLightmaps::new(&level, lightmap_texture.clone());
+        let mut scene = dark::mission::to_scene(&level, asset_cache, lightmap_texture);
         let duration: Duration = start.elapsed().unwrap();
         info!("loading level took {}s", duration.as_secs_f32());
 
@@ -232,7 +262,7 @@ impl Mission {
         // Instantiate held items
         let mut left_hand = VirtualHand::new(vr_config::Handedness::Left);
         let mut right_hand = VirtualHand::new(vr_config::Handedness::Right);
-        let (left_hand_entity, right_hand_entity, maybe_inventory_entity) =
+        let (left_hand_entity, right_hand_entity, maybe_inventory_entity, holstered_entities) =
             held_item_save_data.instantiate(&mut world);
 
         // Instantiate inventory
@@ -253,7 +283,7 @@ impl Mission {
         world.add_unique(GlobalTemplateIdMap(template_to_entity_id.clone()));
 
         // Start background music
-        initialize_background_music(&level, asset_cache, audio_context);
+        let music_director = initialize_background_music(&level, asset_cache, audio_context);
 
         let mut entities_to_instantiate = HashSet::new();
 
@@ -309,6 +339,7 @@ impl Mission {
                 &mut script_world,
                 created_entity,
                 Matrix4::identity(),
+                None,
             );
         }
 
@@ -326,10 +357,15 @@ impl Mission {
             make_un_physical2(&mut id_to_physics, &mut physics, entity_id);
         };
 
+        for entity_id in holstered_entities.values() {
+            make_un_physical2(&mut id_to_physics, &mut physics, *entity_id);
+        }
+
         let (start_pos, start_rotation) =
             spawn_loc.calculate_start_position(&world, &level.entity_info, &template_to_entity_id);
 
         let player_handle = physics.create_player(start_pos, player_entity);
+        script_world.add_entity(player_entity, "internal_player_health");
 
         world.add_unique(PlayerInfo {
             rotation: start_rotation,
@@ -337,19 +373,20 @@ impl Mission {
             entity_id: player_entity,
             left_hand_entity_id: None,
             right_hand_entity_id: None,
+            holstered_entity_ids: holstered_entities.clone(),
             inventory_entity_id: inventory,
         });
 
         world.add_unique(quest_info);
+        world.add_unique(player_health);
 
-        world.add_unique(EffectQueue {
-            effects: Vec::new(),
-        });
+        world.add_unique(EffectQueue::new());
 
         Mission {
             level,
             left_hand,
             right_hand,
+            holsters: Holsters::new(holstered_entities),
             level_name: mission,
             entity_info,
             script_world,
@@ -367,6 +404,10 @@ impl Mission {
             debug_lines: Vec::new(),
             gui: GuiManager::new(),
             hit_boxes: HitBoxManager::new(),
+            speech: SpeechManager::new(),
+            music_director,
+            footsteps: FootstepTracker::new(),
+            animated_lightmaps,
             visibility_engine: Box::new(PortalVisibilityEngine::new()),
         }
     }
@@ -381,9 +422,9 @@ impl Mission {
         self.world.add_unique(time.clone());
         let mut effects = Vec::new();
 
-        let (player_pos, player_rot) = {
+        let (player_pos, player_rot, player_entity_id) = {
             let player_info = self.world.borrow::<UniqueView<PlayerInfo>>().unwrap();
-            (player_info.pos, player_info.rotation)
+            (player_info.pos, player_info.rotation, player_info.entity_id)
         };
 
         self.debug_lines.iter_mut().for_each(|p| {
@@ -403,7 +444,7 @@ impl Mission {
             &mut self.id_to_physics,
         );
 
-        self.update_avatar_hands(asset_cache, player_pos, player_rot, input_context);
+        self.update_avatar_hands(asset_cache, player_pos, player_rot, input_context, time);
 
         // Sync up the position of all the physics objects
         // The timing of this is important - things like the GUI rendering depend on an up-to-date position
@@ -417,9 +458,18 @@ impl Mission {
         );
         effects.append(&mut script_effects);
 
+        effects.extend(self.footsteps.update(player_entity_id, player_pos, time));
+
         self.world.run(run_tweq);
+        self.world.run(run_rotate_tweq);
+        self.world.run(run_scale_tweq);
+        self.world.run(run_joint_tweq);
+        self.world.run(run_blink_tweq);
+        self.world.run(run_anim_light);
         self.world.run(run_bitmap_animation);
 
+        self.animated_lightmaps.update(&self.world, &self.level);
+
         self.gui.update();
 
         let mut current_effects = self.world.borrow::<UniqueViewMut<EffectQueue>>().unwrap();
@@ -646,6 +696,44 @@ impl Mission {
         }
     }
 
+    ///
+    /// contain_entity
+    ///
+    /// Move an entity into a container, like the player's inventory - replacing any container it
+    /// was in before
+    pub fn contain_entity(&mut self, parent_entity_id: EntityId, dropped_entity_id: EntityId) {
+        let mut was_able_to_drop = false;
+        {
+            // First, remove any existing contains links for the dropped entity..
+            let mut v_links = self.world.borrow::<ViewMut<Links>>().unwrap();
+
+            for (id, links) in (&mut v_links).iter().with_id() {
+                links.to_links.retain(|link| {
+                    let is_link_to_entity = matches!(link.link, Link::Contains(_))
+                        && link.to_entity_id.is_some()
+                        && link.to_entity_id.unwrap().0 == dropped_entity_id;
+
+                    !is_link_to_entity
+                });
+
+                // If it is the parent, we'll add the link!
+                if id == parent_entity_id {
+                    links.to_links.push(ToLink {
+                        link: Link::Contains(0),
+                        to_entity_id: Some(dark::properties::WrappedEntityId(dropped_entity_id)),
+                        to_template_id: 0, // todo?
+                    });
+                    was_able_to_drop = true;
+                }
+            }
+        }
+        if was_able_to_drop {
+            self.world
+                .add_component(dropped_entity_id, PropHasRefs(false));
+            self.make_un_physical(dropped_entity_id);
+        }
+    }
+
     pub fn make_un_physical(&mut self, entity_id: EntityId) {
         let current_entity = self.id_to_physics.get(&entity_id);
         if current_entity.is_none() {
@@ -722,6 +810,7 @@ impl Mission {
         root_transform: Matrix4<f32>,
         additional_options: CreateEntityOptions,
     ) -> EntityCreationInfo {
+        let launcher = additional_options.launcher;
         let created_entity = {
             entity_creator::create_entity_with_position(
                 template_id,
@@ -750,6 +839,7 @@ impl Mission {
             &mut self.script_world,
             created_entity,
             root_transform,
+            launcher,
         )
     }
 
@@ -763,6 +853,7 @@ impl Mission {
         script_world: &mut ScriptWorld,
         created_entity: EntityCreationInfo,
         root_transform: Matrix4<f32>,
+        launcher: Option<EntityId>,
     ) -> EntityCreationInfo {
         let ret = created_entity.clone();
 
@@ -793,7 +884,7 @@ impl Mission {
                 // Use raycast strategy for fast moving objects
                 script_world.add_entity2(
                     created_entity.entity_id,
-                    Box::new(InternalFastProjectileScript::new(x_velocity)),
+                    Box::new(InternalFastProjectileScript::new(x_velocity, launcher)),
                 );
                 // HACK: Don't use physics for these entities...
                 physics.remove(created_entity.entity_id);
@@ -817,6 +908,7 @@ impl Mission {
         );
 
         self.script_world.remove_entity(entity_id);
+        self.speech.remove_speaker(entity_id);
         self.id_to_bitmap.remove(&entity_id);
         self.id_to_model.remove(&entity_id);
         self.id_to_physics.remove(&entity_id);
@@ -858,10 +950,31 @@ impl Mission {
                     }
                 }
 
+                Effect::DamagePlayer {
+                    amount,
+                    damage_type,
+                } => {
+                    let mut player_health =
+                        self.world.borrow::<UniqueViewMut<PlayerHealth>>().unwrap();
+                    if player_health.take_damage(amount, damage_type) {
+                        global_effects.push(GlobalEffect::PlayerDied);
+                    }
+                }
+
                 Effect::AwardXP { amount } => {
                     warn!("!! TODO !!: Award XP {}", amount);
                 }
 
+                Effect::AwardNanites { amount } => {
+                    let mut quests = self.world.borrow::<UniqueViewMut<QuestInfo>>().unwrap();
+                    quests.add_nanites(amount);
+                }
+
+                Effect::ActivateResurrectionStation { position, rotation } => {
+                    let mut quests = self.world.borrow::<UniqueViewMut<QuestInfo>>().unwrap();
+                    quests.activate_resurrection_station(&self.level_name, position, rotation);
+                }
+
                 Effect::DrawDebugLines { lines } => {
                     if game_options.debug_draw {
                         for line in lines {
@@ -908,38 +1021,23 @@ impl Mission {
                     parent_entity_id,
                     dropped_entity_id,
                 } => {
-                    let mut was_able_to_drop = false;
-                    {
-                        // First, remove any existing contains links for the dropped entity..
-                        let mut v_links = self.world.borrow::<ViewMut<Links>>().unwrap();
-
-                        for (id, links) in (&mut v_links).iter().with_id() {
-                            links.to_links.retain(|link| {
-                                let is_link_to_entity = matches!(link.link, Link::Contains(_))
-                                    && link.to_entity_id.is_some()
-                                    && link.to_entity_id.unwrap().0 == dropped_entity_id;
+                    self.contain_entity(parent_entity_id, dropped_entity_id);
+                }
 
-                                !is_link_to_entity
-                            });
-
-                            // If it is the parent, we'll add the link!
-                            if id == parent_entity_id {
-                                links.to_links.push(ToLink {
-                                    link: Link::Contains(0),
-                                    to_entity_id: Some(dark::properties::WrappedEntityId(
-                                        dropped_entity_id,
-                                    )),
-                                    to_template_id: 0, // todo?
-                                });
-                                was_able_to_drop = true;
-                            }
-                        }
-                    }
-                    if was_able_to_drop {
-                        self.world
-                            .add_component(dropped_entity_id, PropHasRefs(false));
-                        self.make_un_physical(dropped_entity_id);
-                    }
+                Effect::GiveItem { template_id } => {
+                    let (position, inventory_entity_id) = {
+                        let player_info = self.world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+                        (player_info.pos, player_info.inventory_entity_id)
+                    };
+                    let creation_info = self.create_entity_with_position(
+                        asset_cache,
+                        template_id,
+                        vec3_to_point3(position),
+                        Quaternion::new(1.0, 0.0, 0.0, 0.0),
+                        Matrix4::identity(),
+                        CreateEntityOptions::default(),
+                    );
+                    self.contain_entity(inventory_entity_id, creation_info.entity_id);
                 }
 
                 Effect::GrabEntity {
@@ -1187,6 +1285,22 @@ impl Mission {
                         position,
                     );
                 }
+                Effect::PlayFootstep {
+                    entity_id,
+                    position,
+                    is_running,
+                } => {
+                    let maybe_material = self.level.get_surface_material(position);
+                    let query = footstep_query(&self.world, entity_id, maybe_material, is_running);
+                    play_environmental_sound(
+                        &global_context.gamesys,
+                        asset_cache,
+                        audio_context,
+                        query,
+                        AudioHandle::new(),
+                        position,
+                    );
+                }
                 Effect::SlayEntity { entity_id } => {
                     let did_slay = self.slay_entity(entity_id, asset_cache);
 
@@ -1215,10 +1329,19 @@ impl Mission {
                 Effect::StopSound { handle } => {
                     engine::audio::stop_audio(audio_context, handle);
                 }
+                Effect::SetSoundVolume { handle, volume } => {
+                    engine::audio::set_volume(audio_context, handle, volume);
+                }
+                Effect::ApplyForce { entity_id, force } => {
+                    if let Some(rigid_body_handle) = self.id_to_physics.get(&entity_id) {
+                        self.physics.apply_force(*rigid_body_handle, force);
+                    }
+                }
                 Effect::DestroyEntity { entity_id } => {
                     info!("!!!Destroying entity: {:?}", entity_id);
                     self.left_hand = self.left_hand.destroy_entity(entity_id);
                     self.right_hand = self.right_hand.destroy_entity(entity_id);
+                    self.holsters.remove_entity(entity_id);
                     self.remove_entity(entity_id);
                 }
                 Effect::ResetGravity { entity_id } => {
@@ -1230,6 +1353,13 @@ impl Mission {
                 } => {
                     self.physics.set_gravity(entity_id, gravity_percent);
                 }
+                Effect::SetAIAwareness {
+                    entity_id,
+                    awareness,
+                } => {
+                    self.world
+                        .add_component(entity_id, RuntimePropAIAwareness(awareness));
+                }
                 Effect::SetPlayerPosition {
                     position,
                     is_teleport,
@@ -1284,6 +1414,16 @@ impl Mission {
                         self.physics.set_translation(*rigid_body_handle, position);
                     };
                 }
+                Effect::Speak { entity_id, concept } => {
+                    self.speech.speak(
+                        &self.world,
+                        &global_context.gamesys,
+                        asset_cache,
+                        audio_context,
+                        entity_id,
+                        &concept,
+                    );
+                }
                 Effect::SetRotation {
                     entity_id,
                     rotation,
@@ -1299,12 +1439,19 @@ impl Mission {
                         rotation,
                     )
                 }
+                Effect::SetLightOn { entity_id, is_on } => {
+                    let mut v_anim_light = self.world.borrow::<ViewMut<PropAnimLight>>().unwrap();
+                    if let Ok(light) = (&mut v_anim_light).get(entity_id) {
+                        light.is_on = is_on;
+                    }
+                }
                 Effect::TurnOffTweqs { entity_id } => {
-                    self.world.run_with_data(turn_off_tweqs, entity_id);
+                    turn_off_tweqs(&self.world, entity_id);
                 }
                 Effect::TurnOnTweqs { entity_id } => {
-                    self.world.run_with_data(turn_on_tweqs, entity_id);
+                    turn_on_tweqs(&self.world, entity_id);
                 }
+                Effect::Send { msg } => self.script_world.dispatch(msg),
                 Effect::GlobalEffect(global_effect) => global_effects.push(global_effect),
                 _ => println!("Unhandled effect: {effect:?}"),
             }
@@ -1394,6 +1541,7 @@ impl Mission {
         let v_transform = self.world.borrow::<View<RuntimePropTransform>>().unwrap();
         let v_frame_state = self.world.borrow::<View<PropFrameAnimState>>().unwrap();
         let v_render_type = self.world.borrow::<View<PropRenderType>>().unwrap();
+        let v_tweq_blink_state = self.world.borrow::<View<PropTweqBlinkState>>().unwrap();
 
         // Start with built in scene objects
         let mut scene = self.scene_objects.clone();
@@ -1416,6 +1564,15 @@ impl Mission {
                 };
             }
 
+            // Blinking objects are hidden for the unlit part of the blink
+            if let Ok(blink_state) = v_tweq_blink_state.get(*entity_id) {
+                if blink_state.animation_state.contains(TweqAnimationState::ON)
+                    && !blink_state.is_lit
+                {
+                    continue;
+                }
+            }
+
             if !self.visibility_engine.is_visible(*entity_id) {
                 continue;
             }
@@ -1485,6 +1642,12 @@ impl Mission {
         scene.append(&mut self.left_hand.render());
         scene.append(&mut self.right_hand.render());
 
+        // Render holster slots
+        scene.extend(
+            self.holsters
+                .render(&[self.left_hand.position(), self.right_hand.position()]),
+        );
+
         // Render inventory
         let inventory_objs = PlayerInventoryEntity::render(&self.world);
         scene.extend(inventory_objs);
@@ -1575,7 +1738,11 @@ impl Mission {
         player_pos: Vector3<f32>,
         player_rotation: Quaternion<f32>,
         input_context: &input_context::InputContext,
+        time: &Time,
     ) {
+        self.holsters
+            .update(player_pos, player_rotation, &input_context.head);
+
         let (right_hand, mut right_hand_msgs) = VirtualHand::update(
             &self.right_hand,
             &self.physics,
@@ -1583,6 +1750,9 @@ impl Mission {
             player_pos,
             player_rotation,
             &input_context.right_hand,
+            &self.left_hand,
+            &self.holsters,
+            time,
         );
         self.right_hand = right_hand;
 
@@ -1593,11 +1763,21 @@ impl Mission {
             player_pos,
             player_rotation,
             &input_context.left_hand,
+            &self.right_hand,
+            &self.holsters,
+            time,
         );
         self.left_hand = left_hand;
 
         left_hand_msgs.append(&mut right_hand_msgs);
 
+        // Goes last, to override where the hand holding a two-handed weapon put it
+        left_hand_msgs.extend(VirtualHand::two_handed_aim(
+            &self.world,
+            &self.left_hand,
+            &self.right_hand,
+        ));
+
         for msg in left_hand_msgs {
             match msg {
                 VirtualHandEffect::OutMessage { message } => self.script_world.dispatch(message),
@@ -1619,6 +1799,12 @@ impl Mission {
                 } => {
                     self.set_entity_position_rotation(entity_id, position, rotation, scale);
                 }
+                VirtualHandEffect::SetHeldVelocity {
+                    entity_id,
+                    velocity,
+                } => {
+                    self.world.add_component(entity_id, RuntimePropHeldVelocity(velocity));
+                }
                 VirtualHandEffect::SpawnEntity {
                     template_id,
                     position,
@@ -1644,16 +1830,51 @@ impl Mission {
                         to: entity_id,
                     });
                 }
-                VirtualHandEffect::DropItem { entity_id } => {
+                VirtualHandEffect::DropItem {
+                    entity_id,
+                    velocity,
+                    angular_velocity,
+                } => {
                     self.make_physical(entity_id);
+                    self.world.add_component(entity_id, RuntimePropHeldVelocity(Vector3::zero()));
+
+                    // Carry the hand's motion over, so the item can be thrown
+                    self.physics.set_velocity(entity_id, velocity);
+                    self.physics.set_angular_velocity(entity_id, angular_velocity);
 
                     self.script_world.dispatch(Message {
                         payload: MessagePayload::Drop,
                         to: entity_id,
                     });
                 }
+                VirtualHandEffect::HolsterItem { entity_id, slot } => {
+                    // It stays un-physical, and keeps its held model, while it's holstered
+                    self.holsters.holster(slot, entity_id);
+                    self.world
+                        .add_component(entity_id, RuntimePropHeldVelocity(Vector3::zero()));
+                }
+                VirtualHandEffect::UnholsterItem { slot } => {
+                    self.holsters.take(slot);
+                }
             }
         }
+
+        // Holstered items move along with the player
+        let holstered_entities = self.holsters.contents().clone();
+        for (slot, entity_id) in holstered_entities {
+            let (position, rotation) = self.holsters.slot_transform(slot);
+            let vr_offsets = vr_config::get_vr_hand_model_adjustments_from_entity(
+                entity_id,
+                &self.world,
+                vr_config::Handedness::Right,
+            );
+            self.set_entity_position_rotation(
+                entity_id,
+                position,
+                rotation * vr_offsets.rotation,
+                vec3(1.0, 1.0, 1.0),
+            );
+        }
     }
 }
 
@@ -1698,12 +1919,12 @@ fn create_template_name_map(game_entity_info: &Gamesys) -> HashMap<String, Entit
 ///
 /// initialize_background_music
 ///
-/// Helper function to set up the music player for the level
+/// Helper function to set up the music player for the level, and the director that drives its cues
 fn initialize_background_music(
     level: &dark::mission::SystemShock2Level,
     asset_cache: &mut AssetCache,
     audio_context: &mut AudioContext<EntityId, String>,
-) {
+) -> MusicDirector {
     let song_file_name = &level.song_params.song;
     info!("loading music for level: {}", song_file_name);
     if !song_file_name.is_empty() {
@@ -1714,8 +1935,10 @@ fn initialize_background_music(
         };
         let background_music_player = SongPlayer::new(&song, asset_cache);
         audio_context.set_background_music(Box::new(background_music_player));
+        MusicDirector::new(song_file_name, Some(&song))
     } else {
         audio_context.stop_background_music();
+        MusicDirector::new(song_file_name, None)
     }
 }
 
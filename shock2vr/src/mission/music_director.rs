@@ -0,0 +1,261 @@
+///
+/// music_director.rs
+///
+/// Chooses the song cue for the mission's song (from SONGPARAMS), based on room cues
+/// and how aware the AI currently is of the player.
+///
+/// The song player only consults the cue when the current section finishes, so switching
+/// between ambient, alert and combat happens at section boundaries instead of cutting clips.
+/// The alert and combat cues are held until the AI calms down, so they're only set when the
+/// mode changes.
+///
+use std::time::Duration;
+
+use dark::audio::Song;
+use engine::audio::AudioContext;
+use shipyard::{EntityId, IntoIter, View, World};
+use tracing::{info, warn};
+
+use crate::runtime_props::{AIAwareness, RuntimePropAIAwareness};
+
+// A song's sections branch on schemas named for what's happening - the alert and combat schemas
+// are the ones with these words in their names. Songs without them follow the room themes alone.
+const ALERT_KEYWORDS: [&str; 4] = ["alert", "suspense", "tension", "search"];
+const COMBAT_KEYWORDS: [&str; 4] = ["combat", "action", "attack", "fight"];
+
+// How long to hold a heightened mode after the AI calms down, so the music doesn't flip-flop
+const COMBAT_HOLD_TIME: Duration = Duration::from_secs(8);
+const ALERT_HOLD_TIME: Duration = Duration::from_secs(5);
+
+// How the song's cues change, when the music mode does
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CueChange {
+    // Cue to hold at every section boundary, until the next change
+    held: Option<String>,
+    // One-off cue for the next section boundary
+    next: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MusicMode {
+    Ambient,
+    Alert,
+    Combat,
+}
+
+pub struct MusicDirector {
+    alert_schema: Option<String>,
+    combat_schema: Option<String>,
+
+    room_cue: Option<String>,
+    mode: MusicMode,
+    last_alert_time: Duration,
+    last_combat_time: Duration,
+}
+
+impl MusicDirector {
+    pub fn new(song_name: &str, maybe_song: Option<&Song>) -> MusicDirector {
+        let schemas = maybe_song
+            .map(|song| song.all_schemas())
+            .unwrap_or_default();
+
+        let director = MusicDirector::from_schemas(&schemas);
+        if maybe_song.is_some() && director.alert_schema.is_none() {
+            warn!("music director - song {} has no alert schema", song_name);
+        }
+
+        info!(
+            "music director - alert schema: {:?} combat schema: {:?}",
+            director.alert_schema, director.combat_schema
+        );
+        director
+    }
+
+    fn from_schemas(schemas: &[String]) -> MusicDirector {
+        let alert_schema = find_schema(schemas, &ALERT_KEYWORDS);
+        let combat_schema = find_schema(schemas, &COMBAT_KEYWORDS);
+
+        MusicDirector {
+            alert_schema,
+            combat_schema,
+            room_cue: None,
+            mode: MusicMode::Ambient,
+            last_alert_time: Duration::ZERO,
+            last_combat_time: Duration::ZERO,
+        }
+    }
+
+    ///
+    /// set_room_cue
+    ///
+    /// Set the ambient cue, from the music ambient sound the player is standing in
+    pub fn set_room_cue(
+        &mut self,
+        cue: String,
+        audio_context: &mut AudioContext<EntityId, String>,
+    ) {
+        if self.room_cue.as_ref() == Some(&cue) {
+            return;
+        }
+
+        info!("updating music cue: {}", cue);
+        if self.mode == MusicMode::Ambient {
+            audio_context.set_background_music_cue(cue.to_owned());
+        }
+        self.room_cue = Some(cue);
+    }
+
+    pub fn update(
+        &mut self,
+        world: &World,
+        total_time: Duration,
+        audio_context: &mut AudioContext<EntityId, String>,
+    ) {
+        let (any_alert, any_combat) = world.run(|v_awareness: View<RuntimePropAIAwareness>| {
+            let mut any_alert = false;
+            let mut any_combat = false;
+            for awareness in v_awareness.iter() {
+                match awareness.0 {
+                    AIAwareness::Unaware => (),
+                    AIAwareness::Alert => any_alert = true,
+                    AIAwareness::Combat => any_combat = true,
+                }
+            }
+            (any_alert, any_combat)
+        });
+
+        if let Some(change) = self.change_mode(any_alert, any_combat, total_time) {
+            audio_context.hold_background_music_cue(change.held);
+            if let Some(next) = change.next {
+                audio_context.set_background_music_cue(next);
+            }
+        }
+    }
+
+    // Move between ambient, alert and combat, returning how the cues change if the mode does
+    fn change_mode(
+        &mut self,
+        any_alert: bool,
+        any_combat: bool,
+        total_time: Duration,
+    ) -> Option<CueChange> {
+        if any_combat {
+            self.last_combat_time = total_time;
+        }
+
+        if any_alert {
+            self.last_alert_time = total_time;
+        }
+
+        let next_mode = if any_combat
+            || (self.mode == MusicMode::Combat
+                && total_time < self.last_combat_time + COMBAT_HOLD_TIME)
+        {
+            MusicMode::Combat
+        } else if any_alert
+            || (self.mode != MusicMode::Ambient
+                && total_time < self.last_alert_time + ALERT_HOLD_TIME)
+        {
+            MusicMode::Alert
+        } else {
+            MusicMode::Ambient
+        };
+
+        if next_mode == self.mode {
+            return None;
+        }
+
+        info!("music mode: {:?} -> {:?}", self.mode, next_mode);
+        self.mode = next_mode;
+
+        // Hold the heightened schema, so the song stays in it across section boundaries
+        let held = match self.mode {
+            MusicMode::Ambient => None,
+            MusicMode::Alert => self.alert_schema.clone(),
+            MusicMode::Combat => self.combat_schema.clone().or(self.alert_schema.clone()),
+        };
+
+        // Heading back to ambient - return to the theme for the room we're in
+        let next = match self.mode {
+            MusicMode::Ambient => self.room_cue.clone(),
+            _ => None,
+        };
+
+        Some(CueChange { held, next })
+    }
+}
+
+// The first of the song's schemas named with one of the keywords
+fn find_schema(schemas: &[String], keywords: &[&str]) -> Option<String> {
+    schemas
+        .iter()
+        .map(|schema| schema.to_ascii_lowercase())
+        .find(|schema| keywords.iter().any(|keyword| schema.contains(keyword)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schemas(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_awareness_schemas_come_from_the_song() {
+        // Given
+        let with_awareness = schemas(&["m1_theme", "M1_Suspense", "m1_combat"]);
+        let without_awareness = schemas(&["m1_theme", "m1_cargo"]);
+
+        // When
+        let director = MusicDirector::from_schemas(&with_awareness);
+        let quiet_director = MusicDirector::from_schemas(&without_awareness);
+
+        // Then
+        assert_eq!(director.alert_schema, Some("m1_suspense".to_owned()));
+        assert_eq!(director.combat_schema, Some("m1_combat".to_owned()));
+        assert_eq!(quiet_director.alert_schema, None);
+        assert_eq!(quiet_director.combat_schema, None);
+    }
+
+    #[test]
+    fn test_awareness_changes_pick_the_matching_cue() {
+        // Given - a song with alert and combat sections, in a room with its own theme
+        let mut director =
+            MusicDirector::from_schemas(&schemas(&["m1_theme", "m1_suspense", "m1_combat"]));
+        director.room_cue = Some("m1_theme".to_owned());
+        let at = Duration::from_secs;
+
+        // When
+        let alerted = director.change_mode(true, false, at(10));
+        let still_alerted = director.change_mode(true, false, at(11));
+        let fighting = director.change_mode(false, true, at(12));
+        let holding = director.change_mode(false, false, at(13));
+        let calmed_down = director.change_mode(false, false, at(30));
+
+        // Then - the heightened cues are held until the AI calms down, then the room theme is back
+        assert_eq!(
+            alerted,
+            Some(CueChange {
+                held: Some("m1_suspense".to_owned()),
+                next: None,
+            })
+        );
+        assert_eq!(still_alerted, None);
+        assert_eq!(
+            fighting,
+            Some(CueChange {
+                held: Some("m1_combat".to_owned()),
+                next: None,
+            })
+        );
+        assert_eq!(holding, None);
+        assert_eq!(
+            calmed_down,
+            Some(CueChange {
+                held: None,
+                next: Some("m1_theme".to_owned()),
+            })
+        );
+    }
+}
@@ -1,5 +1,6 @@
 mod debug_render_pipeline;
 mod physics_events;
+mod player_controller;
 mod util;
 
 use collision::Aabb3;
@@ -9,7 +10,7 @@ use std::time::Instant;
 use util::*;
 
 use bitflags::bitflags;
-use cgmath::{point3, InnerSpace, Matrix4, Point3, Quaternion, SquareMatrix, Vector3};
+use cgmath::{point3, vec3, InnerSpace, Matrix4, Point3, Quaternion, SquareMatrix, Vector3};
 use dark::{mission::SystemShock2Level, SCALE_FACTOR};
 use engine::scene::SceneObject;
 use ordered_float::*;
@@ -22,6 +23,7 @@ use rapier3d::{
 use shipyard::EntityId;
 
 use physics_events::*;
+pub use player_controller::*;
 
 use self::debug_render_pipeline::DebugRenderer;
 
@@ -140,6 +142,30 @@ pub struct PlayerHandle {
     // Player
     controller: KinematicCharacterController,
     character_handle: RigidBodyHandle,
+    state: PlayerControllerState,
+}
+
+impl PlayerHandle {
+    pub fn state(&self) -> &PlayerControllerState {
+        &self.state
+    }
+
+    fn controller_offset(&self) -> f32 {
+        match self.controller.offset {
+            CharacterLength::Absolute(offset) => offset,
+            CharacterLength::Relative(offset) => offset * self.state.stance.half_height() * 2.0,
+        }
+    }
+
+    fn query_filter(&self) -> QueryFilter<'static> {
+        QueryFilter::new()
+            .groups(InteractionGroups::new(
+                InternalCollisionGroups::PLAYER.bits.into(),
+                InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
+            ))
+            .exclude_rigid_body(self.character_handle)
+            .exclude_sensors()
+    }
 }
 
 pub struct PhysicsWorld {
@@ -160,6 +186,9 @@ pub struct PhysicsWorld {
 
     entity_id_to_body: HashMap<EntityId, RigidBodyHandle>,
 
+    // Entities the player can climb, like ladders
+    climbable_entities: HashSet<EntityId>,
+
     // TODO:
     // physics_hooks: Box<dyn PhysicsHooks>,
     // event_handler: Box<dyn EventHandler>,
@@ -362,6 +391,47 @@ impl PhysicsWorld {
         character_body.set_translation(vec_to_nvec(position), true)
     }
 
+    ///
+    /// find_player_position_on_floor
+    ///
+    /// Where the player would be, standing on a point of the floor - or None, if the character
+    /// controller's shape doesn't fit there
+    pub fn find_player_position_on_floor(
+        &self,
+        floor_position: Vector3<f32>,
+        player_handle: &PlayerHandle,
+    ) -> Option<Vector3<f32>> {
+        let character_body = self.rigid_body_set.get(player_handle.character_handle)?;
+        let character_collider = self.collider_set.get(character_body.colliders()[0])?;
+        let stance = player_handle.state.stance;
+
+        let collider_center = floor_position
+            + vec3(
+                0.0,
+                stance.half_height() + player_handle.controller_offset(),
+                0.0,
+            );
+        let shape_position =
+            Isometry::translation(collider_center.x, collider_center.y, collider_center.z);
+
+        let is_blocked = self
+            .query_pipeline
+            .intersection_with_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &shape_position,
+                character_collider.shape(),
+                player_handle.query_filter(),
+            )
+            .is_some();
+
+        if is_blocked {
+            None
+        } else {
+            Some(collider_center - stance.collider_offset())
+        }
+    }
+
     pub fn get_position2(&self, entity_id: EntityId) -> Option<Vector3<f32>> {
         if let Some(handle) = self.entity_id_to_body.get(&entity_id) {
             let maybe_rigid_body = self.rigid_body_set.get(*handle);
@@ -422,6 +492,16 @@ impl PhysicsWorld {
         }
     }
 
+    pub fn set_angular_velocity(&mut self, entity_id: EntityId, angular_velocity: Vector3<f32>) {
+        if let Some(handle) = self.entity_id_to_body.get(&entity_id) {
+            let maybe_rigid_body = self.rigid_body_set.get_mut(*handle);
+
+            if let Some(rigid_body) = maybe_rigid_body {
+                rigid_body.set_angvel(vec_to_nvec(angular_velocity), true);
+            }
+        }
+    }
+
     pub fn get_rotation(&self, handle: RigidBodyHandle) -> Option<Quaternion<f32>> {
         let maybe_rigid_body = self.rigid_body_set.get(handle);
 
@@ -589,6 +669,15 @@ impl PhysicsWorld {
             );
         }
         self.entity_id_to_body.remove(&entity_id);
+        self.climbable_entities.remove(&entity_id);
+    }
+
+    ///
+    /// set_climbable
+    ///
+    /// Mark an entity, like a ladder, as something the player can climb
+    pub fn set_climbable(&mut self, entity_id: EntityId) {
+        self.climbable_entities.insert(entity_id);
     }
 
     pub fn create_player(
@@ -605,7 +694,7 @@ impl PhysicsWorld {
         rigid_body.user_data = player_entity_user_data;
         let character_handle = self.rigid_body_set.insert(rigid_body);
         let mut collider =
-            ColliderBuilder::cuboid(0.8 / SCALE_FACTOR, 2.4 / SCALE_FACTOR, 0.8 / SCALE_FACTOR);
+            ColliderBuilder::cuboid(PLAYER_HALF_WIDTH, STANDING_HALF_HEIGHT, PLAYER_HALF_WIDTH);
         //let mut collider = ColliderBuilder::capsule_y(2.4 / SCALE_FACTOR, 0.8 / SCALE_FACTOR);
         collider = collider.collision_groups(InteractionGroups::new(
             InternalCollisionGroups::PLAYER.bits.into(),
@@ -630,6 +719,7 @@ impl PhysicsWorld {
         PlayerHandle {
             controller,
             character_handle,
+            state: PlayerControllerState::new(),
         }
     }
 
@@ -679,6 +769,7 @@ impl PhysicsWorld {
             // physics_hooks: Box::new(physics_hooks),
             // event_handler: Box::new(event_handler),
             entity_id_to_body: HashMap::new(),
+            climbable_entities: HashSet::new(),
 
             debug_pipeline,
 
@@ -706,6 +797,7 @@ impl PhysicsWorld {
     pub fn update(
         &mut self,
         desired_movement: Vector3<f32>,
+        input: &PlayerControllerInput,
         player_handle: &mut PlayerHandle,
     ) -> (Vector3<f32>, Vec<CollisionEvent>) {
         /* Run the game loop, stepping the simulation once per frame. */
@@ -737,7 +829,7 @@ impl PhysicsWorld {
         // Update character controller
         let desired_movement = vec_to_nvec(desired_movement);
         let (mut collision_events, character_body) =
-            { self.move_player(desired_movement, player_handle) };
+            { self.move_player(desired_movement, input, player_handle) };
         let translation = nvec_to_cgmath(*character_body.translation());
 
         let mut additional_collision_events = { self.events.get_and_clear_events() };
@@ -751,40 +843,78 @@ impl PhysicsWorld {
     fn move_player(
         &mut self,
         desired_movement: Vector<Real>,
+        input: &PlayerControllerInput,
         player_handle: &mut PlayerHandle,
     ) -> (Vec<CollisionEvent>, &RigidBody) {
+        let dt = self.integration_parameters.dt;
+        self.update_player_stance(input, player_handle);
+
         let character_body = &self.rigid_body_set[player_handle.character_handle];
-        let original_position = *character_body.position();
+        let body_translation = nvec_to_cgmath(character_body.position().translation.vector);
+        let original_position = self.player_collider_position(player_handle);
         let character_collider = &self.collider_set[character_body.colliders()[0]];
         let _character_mass = character_body.mass();
 
-        let mut gravity = -0.5 / SCALE_FACTOR;
-        gravity *= character_body.gravity_scale();
-
-        let movement_with_gravity = desired_movement + Vector::y() * gravity;
+        let is_jump_started = player_handle.state.take_jump_press(input.is_jump_pressed);
+        player_handle.state.is_on_ladder = player_handle.state.mantle_target.is_none()
+            && self.is_touching_climbable(&original_position, player_handle);
+
+        let horizontal_movement = vec3(desired_movement.x, 0.0, desired_movement.z);
+        let movement = match player_handle.state.mantle_movement(body_translation, dt) {
+            Some(mantle_movement) => mantle_movement,
+            None => {
+                player_handle.state.mantle_target = None;
+                let vertical_movement = player_handle.state.vertical_movement(
+                    horizontal_movement,
+                    input,
+                    is_jump_started,
+                    character_body.gravity_scale(),
+                    dt,
+                );
+                nvec_to_cgmath(desired_movement) + vec3(0.0, vertical_movement, 0.0)
+            }
+        };
 
         //let mut collisions = vec![];
         let mvt = profile!(
             "physics.move_player",
             player_handle.controller.move_shape(
-                self.integration_parameters.dt,
+                dt,
                 &self.rigid_body_set,
                 &self.collider_set,
                 &self.query_pipeline,
                 character_collider.shape(),
-                character_collider.position(),
-                movement_with_gravity.cast::<Real>(),
-                QueryFilter::new()
-                    .groups(InteractionGroups::new(
-                        InternalCollisionGroups::PLAYER.bits.into(),
-                        InternalCollisionGroups::ALL_COLLIDABLE.bits.into(),
-                    ))
-                    .exclude_rigid_body(player_handle.character_handle)
-                    .exclude_sensors(),
+                &original_position,
+                vec_to_nvec(movement),
+                player_handle.query_filter(),
                 |_c| (),
                 //|c| collisions.push(c),
             )
         );
+        player_handle.state.land(mvt.grounded);
+
+        if player_handle.state.mantle_target.is_some() {
+            // Stuck on something partway up - let go of the ledge
+            if mvt.translation.norm() < f32::EPSILON {
+                player_handle.state.mantle_target = None;
+            }
+        } else if input.is_jump_pressed && !player_handle.state.is_on_ladder {
+            // Holding jump against a wall pulls the player onto the ledge above it
+            let moved = vec3(mvt.translation.x, 0.0, mvt.translation.z);
+            let is_blocked = horizontal_movement.magnitude() > f32::EPSILON
+                && moved.magnitude() < horizontal_movement.magnitude() * 0.5;
+            if is_blocked {
+                player_handle.state.mantle_target = self.find_mantle_target(
+                    &original_position,
+                    character_collider.shape(),
+                    horizontal_movement.normalize(),
+                    player_handle,
+                );
+                if player_handle.state.mantle_target.is_some() {
+                    player_handle.state.vertical_velocity = 0.0;
+                }
+            }
+        }
 
         let mut collision_events = Vec::new();
         let mut current_sensor_intersections = HashSet::new();
@@ -867,6 +997,162 @@ impl PhysicsWorld {
         (collision_events, character_body)
     }
 
+    fn player_collider_position(&self, player_handle: &PlayerHandle) -> Isometry<Real> {
+        let character_body = &self.rigid_body_set[player_handle.character_handle];
+        let mut position = *character_body.position();
+        position.translation.vector += vec_to_nvec(player_handle.state.stance.collider_offset());
+        position
+    }
+
+    ///
+    /// update_player_stance
+    ///
+    /// Resize the player's collider to crouch or stand, following the head. Under a low ceiling,
+    /// like in a vent, the player stays crouched until there's room to stand.
+    fn update_player_stance(
+        &mut self,
+        input: &PlayerControllerInput,
+        player_handle: &mut PlayerHandle,
+    ) {
+        let stance = PlayerStance::from_head_height(input.head_height);
+        if stance == player_handle.state.stance {
+            return;
+        }
+
+        let shape = SharedShape::cuboid(PLAYER_HALF_WIDTH, stance.half_height(), PLAYER_HALF_WIDTH);
+        let character_body = &self.rigid_body_set[player_handle.character_handle];
+        let mut position = *character_body.position();
+        position.translation.vector += vec_to_nvec(stance.collider_offset());
+
+        let is_blocked = self
+            .query_pipeline
+            .intersection_with_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &position,
+                &*shape,
+                player_handle.query_filter(),
+            )
+            .is_some();
+        if is_blocked {
+            return;
+        }
+
+        let collider_handle = character_body.colliders()[0];
+        let offset = stance.collider_offset();
+        let collider = &mut self.collider_set[collider_handle];
+        collider.set_shape(shape);
+        collider.set_position_wrt_parent(Isometry::translation(offset.x, offset.y, offset.z));
+        player_handle.state.stance = stance;
+    }
+
+    fn is_touching_climbable(
+        &self,
+        collider_position: &Isometry<Real>,
+        player_handle: &PlayerHandle,
+    ) -> bool {
+        if self.climbable_entities.is_empty() {
+            return false;
+        }
+
+        let reach = PLAYER_HALF_WIDTH + LADDER_REACH;
+        let reach_shape =
+            SharedShape::cuboid(reach, player_handle.state.stance.half_height(), reach);
+        let mut is_touching = false;
+        self.query_pipeline.intersections_with_shape(
+            &self.rigid_body_set,
+            &self.collider_set,
+            collider_position,
+            &*reach_shape,
+            player_handle.query_filter(),
+            |handle| {
+                let collider = &self.collider_set[handle];
+                is_touching = EntityId::from_inner(collider.user_data as u64)
+                    .map_or(false, |entity_id| {
+                        self.climbable_entities.contains(&entity_id)
+                    });
+                !is_touching
+            },
+        );
+        is_touching
+    }
+
+    ///
+    /// find_mantle_target
+    ///
+    /// Where the player ends up pulling themselves onto the ledge ahead, if there is one. The
+    /// player's shape is cast up, over the edge, and then down onto the top of the ledge - so
+    /// there has to be room for the player the whole way.
+    fn find_mantle_target(
+        &self,
+        collider_position: &Isometry<Real>,
+        shape: &dyn Shape,
+        forward: Vector3<f32>,
+        player_handle: &PlayerHandle,
+    ) -> Option<Vector3<f32>> {
+        let up = vec3(0.0, 1.0, 0.0);
+        if self.cast_player_shape(
+            collider_position,
+            shape,
+            up,
+            MANTLE_MAX_HEIGHT,
+            player_handle,
+        ) < MANTLE_MAX_HEIGHT
+        {
+            return None;
+        }
+
+        let mut above_edge = *collider_position;
+        above_edge.translation.vector += vec_to_nvec(up * MANTLE_MAX_HEIGHT);
+        if self.cast_player_shape(&above_edge, shape, forward, MANTLE_REACH, player_handle)
+            < MANTLE_REACH
+        {
+            return None;
+        }
+
+        let mut above_ledge = above_edge;
+        above_ledge.translation.vector += vec_to_nvec(forward * MANTLE_REACH);
+        let drop =
+            self.cast_player_shape(&above_ledge, shape, -up, MANTLE_MAX_HEIGHT, player_handle);
+
+        // Low enough to step onto, or nothing to stand on
+        let ledge_height = MANTLE_MAX_HEIGHT - drop;
+        if ledge_height < MANTLE_MIN_HEIGHT || drop >= MANTLE_MAX_HEIGHT {
+            return None;
+        }
+
+        let collider_center = nvec_to_cgmath(above_ledge.translation.vector)
+            + vec3(0.0, player_handle.controller_offset() - drop, 0.0);
+        Some(collider_center - player_handle.state.stance.collider_offset())
+    }
+
+    ///
+    /// cast_player_shape
+    ///
+    /// How far the player's shape can move in a direction, up to max_distance, before hitting
+    /// something
+    fn cast_player_shape(
+        &self,
+        position: &Isometry<Real>,
+        shape: &dyn Shape,
+        direction: Vector3<f32>,
+        max_distance: f32,
+        player_handle: &PlayerHandle,
+    ) -> f32 {
+        self.query_pipeline
+            .cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                position,
+                &vec_to_nvec(direction),
+                shape,
+                max_distance,
+                true,
+                player_handle.query_filter(),
+            )
+            .map_or(max_distance, |(_handle, toi)| toi.toi)
+    }
+
     pub fn ray_cast2(
         &self,
         start_point: Point3<f32>,
@@ -940,6 +1226,43 @@ impl PhysicsWorld {
         }
     }
 
+    ///
+    /// sphere_cast
+    ///
+    /// How far a sphere moves along a direction, up to max_toi, before it hits something. Only
+    /// fixed objects count, like the level and doors - sensors and dynamic bodies are ignored.
+    pub fn sphere_cast(
+        &self,
+        start_point: Point3<f32>,
+        direction: Vector3<f32>,
+        radius: f32,
+        max_toi: f32,
+        collision_groups: InternalCollisionGroups,
+    ) -> Option<f32> {
+        let direction = direction.normalize();
+        let sphere = SharedShape::ball(radius);
+        let filter = QueryFilter::new()
+            .groups(InteractionGroups::new(
+                InternalCollisionGroups::ALL.bits.into(),
+                collision_groups.bits.into(),
+            ))
+            .exclude_sensors()
+            .exclude_dynamic();
+
+        self.query_pipeline
+            .cast_shape(
+                &self.rigid_body_set,
+                &self.collider_set,
+                &Isometry::translation(start_point.x, start_point.y, start_point.z),
+                &vec_to_nvec(direction),
+                &*sphere,
+                max_toi,
+                false,
+                filter,
+            )
+            .map(|(_handle, toi)| toi.toi)
+    }
+
     pub fn ray_cast(
         &self,
         start_point: Point3<f32>,
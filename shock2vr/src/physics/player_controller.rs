@@ -0,0 +1,266 @@
+// Player controller
+//
+// State for the player's character controller, on top of rapier's KinematicCharacterController:
+// crouching, jumping, climbing ladders and mantling onto ledges.
+
+use cgmath::{vec3, InnerSpace, Vector3};
+use dark::SCALE_FACTOR;
+
+// Half-extents of the player's collider
+pub const PLAYER_HALF_WIDTH: f32 = 0.8 / SCALE_FACTOR;
+pub const STANDING_HALF_HEIGHT: f32 = 2.4 / SCALE_FACTOR;
+pub const CROUCHING_HALF_HEIGHT: f32 = 1.2 / SCALE_FACTOR;
+
+// When the head is lower than this above the floor, the player crouches
+const CROUCH_HEAD_HEIGHT: f32 = 1.1;
+
+// Upward speed at the start of a jump, and the gravity that slows it, in units per second
+const JUMP_SPEED: f32 = 4.0;
+const GRAVITY: f32 = 9.81;
+
+// Fastest the player falls, per physics step
+const MAX_FALL_PER_STEP: f32 = 0.5 / SCALE_FACTOR;
+
+// How far the player can reach for a ladder, past the edge of the collider
+pub const LADDER_REACH: f32 = 0.4 / SCALE_FACTOR;
+
+// Looking further down than this, moving on a ladder climbs down instead of up
+const LADDER_LOOK_DOWN: f32 = -0.3;
+
+// Tallest ledge the player can pull themselves onto, and how far over the edge they end up.
+// Ledges lower than the minimum are stepped onto instead.
+pub const MANTLE_MIN_HEIGHT: f32 = 1.0 / SCALE_FACTOR;
+pub const MANTLE_MAX_HEIGHT: f32 = 3.0 / SCALE_FACTOR;
+pub const MANTLE_REACH: f32 = 1.2 / SCALE_FACTOR;
+
+// Speed of the mantle, in units per second
+const MANTLE_SPEED: f32 = 3.0;
+
+// Mantling is done once the player is this close to the top of the ledge
+const MANTLE_DONE_DISTANCE: f32 = 0.02;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerStance {
+    Standing,
+    Crouching,
+}
+
+impl PlayerStance {
+    ///
+    /// from_head_height
+    ///
+    /// The stance for a head at the given height above the floor - ducking in VR crouches
+    pub fn from_head_height(head_height: f32) -> PlayerStance {
+        if head_height < CROUCH_HEAD_HEIGHT {
+            PlayerStance::Crouching
+        } else {
+            PlayerStance::Standing
+        }
+    }
+
+    pub fn half_height(&self) -> f32 {
+        match self {
+            PlayerStance::Standing => STANDING_HALF_HEIGHT,
+            PlayerStance::Crouching => CROUCHING_HALF_HEIGHT,
+        }
+    }
+
+    ///
+    /// collider_offset
+    ///
+    /// Offset of the collider from the player's body. Crouching shrinks the collider from the top,
+    /// so the feet stay on the floor and the player's position doesn't jump.
+    pub fn collider_offset(&self) -> Vector3<f32> {
+        vec3(0.0, self.half_height() - STANDING_HALF_HEIGHT, 0.0)
+    }
+}
+
+// Input for the player controller, from the headset and controllers
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerControllerInput {
+    // Height of the head above the floor of the play space
+    pub head_height: f32,
+    pub is_jump_pressed: bool,
+    // Where the player is looking, to pick which way to climb a ladder
+    pub look_direction: Vector3<f32>,
+}
+
+impl Default for PlayerControllerInput {
+    fn default() -> Self {
+        Self {
+            head_height: CROUCH_HEAD_HEIGHT * 2.0,
+            is_jump_pressed: false,
+            look_direction: vec3(0.0, 0.0, -1.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlayerControllerState {
+    pub stance: PlayerStance,
+    // Units per second - positive while rising from a jump
+    pub vertical_velocity: f32,
+    pub is_grounded: bool,
+    // Speed the player hit the ground at, in units per second - only on the step they land
+    pub landing_speed: Option<f32>,
+    pub is_on_ladder: bool,
+    // Top of the ledge the player is pulling themselves onto
+    pub mantle_target: Option<Vector3<f32>>,
+    was_jump_pressed: bool,
+}
+
+impl PlayerControllerState {
+    pub fn new() -> PlayerControllerState {
+        PlayerControllerState {
+            stance: PlayerStance::Standing,
+            vertical_velocity: 0.0,
+            is_grounded: false,
+            landing_speed: None,
+            is_on_ladder: false,
+            mantle_target: None,
+            was_jump_pressed: false,
+        }
+    }
+
+    ///
+    /// take_jump_press
+    ///
+    /// Whether jump was just pressed this step - holding the button only jumps once
+    pub fn take_jump_press(&mut self, is_jump_pressed: bool) -> bool {
+        let is_new_press = is_jump_pressed && !self.was_jump_pressed;
+        self.was_jump_pressed = is_jump_pressed;
+        is_new_press
+    }
+
+    ///
+    /// vertical_movement
+    ///
+    /// How far the player moves up or down this step. Jumps start from the ground, or off a
+    /// ladder. On a ladder, moving towards where the player is looking climbs.
+    pub fn vertical_movement(
+        &mut self,
+        horizontal_movement: Vector3<f32>,
+        input: &PlayerControllerInput,
+        is_jump_started: bool,
+        gravity_scale: f32,
+        dt: f32,
+    ) -> f32 {
+        if is_jump_started && (self.is_grounded || self.is_on_ladder) {
+            self.vertical_velocity = JUMP_SPEED;
+            self.is_on_ladder = false;
+        }
+
+        if self.is_on_ladder {
+            self.vertical_velocity = 0.0;
+            return ladder_climb(horizontal_movement, input.look_direction);
+        }
+
+        self.vertical_velocity -= GRAVITY * gravity_scale * dt;
+        let max_fall = MAX_FALL_PER_STEP * gravity_scale;
+        let movement = (self.vertical_velocity * dt).max(-max_fall);
+        self.vertical_velocity = movement / dt;
+        movement
+    }
+
+    ///
+    /// land
+    ///
+    /// Update after the character controller moves - touching the ground ends a fall
+    pub fn land(&mut self, is_grounded: bool) {
+        let was_grounded = self.is_grounded;
+        self.is_grounded = is_grounded;
+        self.landing_speed = None;
+        if is_grounded && self.vertical_velocity < 0.0 {
+            if !was_grounded {
+                self.landing_speed = Some(-self.vertical_velocity);
+            }
+            self.vertical_velocity = 0.0;
+        }
+    }
+
+    ///
+    /// mantle_movement
+    ///
+    /// Movement towards the top of the ledge being mantled - straight up, then over the edge
+    pub fn mantle_movement(&self, position: Vector3<f32>, dt: f32) -> Option<Vector3<f32>> {
+        let target = self.mantle_target?;
+        let max_distance = MANTLE_SPEED * dt;
+
+        let rise = target.y - position.y;
+        if rise > MANTLE_DONE_DISTANCE {
+            return Some(vec3(0.0, rise.min(max_distance), 0.0));
+        }
+
+        let over = vec3(target.x - position.x, 0.0, target.z - position.z);
+        let distance = over.magnitude();
+        if distance > MANTLE_DONE_DISTANCE {
+            return Some(over * (max_distance / distance).min(1.0));
+        }
+
+        None
+    }
+}
+
+///
+/// ladder_climb
+///
+/// How far to climb for a movement on a ladder. Moving forward climbs up, unless the player is
+/// looking down - then it climbs down.
+fn ladder_climb(horizontal_movement: Vector3<f32>, look_direction: Vector3<f32>) -> f32 {
+    let forward = vec3(look_direction.x, 0.0, look_direction.z);
+    if forward.magnitude2() < f32::EPSILON {
+        return 0.0;
+    }
+
+    let amount = horizontal_movement.dot(forward.normalize());
+    if look_direction.normalize().y < LADDER_LOOK_DOWN {
+        -amount
+    } else {
+        amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stance_follows_head_height() {
+        // Then - standing and sitting stay standing, ducking crouches
+        assert_eq!(PlayerStance::from_head_height(1.7), PlayerStance::Standing);
+        assert_eq!(PlayerStance::from_head_height(1.2), PlayerStance::Standing);
+        assert_eq!(PlayerStance::from_head_height(0.6), PlayerStance::Crouching);
+        assert_eq!(
+            PlayerStance::Standing.collider_offset(),
+            vec3(0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_jump_only_starts_from_the_ground() {
+        // Given
+        let input = PlayerControllerInput::default();
+        let mut grounded = PlayerControllerState::new();
+        grounded.land(true);
+        let mut falling = PlayerControllerState::new();
+
+        // When
+        let rise = grounded.vertical_movement(vec3(0.0, 0.0, 0.0), &input, true, 1.0, 0.1);
+        let fall = falling.vertical_movement(vec3(0.0, 0.0, 0.0), &input, true, 1.0, 0.1);
+
+        // Then
+        assert!(rise > 0.0);
+        assert!(fall < 0.0);
+    }
+
+    #[test]
+    fn test_ladder_climbs_towards_look_direction() {
+        // Given - pushing forward, towards the ladder
+        let forward = vec3(0.0, 0.0, -0.1);
+
+        // Then
+        assert!(ladder_climb(forward, vec3(0.0, 0.5, -1.0)) > 0.0);
+        assert!(ladder_climb(forward, vec3(0.0, -1.0, -1.0)) < 0.0);
+        assert_eq!(ladder_climb(vec3(0.1, 0.0, 0.0), vec3(0.0, 0.0, -1.0)), 0.0);
+    }
+}
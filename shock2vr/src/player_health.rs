@@ -0,0 +1,140 @@
+///
+/// player_health.rs
+///
+/// The player's hit points and armor - carried from level to level, like the quest info
+///
+use serde::{Deserialize, Serialize};
+use shipyard::Unique;
+
+use crate::scripts::DamageType;
+
+// What the player starts with - the armor is a light vest, worn down by the hits it absorbs
+const STARTING_HIT_POINTS: f32 = 50.0;
+const STARTING_ARMOR: f32 = 15.0;
+
+// Landing faster than this, in units per second, hurts
+const SAFE_FALL_SPEED: f32 = 8.0;
+
+// Damage for every unit per second over the safe fall speed
+const DAMAGE_PER_FALL_SPEED: f32 = 4.0;
+
+#[derive(Deserialize, Serialize, Unique, Clone, Debug)]
+pub struct PlayerHealth {
+    hit_points: f32,
+    max_hit_points: f32,
+    armor: f32,
+}
+
+impl PlayerHealth {
+    pub fn new() -> PlayerHealth {
+        PlayerHealth {
+            hit_points: STARTING_HIT_POINTS,
+            max_hit_points: STARTING_HIT_POINTS,
+            armor: STARTING_ARMOR,
+        }
+    }
+
+    pub fn hit_points(&self) -> f32 {
+        self.hit_points
+    }
+
+    pub fn max_hit_points(&self) -> f32 {
+        self.max_hit_points
+    }
+
+    pub fn armor(&self) -> f32 {
+        self.armor
+    }
+
+    pub fn max_armor(&self) -> f32 {
+        STARTING_ARMOR
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.hit_points <= 0.0
+    }
+
+    ///
+    /// take_damage
+    ///
+    /// Hurt the player, with the armor soaking up part of the damage while it lasts. Returns true
+    /// if this was the hit that killed them.
+    pub fn take_damage(&mut self, amount: f32, damage_type: DamageType) -> bool {
+        if self.is_dead() || amount <= 0.0 {
+            return false;
+        }
+
+        let absorbed = (amount * armor_absorption(damage_type)).min(self.armor);
+        self.armor -= absorbed;
+        self.hit_points = (self.hit_points - (amount - absorbed)).max(0.0);
+        self.is_dead()
+    }
+}
+
+// Fraction of each type of damage the armor absorbs - it stops bullets and shrapnel, but does
+// little against armor-piercing rounds, and nothing against toxins, radiation or energy
+fn armor_absorption(damage_type: DamageType) -> f32 {
+    match damage_type {
+        DamageType::Standard => 0.5,
+        DamageType::HighExplosive => 0.5,
+        DamageType::Incendiary => 0.25,
+        DamageType::ArmorPiercing => 0.2,
+        DamageType::Energy | DamageType::EMP | DamageType::Toxic => 0.0,
+    }
+}
+
+///
+/// fall_damage
+///
+/// Damage for landing at the given speed, in units per second
+pub fn fall_damage(landing_speed: f32) -> f32 {
+    (landing_speed - SAFE_FALL_SPEED).max(0.0) * DAMAGE_PER_FALL_SPEED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_armor_absorbs_until_worn_out() {
+        // Given
+        let mut health = PlayerHealth::new();
+
+        // When - the first shot is half absorbed, the second wears out what's left of the armor
+        health.take_damage(20.0, DamageType::Standard);
+        let after_first_shot = (health.hit_points(), health.armor());
+        health.take_damage(20.0, DamageType::Standard);
+        let after_second_shot = (health.hit_points(), health.armor());
+        health.take_damage(10.0, DamageType::Toxic);
+
+        // Then
+        assert_eq!(after_first_shot, (40.0, 5.0));
+        assert_eq!(after_second_shot, (25.0, 0.0));
+        assert_eq!(health.hit_points(), 15.0);
+    }
+
+    #[test]
+    fn test_only_the_killing_blow_reports_death() {
+        // Given
+        let mut health = PlayerHealth::new();
+
+        // When
+        let survived = health.take_damage(10.0, DamageType::Energy);
+        let killed = health.take_damage(100.0, DamageType::Energy);
+        let already_dead = health.take_damage(10.0, DamageType::Energy);
+
+        // Then
+        assert!(!survived);
+        assert!(killed);
+        assert!(!already_dead);
+        assert_eq!(health.hit_points(), 0.0);
+    }
+
+    #[test]
+    fn test_fall_damage_needs_a_long_fall() {
+        // Then - jumping down a step is fine, falling from a height isn't
+        assert_eq!(fall_damage(4.0), 0.0);
+        assert_eq!(fall_damage(SAFE_FALL_SPEED), 0.0);
+        assert_eq!(fall_damage(11.0), 12.0);
+    }
+}
@@ -5,6 +5,7 @@
 ///
 use std::collections::{HashMap, HashSet};
 
+use cgmath::{Quaternion, Vector3};
 use dark::properties::{KeyCard, QuestBitValue};
 use serde::{Deserialize, Serialize};
 use shipyard::Unique;
@@ -14,6 +15,12 @@ pub struct QuestInfo {
     quest_bit_values: HashMap<String, QuestBitValue>,
     played_emails: HashSet<String>,
     key_cards: Vec<KeyCard>,
+    // The currency for resurrection stations
+    #[serde(default)]
+    nanites: u32,
+    // Where the player is rebuilt, for each level with an activated resurrection station
+    #[serde(default)]
+    resurrection_stations: HashMap<String, (Vector3<f32>, Quaternion<f32>)>,
 }
 
 impl QuestInfo {
@@ -22,6 +29,8 @@ impl QuestInfo {
             quest_bit_values: HashMap::new(),
             played_emails: HashSet::new(),
             key_cards: Vec::new(),
+            nanites: 0,
+            resurrection_stations: HashMap::new(),
         }
     }
 
@@ -53,4 +62,44 @@ impl QuestInfo {
     pub fn mark_email_as_played(&mut self, email: &str) {
         self.played_emails.insert(email.to_owned());
     }
+
+    pub fn nanites(&self) -> u32 {
+        self.nanites
+    }
+
+    pub fn add_nanites(&mut self, amount: u32) {
+        self.nanites += amount;
+    }
+
+    ///
+    /// spend_nanites
+    ///
+    /// Take the nanites, if the player has enough - returns false, and takes nothing, if they don't
+    pub fn spend_nanites(&mut self, amount: u32) -> bool {
+        if self.nanites < amount {
+            return false;
+        }
+
+        self.nanites -= amount;
+        true
+    }
+
+    pub fn activate_resurrection_station(
+        &mut self,
+        level_name: &str,
+        position: Vector3<f32>,
+        rotation: Quaternion<f32>,
+    ) {
+        self.resurrection_stations
+            .insert(level_name.to_ascii_lowercase(), (position, rotation));
+    }
+
+    pub fn resurrection_station(
+        &self,
+        level_name: &str,
+    ) -> Option<(Vector3<f32>, Quaternion<f32>)> {
+        self.resurrection_stations
+            .get(&level_name.to_ascii_lowercase())
+            .copied()
+    }
 }
@@ -7,7 +7,7 @@
  * - They are not part of SS2 / Dark - just convenience properties for implementing the game.
  * - They are not serialized / deserialized
  */
-use cgmath::Matrix4;
+use cgmath::{Matrix4, Vector3};
 use dark::ss2_bin_obj_loader::Vhot;
 use shipyard::Component;
 
@@ -30,6 +30,10 @@ pub struct RuntimeBitmapAnimationFrameCount(pub u32);
 #[derive(Component, Debug)]
 pub struct RuntimePropVhots(pub Vec<Vhot>);
 
+// RuntimePropHeldVelocity - how fast a held entity is being swung by the player's hand
+#[derive(Component)]
+pub struct RuntimePropHeldVelocity(pub Vector3<f32>);
+
 // RuntimePropDoNotSerialize - runtime prop to signal that this prop should not be serialized
 #[derive(Component)]
 pub struct RuntimePropDoNotSerialize;
@@ -37,3 +41,16 @@ pub struct RuntimePropDoNotSerialize;
 // RuntimePropProxyEntity - pointer to the parent entity (for example, hitboxes use this to point to the parent entity)
 #[derive(Component)]
 pub struct RuntimePropProxyEntity(pub shipyard::EntityId);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AIAwareness {
+    Unaware,
+    // Lost track of the player, and searching
+    Alert,
+    // Actively engaging the player
+    Combat,
+}
+
+// RuntimePropAIAwareness - how aware an AI is of the player, used to drive the music
+#[derive(Component)]
+pub struct RuntimePropAIAwareness(pub AIAwareness);
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use shipyard::{EntityId, World};
 
+use crate::holster::HolsterSlot;
+
 use super::EntitySaveData;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -9,6 +13,8 @@ pub struct HeldItemSaveData {
     pub entity_in_left_hand: Option<u64>,
     pub entity_in_right_hand: Option<u64>,
     pub inventory_entity: Option<u64>,
+    #[serde(default)]
+    pub holstered_entities: HashMap<HolsterSlot, u64>,
 }
 
 impl HeldItemSaveData {
@@ -18,6 +24,7 @@ impl HeldItemSaveData {
             entity_in_left_hand: None,
             entity_in_right_hand: None,
             inventory_entity: None,
+            holstered_entities: HashMap::new(),
         }
     }
 
@@ -25,7 +32,12 @@ impl HeldItemSaveData {
     pub fn instantiate(
         &self,
         world: &mut World,
-    ) -> (Option<EntityId>, Option<EntityId>, Option<EntityId>) {
+    ) -> (
+        Option<EntityId>,
+        Option<EntityId>,
+        Option<EntityId>,
+        HashMap<HolsterSlot, EntityId>,
+    ) {
         let (_, entity_id_map) = self.held_entities.instantiate(world);
 
         let mut left_hand_entity_id = None;
@@ -50,10 +62,21 @@ impl HeldItemSaveData {
             }
         }
 
+        let holstered_entity_ids = self
+            .holstered_entities
+            .iter()
+            .filter_map(|(slot, ent)| {
+                entity_id_map
+                    .get(&EntityId::from_inner(*ent).unwrap())
+                    .map(|new_entity_id| (*slot, *new_entity_id))
+            })
+            .collect();
+
         (
             left_hand_entity_id,
             right_hand_entity_id,
             inventory_entity_id,
+            holstered_entity_ids,
         )
     }
 }
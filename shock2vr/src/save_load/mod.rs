@@ -38,6 +38,11 @@ fn get_held_items(world: &World) -> HashSet<u64> {
         add_contained_entities(&mut out, world, 2, right_hand);
     }
 
+    for holstered in player.holstered_entity_ids.values() {
+        out.insert(holstered.inner());
+        add_contained_entities(&mut out, world, 2, *holstered);
+    }
+
     out.insert(player.inventory_entity_id.inner());
     add_contained_entities(&mut out, world, 2, player.inventory_entity_id);
 
@@ -159,6 +164,49 @@ pub fn to_save_data(world: &World) -> (EntitySaveData, HeldItemSaveData) {
         entity_in_right_hand: player.right_hand_entity_id.map(|ent| ent.inner()),
         held_entities: held_entity_data,
         inventory_entity: Some(player.inventory_entity_id.inner()),
+        holstered_entities: player
+            .holstered_entity_ids
+            .iter()
+            .map(|(slot, ent)| (*slot, ent.inner()))
+            .collect(),
     };
     (world_entity_data, held_metadata)
 }
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::PropObjName;
+    use shipyard::{Get, UniqueViewMut};
+
+    use super::*;
+    use crate::{
+        holster::HolsterSlot,
+        test_harness::{EntityFixtures, MissionHarness},
+    };
+
+    #[test]
+    fn test_holstered_items_are_restored_from_save() {
+        // Given - a wrench in the left hip holster
+        let mut fixtures = EntityFixtures::new();
+        fixtures.add_entity(1, PropObjName("Wrench".to_owned()));
+        let harness = MissionHarness::from_save_data(&fixtures.build());
+        let wrench = harness.entity(1);
+        harness
+            .world
+            .borrow::<UniqueViewMut<PlayerInfo>>()
+            .unwrap()
+            .holstered_entity_ids
+            .insert(HolsterSlot::LeftHip, wrench);
+
+        // When - the game is saved, and loaded into a new world
+        let (_, held_items) = to_save_data(&harness.world);
+        let mut world = World::new();
+        let (_, _, _, holstered) = held_items.instantiate(&mut world);
+
+        // Then - the wrench is back in the same slot
+        assert_eq!(holstered.len(), 1);
+        let restored = holstered[&HolsterSlot::LeftHip];
+        let v_obj_name = world.borrow::<View<PropObjName>>().unwrap();
+        assert_eq!(v_obj_name.get(restored).unwrap().0, "Wrench");
+    }
+}
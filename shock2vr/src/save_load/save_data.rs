@@ -4,7 +4,7 @@
  * Data type for information we serialize to load/save the game
  */
 use super::{EntitySaveData, HeldItemSaveData};
-use crate::quest_info::QuestInfo;
+use crate::{player_health::PlayerHealth, quest_info::QuestInfo};
 use cgmath::{Quaternion, Vector3};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -37,6 +37,9 @@ pub struct GlobalData {
     pub position: Vector3<f32>,
     pub rotation: Quaternion<f32>,
     pub quest_info: QuestInfo,
+    // Saves from before the player had health start out healthy
+    #[serde(default = "PlayerHealth::new")]
+    pub player_health: PlayerHealth,
     pub held_items: HeldItemSaveData,
     pub active_mission: String,
 }
@@ -11,7 +11,7 @@ use shipyard::{EntityId, Get, IntoIter, IntoWithId, UniqueView, View, World};
 
 use crate::{
     creature,
-    mission::{entity_creator::CreateEntityOptions, PlayerInfo},
+    mission::{entity_creator::CreateEntityOptions, PlayerInfo, FOOTSTEP_RUN_SPEED},
     physics::{InternalCollisionGroups, PhysicsWorld},
     runtime_props::{RuntimePropJointTransforms, RuntimePropTransform},
     scripts::{script_util::get_first_link_with_template_and_data, Effect},
@@ -181,7 +181,10 @@ pub fn fire_ranged_weapon(world: &World, entity_id: EntityId, rotation: Quaterni
                 position: point3(0.0, 0.0, 0.0) + forward,
                 orientation: Quaternion::from_angle_y(Deg(90.0)),
                 root_transform: root_transform.0 * rot_matrix,
-                options: CreateEntityOptions::default(),
+                options: CreateEntityOptions {
+                    launcher: Some(entity_id),
+                    ..CreateEntityOptions::default()
+                },
             });
 
             fire_effects.push(play_positional_sound(
@@ -287,7 +290,10 @@ pub fn fire_ranged_projectile(world: &World, entity_id: EntityId) -> Effect {
             orientation: Quaternion::from_angle_y(Deg(90.0)),
             // root_transform: transform * rot_matrix,
             root_transform: transform,
-            options: CreateEntityOptions::default(),
+            options: CreateEntityOptions {
+                launcher: Some(entity_id),
+                ..CreateEntityOptions::default()
+            },
         }
     } else {
         Effect::NoEffect
@@ -334,6 +340,29 @@ pub fn play_positional_sound(
     }
 }
 
+///
+/// play_footstep
+///
+/// Footstep for a creature, triggered from the foot step flags in its motions
+pub fn play_footstep(entity_id: EntityId, world: &World, physics: &PhysicsWorld) -> Effect {
+    let v_pos = world.borrow::<View<PropPosition>>().unwrap();
+
+    if let Ok(pos) = v_pos.get(entity_id) {
+        let speed = physics
+            .get_velocity(entity_id)
+            .map(|velocity| vec3(velocity.x, 0.0, velocity.z).magnitude())
+            .unwrap_or(0.0);
+
+        Effect::PlayFootstep {
+            entity_id,
+            position: pos.position,
+            is_running: speed > FOOTSTEP_RUN_SPEED,
+        }
+    } else {
+        Effect::NoEffect
+    }
+}
+
 pub fn is_player_visible(from_entity: EntityId, world: &World, physics: &PhysicsWorld) -> bool {
     let u_player = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
     let v_current_pos = world.borrow::<View<PropPosition>>().unwrap();
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, collections::HashSet};
+use std::{cell::RefCell, collections::HashSet, time::Duration};
 
 use cgmath::{vec3, vec4, Deg, MetricSpace, Quaternion, Rotation3};
 use dark::{
@@ -6,11 +6,13 @@ use dark::{
     properties::{Link, PropAISignalResponse, PropPosition},
     SCALE_FACTOR,
 };
+use rand::{thread_rng, Rng};
 use shipyard::{EntityId, Get, View, World};
 
 use crate::{
     mission::PlayerInfo,
     physics::{InternalCollisionGroups, PhysicsWorld},
+    runtime_props::AIAwareness,
     scripts::script_util,
     time::Time,
 };
@@ -18,9 +20,23 @@ use crate::{
 use super::{
     ai_util::*,
     behavior::*,
+    get_damage_modifier,
     steering::{Steering, SteeringOutput},
     Effect, Message, MessagePayload, Script,
 };
+
+// How far away a monster can notice the player from
+const AWARENESS_DISTANCE: f32 = 60.0 / SCALE_FACTOR;
+
+// How long the player needs to be out of sight before the monster loses contact
+const LOST_CONTACT_TIME: Duration = Duration::from_secs(5);
+
+// How long the monster stays alert, searching, after losing contact with the player
+const SEARCH_TIME: Duration = Duration::from_secs(20);
+
+// Range of seconds between idle mumbling, while unaware of the player
+const IDLE_SPEECH_INTERVAL: std::ops::Range<f32> = 10.0..30.0;
+
 pub struct AnimatedMonsterAI {
     last_hit_sensor: Option<EntityId>,
     current_behavior: Box<RefCell<dyn Behavior>>,
@@ -30,6 +46,10 @@ pub struct AnimatedMonsterAI {
     animation_seq: u32,
 
     played_ai_watch_obj: HashSet<EntityId>,
+
+    awareness: AIAwareness,
+    last_saw_player: Duration,
+    next_idle_speech: Option<Duration>,
 }
 
 impl AnimatedMonsterAI {
@@ -45,6 +65,10 @@ impl AnimatedMonsterAI {
             last_hit_sensor: None,
 
             played_ai_watch_obj: HashSet::new(),
+
+            awareness: AIAwareness::Unaware,
+            last_saw_player: Duration::ZERO,
+            next_idle_speech: None,
         }
     }
     pub fn new() -> AnimatedMonsterAI {
@@ -58,6 +82,10 @@ impl AnimatedMonsterAI {
             animation_seq: 0,
             last_hit_sensor: None,
             played_ai_watch_obj: HashSet::new(),
+
+            awareness: AIAwareness::Unaware,
+            last_saw_player: Duration::ZERO,
+            next_idle_speech: None,
         }
     }
 
@@ -85,6 +113,77 @@ impl AnimatedMonsterAI {
         }
     }
 
+    ///
+    /// update_awareness
+    ///
+    /// Track whether the monster has seen the player recently, and voice the transitions:
+    /// spotting the player, losing contact, giving up the search, and idle mumbling while unaware.
+    fn update_awareness(
+        &mut self,
+        world: &World,
+        physics: &PhysicsWorld,
+        entity_id: EntityId,
+        time: &Time,
+    ) -> Effect {
+        if self.is_dead || is_killed(entity_id, world) {
+            return Effect::NoEffect;
+        }
+
+        let can_see_player = player_is_within_distance(world, entity_id, AWARENESS_DISTANCE)
+            && is_player_visible(entity_id, world, physics);
+
+        if can_see_player {
+            self.last_saw_player = time.total;
+        }
+
+        let time_since_saw_player = time.total.saturating_sub(self.last_saw_player);
+        let (next_awareness, maybe_concept) = match self.awareness {
+            _ if can_see_player && self.awareness != AIAwareness::Combat => {
+                (AIAwareness::Combat, Some("spotplayer"))
+            }
+            AIAwareness::Combat if time_since_saw_player > LOST_CONTACT_TIME => {
+                (AIAwareness::Alert, Some("lostcontact"))
+            }
+            AIAwareness::Alert if time_since_saw_player > LOST_CONTACT_TIME + SEARCH_TIME => {
+                self.next_idle_speech = None;
+                (AIAwareness::Unaware, Some("backtozero"))
+            }
+            AIAwareness::Unaware => match self.next_idle_speech {
+                Some(next_idle_speech) if time.total >= next_idle_speech => {
+                    self.next_idle_speech = None;
+                    (AIAwareness::Unaware, Some("atlevelzero"))
+                }
+                Some(_) => (AIAwareness::Unaware, None),
+                None => {
+                    let delay = thread_rng().gen_range(IDLE_SPEECH_INTERVAL);
+                    self.next_idle_speech = Some(time.total + Duration::from_secs_f32(delay));
+                    (AIAwareness::Unaware, None)
+                }
+            },
+            awareness => (awareness, None),
+        };
+
+        let awareness_effect = if next_awareness != self.awareness {
+            self.awareness = next_awareness;
+            Effect::SetAIAwareness {
+                entity_id,
+                awareness: next_awareness,
+            }
+        } else {
+            Effect::NoEffect
+        };
+
+        let speech_effect = match maybe_concept {
+            Some(concept) => Effect::Speak {
+                entity_id,
+                concept: concept.to_owned(),
+            },
+            None => Effect::NoEffect,
+        };
+
+        Effect::combine(vec![awareness_effect, speech_effect])
+    }
+
     fn try_tickle_sensor(
         &mut self,
         world: &World,
@@ -239,11 +338,14 @@ impl Script for AnimatedMonsterAI {
 
         let sensor_effect = self.try_tickle_sensor(world, physics, entity_id);
 
+        let speech_effect = self.update_awareness(world, physics, entity_id, time);
+
         Effect::combine(vec![
             steering_effects,
             rotation_effect,
             debug_effect,
             sensor_effect,
+            speech_effect,
         ])
     }
 
@@ -260,12 +362,17 @@ impl Script for AnimatedMonsterAI {
                 .handle_message(entity_id, world, physics, msg);
         }
         match msg {
-            MessagePayload::Damage { amount } => {
+            MessagePayload::Damage {
+                amount,
+                damage_type,
+                ..
+            } => {
                 // TODO: Let behavior handle this?
                 //self.took_damage = true;
+                let modifier = get_damage_modifier(world, entity_id, *damage_type);
                 Effect::AdjustHitPoints {
                     entity_id,
-                    delta: -(amount.round() as i32),
+                    delta: -((amount * modifier).round() as i32),
                 }
             }
             MessagePayload::TurnOn { from } => {
@@ -317,11 +424,22 @@ impl Script for AnimatedMonsterAI {
                     Effect::NoEffect
                 } else if is_killed(entity_id, world) {
                     self.current_behavior = Box::new(RefCell::new(DeadBehavior {}));
-                    Effect::QueueAnimationBySchema {
-                        entity_id,
-                        motion_query_items: vec![MotionQueryItem::new("crumple")],
-                        selection_strategy: dark::motion::MotionQuerySelectionStrategy::Random,
-                    }
+                    self.awareness = AIAwareness::Unaware;
+                    Effect::combine(vec![
+                        Effect::SetAIAwareness {
+                            entity_id,
+                            awareness: AIAwareness::Unaware,
+                        },
+                        Effect::Speak {
+                            entity_id,
+                            concept: "comdieloud".to_owned(),
+                        },
+                        Effect::QueueAnimationBySchema {
+                            entity_id,
+                            motion_query_items: vec![MotionQueryItem::new("crumple")],
+                            selection_strategy: dark::motion::MotionQuerySelectionStrategy::Random,
+                        },
+                    ])
                 } else if self.took_damage {
                     self.took_damage = false;
                     Effect::QueueAnimationBySchema {
@@ -388,7 +506,15 @@ impl Script for AnimatedMonsterAI {
                 }
             }
             MessagePayload::AnimationFlagTriggered { motion_flags } => {
-                if motion_flags.contains(MotionFlags::FIRE) {
+                let footstep_effect = if motion_flags
+                    .intersects(MotionFlags::LEFT_FOOT_STEP | MotionFlags::RIGHT_FOOT_STEP)
+                {
+                    play_footstep(entity_id, world, physics)
+                } else {
+                    Effect::NoEffect
+                };
+
+                let flag_effect = if motion_flags.contains(MotionFlags::FIRE) {
                     fire_ranged_projectile(world, entity_id)
                 // } else if motion_flags.contains(MotionFlags::END) {
                 //     Effect::QueueAnimationBySchema {
@@ -405,7 +531,9 @@ impl Script for AnimatedMonsterAI {
                     Effect::NoEffect
                 } else {
                     Effect::NoEffect
-                }
+                };
+
+                Effect::combine(vec![flag_effect, footstep_effect])
             }
             _ => Effect::NoEffect,
         }
@@ -413,6 +541,10 @@ impl Script for AnimatedMonsterAI {
 }
 
 fn player_is_within_watch_obj(world: &World, entity_id: EntityId, radius: f32) -> bool {
+    player_is_within_distance(world, entity_id, radius)
+}
+
+fn player_is_within_distance(world: &World, entity_id: EntityId, radius: f32) -> bool {
     let u_player = world.borrow::<shipyard::UniqueView<PlayerInfo>>().unwrap();
     let v_current_pos = world.borrow::<View<PropPosition>>().unwrap();
 
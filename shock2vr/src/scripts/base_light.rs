@@ -0,0 +1,33 @@
+use shipyard::{EntityId, World};
+
+use crate::physics::PhysicsWorld;
+
+use super::{Effect, MessagePayload, Script};
+
+pub struct BaseLight {}
+impl BaseLight {
+    pub fn new() -> BaseLight {
+        BaseLight {}
+    }
+}
+impl Script for BaseLight {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        _world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::TurnOn { from: _ } => Effect::SetLightOn {
+                entity_id,
+                is_on: true,
+            },
+            MessagePayload::TurnOff { from: _ } | MessagePayload::Slay => Effect::SetLightOn {
+                entity_id,
+                is_on: false,
+            },
+            _ => Effect::NoEffect,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use cgmath::{Point3, Vector3};
+use dark::properties::Link;
+use shipyard::{EntityId, UniqueView, World};
+
+use crate::{
+    creature::{get_entity_creature, ActorType},
+    mission::GlobalEntityMetadata,
+    scripts::script_util::get_all_links_with_template,
+};
+
+// Damage done by a projectile - the intensity of the stimulus it gives off is in the link data,
+// which isn't read yet
+const DEFAULT_PROJECTILE_DAMAGE: f32 = 6.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageType {
+    Standard = 0,
+    ArmorPiercing = 1,
+    HighExplosive = 2,
+    Energy = 3,
+    EMP = 4,
+    Toxic = 5,
+    Incendiary = 6,
+}
+
+// Where a hit landed, in world space
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HitLocation {
+    pub point: Point3<f32>,
+    pub normal: Vector3<f32>,
+}
+
+///
+/// get_damage_modifier
+///
+/// How much of a type of damage the entity takes - from the creature type it has in the gamesys.
+/// Entities that aren't creatures take full damage.
+pub fn get_damage_modifier(world: &World, entity_id: EntityId, damage_type: DamageType) -> f32 {
+    get_entity_creature(world, entity_id)
+        .map(|creature| default_damage_modifier(&creature.actor_type, damage_type))
+        .unwrap_or(1.0)
+}
+
+///
+/// get_projectile_damage
+///
+/// How much damage a projectile does, and of what type - the type comes from the stimulus it
+/// gives off.
+pub fn get_projectile_damage(world: &World, entity_id: EntityId) -> (f32, DamageType) {
+    let damage_type = get_stimulus_damage_type(world, entity_id).unwrap_or(DamageType::Standard);
+    (DEFAULT_PROJECTILE_DAMAGE, damage_type)
+}
+
+///
+/// get_stimulus_damage_type
+///
+/// The type of damage an object does, from the name of a stimulus it gives off via its act/react
+/// source links - or None if it doesn't give off a damaging stimulus.
+pub fn get_stimulus_damage_type(world: &World, entity_id: EntityId) -> Option<DamageType> {
+    let stimulus_template_ids = get_all_links_with_template(world, entity_id, |link| match link {
+        Link::StimulusSource => Some(()),
+        _ => None,
+    });
+
+    let entity_metadata = world.borrow::<UniqueView<GlobalEntityMetadata>>().ok()?;
+    stimulus_template_ids.iter().find_map(|(template_id, _)| {
+        entity_metadata
+            .0
+            .iter()
+            .find(|(_, metadata)| metadata.template_id == *template_id)
+            .and_then(|(name, _)| damage_type_for_stimulus(name))
+    })
+}
+
+// Stimuli are named after the damage they do, ie 'EMP' or 'Armor Piercing Stim' - spacing,
+// dashes, case and a trailing 'stim' don't matter
+fn damage_type_for_stimulus(name: &str) -> Option<DamageType> {
+    let name = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let name = name.strip_suffix("stim").unwrap_or(&name);
+
+    match name {
+        "standard" => Some(DamageType::Standard),
+        "armorpiercing" | "ap" => Some(DamageType::ArmorPiercing),
+        "highexplosive" | "he" => Some(DamageType::HighExplosive),
+        "energy" => Some(DamageType::Energy),
+        "emp" => Some(DamageType::EMP),
+        "toxic" => Some(DamageType::Toxic),
+        "incendiary" => Some(DamageType::Incendiary),
+        _ => None,
+    }
+}
+
+// Resistances by kind of creature, used when the gamesys doesn't specify them: robots shrug off
+// toxins and are vulnerable to EMP and armor-piercing rounds, organics are the opposite.
+fn default_damage_modifier(actor_type: &ActorType, damage_type: DamageType) -> f32 {
+    let is_robotic = matches!(actor_type, ActorType::Droid);
+    match (is_robotic, damage_type) {
+        (true, DamageType::ArmorPiercing) => 1.5,
+        (true, DamageType::EMP) => 2.0,
+        (true, DamageType::Toxic) => 0.0,
+        (true, DamageType::Incendiary) => 0.5,
+        (false, DamageType::ArmorPiercing) => 0.5,
+        (false, DamageType::EMP) => 0.0,
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dark::properties::PropCreature;
+
+    use super::*;
+
+    // Index of the droid in the creature definitions
+    const DROID_CREATURE_TYPE: u32 = 4;
+
+    #[test]
+    fn test_damage_modifiers_come_from_the_creature_type() {
+        // Given - a droid, and an object that isn't a creature
+        let mut world = World::new();
+        let droid = world.add_entity((PropCreature(DROID_CREATURE_TYPE),));
+        let crate_entity = world.add_entity(());
+
+        // When
+        let droid_emp = get_damage_modifier(&world, droid, DamageType::EMP);
+        let droid_toxic = get_damage_modifier(&world, droid, DamageType::Toxic);
+        let crate_emp = get_damage_modifier(&world, crate_entity, DamageType::EMP);
+
+        // Then
+        assert_eq!((droid_emp, droid_toxic, crate_emp), (2.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn test_damage_type_for_stimulus() {
+        assert_eq!(damage_type_for_stimulus("EMPStim"), Some(DamageType::EMP));
+        assert_eq!(
+            damage_type_for_stimulus("Armor-Piercing Stim"),
+            Some(DamageType::ArmorPiercing)
+        );
+        assert_eq!(damage_type_for_stimulus("energy"), Some(DamageType::Energy));
+        assert_eq!(damage_type_for_stimulus("Radiation Stim"), None);
+    }
+
+    #[test]
+    fn test_default_damage_modifiers_favor_weapon_choice() {
+        // Then - EMP is for droids, armor-piercing rounds aren't for hybrids
+        assert_eq!(
+            default_damage_modifier(&ActorType::Droid, DamageType::EMP),
+            2.0
+        );
+        assert_eq!(
+            default_damage_modifier(&ActorType::Human, DamageType::EMP),
+            0.0
+        );
+        assert_eq!(
+            default_damage_modifier(&ActorType::Human, DamageType::ArmorPiercing),
+            0.5
+        );
+    }
+}
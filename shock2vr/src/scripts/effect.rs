@@ -9,11 +9,15 @@ use shipyard::{Component, EntityId};
 
 use crate::{
     gui::{GuiComponentRenderInfo, GuiHandle},
+    head_collision::HeadCollisionMode,
+    locomotion::LocomotionSettings,
     mission::entity_creator::CreateEntityOptions,
+    runtime_props::AIAwareness,
     vr_config::Handedness,
+    DebugFlag,
 };
 
-use super::Message;
+use super::{DamageType, Message};
 
 #[derive(Clone, Debug)]
 pub enum GlobalEffect {
@@ -32,6 +36,18 @@ pub enum GlobalEffect {
         loc: Option<i32>,
     },
 
+    // Flip one of the debug options, like portal or physics rendering
+    ToggleDebugFlag(DebugFlag),
+
+    // Switch how the player turns and moves, like snap turning or teleporting
+    SetLocomotion(LocomotionSettings),
+
+    // Switch what happens when the player leans into a wall
+    SetHeadCollision(HeadCollisionMode),
+
+    // The player's hit points ran out
+    PlayerDied,
+
     // Test the reload functionality (as if saving + loading)
     TestReload,
 }
@@ -44,11 +60,27 @@ pub enum Effect {
         amount: i32,
     },
 
+    AwardNanites {
+        amount: u32,
+    },
+
+    // Remember where the player is rebuilt, if they die on this level
+    ActivateResurrectionStation {
+        position: Vector3<f32>,
+        rotation: Quaternion<f32>,
+    },
+
     AdjustHitPoints {
         entity_id: EntityId,
         delta: i32,
     },
 
+    // Hurt the player - the player's armor absorbs some of it
+    DamagePlayer {
+        amount: f32,
+        damage_type: DamageType,
+    },
+
     ApplyForce {
         entity_id: EntityId,
         force: Vector3<f32>,
@@ -83,6 +115,10 @@ pub enum Effect {
         parent_entity_id: EntityId,
         dropped_entity_id: EntityId,
     },
+    // Create an item directly in the player's inventory
+    GiveItem {
+        template_id: i32,
+    },
     GrabEntity {
         entity_id: EntityId,
         hand: Handedness,
@@ -121,6 +157,12 @@ pub enum Effect {
         query: EnvSoundQuery,
         position: Vector3<f32>,
     },
+    // Play a footstep for the entity, based on the surface material below the position
+    PlayFootstep {
+        entity_id: EntityId,
+        position: Vector3<f32>,
+        is_running: bool,
+    },
     PositionInventory {
         position: Vector3<f32>,
         rotation: Quaternion<f32>,
@@ -128,10 +170,20 @@ pub enum Effect {
     StopSound {
         handle: AudioHandle,
     },
+    // Scale the volume of a playing sound, like an impact that should be louder the harder it hits
+    SetSoundVolume {
+        handle: AudioHandle,
+        volume: f32,
+    },
     SetPosition {
         entity_id: EntityId,
         position: Vector3<f32>,
     },
+    // Have a creature say a speech concept, like 'spotplayer' or 'comdieloud'
+    Speak {
+        entity_id: EntityId,
+        concept: String,
+    },
     SetRotation {
         entity_id: EntityId,
         rotation: Quaternion<f32>,
@@ -146,6 +198,10 @@ pub enum Effect {
         joint_id: u32,
         transform: Matrix4<f32>,
     },
+    SetAIAwareness {
+        entity_id: EntityId,
+        awareness: AIAwareness,
+    },
     SetPlayerPosition {
         position: Vector3<f32>,
         is_teleport: bool,
@@ -169,6 +225,11 @@ pub enum Effect {
         key_card: KeyCard,
     },
 
+    SetLightOn {
+        entity_id: EntityId,
+        is_on: bool,
+    },
+
     TurnOffTweqs {
         entity_id: EntityId,
     },
@@ -0,0 +1,178 @@
+use shipyard::{EntityId, UniqueView, World};
+
+use crate::{mission::PlayerInfo, physics::PhysicsWorld, time::Time};
+
+use super::{
+    damage::get_stimulus_damage_type, DamageType, Effect, Message, MessagePayload, Script,
+};
+
+// How often the hazard hurts the player while they stay in it. The first hit comes after a full
+// tick, so just cutting through a corner is free.
+const TICK_IN_SECONDS: f32 = 1.0;
+
+// Script for rooms and volumes that hurt the player while they're inside, like radiation rooms and
+// toxic areas
+pub struct HazardRoom {
+    damage_type: DamageType,
+    damage_per_tick: f32,
+    is_player_inside: bool,
+    time_until_tick_in_seconds: f32,
+}
+
+impl HazardRoom {
+    pub fn radiation() -> HazardRoom {
+        // There's no radiation damage type - it goes straight through armor, like a toxin
+        HazardRoom::new(DamageType::Toxic, 2.0)
+    }
+
+    pub fn toxic() -> HazardRoom {
+        HazardRoom::new(DamageType::Toxic, 3.0)
+    }
+
+    fn new(damage_type: DamageType, damage_per_tick: f32) -> HazardRoom {
+        HazardRoom {
+            damage_type,
+            damage_per_tick,
+            is_player_inside: false,
+            time_until_tick_in_seconds: TICK_IN_SECONDS,
+        }
+    }
+
+    // Count down to the next hit - returns true when it's time to hurt the player
+    fn tick(&mut self, elapsed_in_seconds: f32) -> bool {
+        if !self.is_player_inside {
+            return false;
+        }
+
+        self.time_until_tick_in_seconds -= elapsed_in_seconds;
+        if self.time_until_tick_in_seconds > 0.0 {
+            return false;
+        }
+
+        self.time_until_tick_in_seconds += TICK_IN_SECONDS;
+        true
+    }
+}
+
+impl Script for HazardRoom {
+    fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
+        // Damage volumes give off the stimulus they hurt with - toxic areas give off a toxic one
+        if let Some(damage_type) = get_stimulus_damage_type(world, entity_id) {
+            self.damage_type = damage_type;
+        }
+
+        Effect::NoEffect
+    }
+
+    fn update(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        time: &Time,
+    ) -> Effect {
+        if !self.tick(time.elapsed.as_secs_f32()) {
+            return Effect::NoEffect;
+        }
+
+        let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+        Effect::Send {
+            msg: Message {
+                to: player_entity_id,
+                payload: MessagePayload::Damage {
+                    amount: self.damage_per_tick,
+                    damage_type: self.damage_type,
+                    source: Some(entity_id),
+                    hit: None,
+                },
+            },
+        }
+    }
+
+    fn handle_message(
+        &mut self,
+        _entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+        match msg {
+            MessagePayload::SensorBeginIntersect { with } if *with == player_entity_id => {
+                self.is_player_inside = true;
+                self.time_until_tick_in_seconds = TICK_IN_SECONDS;
+            }
+            MessagePayload::SensorEndIntersect { with } if *with == player_entity_id => {
+                self.is_player_inside = false;
+            }
+            _ => (),
+        }
+
+        Effect::NoEffect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dark::properties::{Link, Links, ToLink};
+
+    use crate::mission::{EntityMetadata, GlobalEntityMetadata};
+
+    use super::*;
+
+    const INCENDIARY_STIMULUS_ID: i32 = -60;
+
+    #[test]
+    fn test_hazard_ticks_while_player_is_inside() {
+        // Given
+        let mut hazard = HazardRoom::radiation();
+
+        // When
+        let outside = hazard.tick(5.0);
+        hazard.is_player_inside = true;
+        let just_entered = hazard.tick(0.5);
+        let after_a_second = hazard.tick(0.5);
+        let right_after = hazard.tick(0.1);
+
+        // Then
+        assert!(!outside);
+        assert!(!just_entered);
+        assert!(after_a_second);
+        assert!(!right_after);
+    }
+
+    #[test]
+    fn test_damage_type_comes_from_the_stimulus_given_off() {
+        // Given - a toxic area, and a damage volume that gives off an incendiary stimulus
+        let mut world = World::new();
+        world.add_unique(GlobalEntityMetadata(HashMap::from([(
+            "incendiarystim".to_owned(),
+            EntityMetadata {
+                template_id: INCENDIARY_STIMULUS_ID,
+                obj_icon: None,
+                obj_short_name: None,
+                obj_name: None,
+            },
+        )])));
+        let toxic_area = world.add_entity((Links::empty(),));
+        let fire = world.add_entity((Links {
+            to_links: vec![ToLink {
+                to_template_id: INCENDIARY_STIMULUS_ID,
+                to_entity_id: None,
+                link: Link::StimulusSource,
+            }],
+        },));
+        let mut toxic_hazard = HazardRoom::toxic();
+        let mut fire_hazard = HazardRoom::toxic();
+
+        // When
+        toxic_hazard.initialize(toxic_area, &world);
+        fire_hazard.initialize(fire, &world);
+
+        // Then
+        assert_eq!(toxic_hazard.damage_type, DamageType::Toxic);
+        assert_eq!(fire_hazard.damage_type, DamageType::Incendiary);
+    }
+}
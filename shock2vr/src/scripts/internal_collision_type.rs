@@ -3,7 +3,7 @@ use shipyard::{EntityId, Get, View, World};
 
 use crate::physics::PhysicsWorld;
 
-use super::{Effect, MessagePayload, Script, Message};
+use super::{DamageType, Effect, Message, MessagePayload, Script};
 
 // Script to handle collision type
 pub struct InternalCollisionType {
@@ -54,7 +54,12 @@ impl Script for InternalCollisionType {
                 let damage_effect = Effect::Send {
                     msg: Message {
                         to: *with,
-                        payload: MessagePayload::Damage { amount: 1.0 },
+                        payload: MessagePayload::Damage {
+                            amount: 1.0,
+                            damage_type: DamageType::Standard,
+                            source: Some(entity_id),
+                            hit: None,
+                        },
                     },
                 };
                 Effect::Multiple(vec![initial_effect, damage_effect])
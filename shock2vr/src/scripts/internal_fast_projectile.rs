@@ -14,10 +14,11 @@ use crate::{
     runtime_props::RuntimePropTransform,
     scripts::{
         ai::ai_util::does_entity_have_hitboxes,
+        damage::get_projectile_damage,
         script_util::{
             get_first_link_with_template_and_data,
         },
-        Message,
+        HitLocation, Message,
     },
     time::Time,
     util::{
@@ -27,10 +28,14 @@ use crate::{
 
 use super::{Effect, MessagePayload, Script};
 
-pub struct InternalFastProjectileScript { velocity: Vector3<f32> }
+pub struct InternalFastProjectileScript {
+    velocity: Vector3<f32>,
+    // Whoever fired the projectile - it passes through them, so they can't shoot themselves
+    launcher: Option<EntityId>,
+}
 impl InternalFastProjectileScript {
-    pub fn new(velocity: Vector3<f32>) -> InternalFastProjectileScript {
-        InternalFastProjectileScript { velocity}
+    pub fn new(velocity: Vector3<f32>, launcher: Option<EntityId>) -> InternalFastProjectileScript {
+        InternalFastProjectileScript { velocity, launcher }
     }
 }
 
@@ -54,7 +59,7 @@ impl Script for InternalFastProjectileScript {
         // let forward = xform.transform_vector(vec3(0.0, 0.0, -1.0));
         let forward = self.velocity.normalize();
         let start_point = current_position - forward * SCALE_FACTOR * 0.25;
-        let maybe_hit_spot = projectile_ray_cast(start_point, forward, physics, distance, world);
+        let maybe_hit_spot = projectile_ray_cast(start_point, forward, physics, distance, world, self.launcher);
 
         if let Some(RayCastResult {
             hit_point,
@@ -75,12 +80,20 @@ impl Script for InternalFastProjectileScript {
                 vec4(0.0, 1.0, 0.0, 1.0)
             };
 
+            let (amount, damage_type) = get_projectile_damage(world, entity_id);
             let mut effects = vec![
                 Effect::Send {
                     msg: Message {
                         to: hit_entity_id,
-                        // TODO: Properly calculate damage
-                        payload: MessagePayload::Damage { amount: 6.0 },
+                        payload: MessagePayload::Damage {
+                            amount,
+                            damage_type,
+                            source: Some(entity_id),
+                            hit: Some(HitLocation {
+                                point: hit_point,
+                                normal: hit_normal,
+                            }),
+                        },
                     },
                 },
                 Effect::DrawDebugLines {
@@ -148,15 +161,20 @@ impl Script for InternalFastProjectileScript {
     }
 }
 
-fn projectile_ray_cast(start_point: Point3<f32>, forward: cgmath::Vector3<f32>, physics: &PhysicsWorld, distance: f32, world: &World) -> Option<RayCastResult> {
-     let mut maybe_hit_spot = physics.ray_cast( start_point,
+fn projectile_ray_cast(start_point: Point3<f32>, forward: cgmath::Vector3<f32>, physics: &PhysicsWorld, distance: f32, world: &World, launcher: Option<EntityId>) -> Option<RayCastResult> {
+     let mut maybe_hit_spot = physics.ray_cast2( start_point,
         forward * distance,
+        100.0,
         InternalCollisionGroups::ENTITY
             // Sometimes, the hitbox can stick out past the bounding box...
             // so we should still check for it here
             | InternalCollisionGroups::HITBOX 
             | InternalCollisionGroups::SELECTABLE
-            | InternalCollisionGroups::WORLD,
+            | InternalCollisionGroups::WORLD
+            // Monsters' shots can hit the player
+            | InternalCollisionGroups::PLAYER,
+        launcher,
+        true,
     );
 
     // If we hit an entity with a hitbox, scan again for the hitbox
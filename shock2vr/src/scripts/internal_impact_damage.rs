@@ -0,0 +1,128 @@
+use cgmath::InnerSpace;
+use shipyard::{EntityId, UniqueView, World};
+
+use crate::{mission::PlayerInfo, physics::PhysicsWorld};
+
+use super::{DamageType, Effect, Message, MessagePayload, Script};
+
+// Speed, in meters per second, an object has to be moving to hurt what it hits
+const MIN_IMPACT_SPEED: f32 = 4.0;
+
+// Damage for every meter per second over the minimum speed
+const DAMAGE_PER_IMPACT_SPEED: f32 = 1.5;
+
+// Script for items that can be picked up - when thrown, they damage whatever they hit. Only a
+// throw counts: items that fall, or get knocked around, without ever leaving a hand don't.
+pub struct InternalImpactDamage {
+    // How fast the item left the hand, until it first hits something
+    thrown_speed: Option<f32>,
+}
+
+impl InternalImpactDamage {
+    pub fn new() -> InternalImpactDamage {
+        InternalImpactDamage { thrown_speed: None }
+    }
+}
+
+impl Script for InternalImpactDamage {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::Hold => {
+                self.thrown_speed = None;
+                Effect::NoEffect
+            }
+            MessagePayload::Drop => {
+                // The item is let go with the velocity of the hand - tracked in play space, so
+                // the player turning or moving doesn't make a throw out of a drop
+                self.thrown_speed = physics
+                    .get_velocity(entity_id)
+                    .map(|velocity| velocity.magnitude());
+                Effect::NoEffect
+            }
+            MessagePayload::Collided { with } => {
+                // Don't let the player hurt themselves letting go of something
+                let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+                if *with == player_entity_id {
+                    return Effect::NoEffect;
+                }
+
+                // Only the first hit of a throw does damage, not each bounce after
+                let amount = match self.thrown_speed.take() {
+                    Some(speed) => impact_damage(speed),
+                    None => return Effect::NoEffect,
+                };
+
+                if amount <= 0.0 {
+                    return Effect::NoEffect;
+                }
+
+                Effect::Send {
+                    msg: Message {
+                        to: *with,
+                        payload: MessagePayload::Damage {
+                            amount,
+                            damage_type: DamageType::Standard,
+                            source: Some(entity_id),
+                            hit: None,
+                        },
+                    },
+                }
+            }
+            _ => Effect::NoEffect,
+        }
+    }
+}
+
+fn impact_damage(speed: f32) -> f32 {
+    (speed - MIN_IMPACT_SPEED).max(0.0) * DAMAGE_PER_IMPACT_SPEED
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_impact_damage_needs_a_throw() {
+        // Then - a dropped item doesn't hurt, a thrown one does
+        assert_eq!(impact_damage(2.0), 0.0);
+        assert_eq!(impact_damage(MIN_IMPACT_SPEED), 0.0);
+        assert_eq!(impact_damage(8.0), 6.0);
+    }
+
+    #[test]
+    fn test_items_never_thrown_do_no_damage() {
+        // Given - an item that hits something, without having been let go from a hand
+        let mut world = World::new();
+        let player = world.add_entity(());
+        let item = world.add_entity(());
+        let target = world.add_entity(());
+        world.add_unique(PlayerInfo {
+            pos: cgmath::vec3(0.0, 0.0, 0.0),
+            rotation: cgmath::Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            entity_id: player,
+            left_hand_entity_id: None,
+            right_hand_entity_id: None,
+            holstered_entity_ids: std::collections::HashMap::new(),
+            inventory_entity_id: player,
+        });
+        let physics = PhysicsWorld::new();
+        let mut script = InternalImpactDamage::new();
+
+        // When
+        let effect = script.handle_message(
+            item,
+            &world,
+            &physics,
+            &MessagePayload::Collided { with: target },
+        );
+
+        // Then
+        assert!(matches!(effect, Effect::NoEffect));
+    }
+}
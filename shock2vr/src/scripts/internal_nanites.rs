@@ -0,0 +1,67 @@
+use shipyard::{EntityId, World};
+
+use crate::physics::PhysicsWorld;
+
+use super::{Effect, MessagePayload, Script};
+
+// Nanites in each pile picked up - stack counts aren't read yet, so every pile is the same size
+pub const NANITES_PER_PICKUP: u32 = 10;
+
+pub struct NanitePickupScript {}
+impl NanitePickupScript {
+    pub fn new() -> NanitePickupScript {
+        NanitePickupScript {}
+    }
+}
+
+impl Script for NanitePickupScript {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        _world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::Frob => Effect::Combined {
+                effects: vec![
+                    Effect::AwardNanites {
+                        amount: NANITES_PER_PICKUP,
+                    },
+                    Effect::DestroyEntity { entity_id },
+                ],
+            },
+            _ => Effect::NoEffect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frobbing_nanites_awards_them_and_removes_the_pile() {
+        // Given
+        let mut world = World::new();
+        let nanites = world.add_entity(());
+        let physics = PhysicsWorld::new();
+        let mut script = NanitePickupScript::new();
+
+        // When
+        let effect = script.handle_message(nanites, &world, &physics, &MessagePayload::Frob);
+
+        // Then
+        let effects = match effect {
+            Effect::Combined { effects } => effects,
+            _ => panic!("expected a combined effect"),
+        };
+        assert!(matches!(
+            effects[0],
+            Effect::AwardNanites {
+                amount: NANITES_PER_PICKUP
+            }
+        ));
+        assert!(matches!(effects[1], Effect::DestroyEntity { entity_id } if entity_id == nanites));
+    }
+}
@@ -0,0 +1,36 @@
+use shipyard::{EntityId, World};
+
+use crate::physics::PhysicsWorld;
+
+use super::{Effect, MessagePayload, Script};
+
+// Script for the player - damage from monsters, hazards and falls goes to the player's health
+pub struct InternalPlayerHealth {}
+
+impl InternalPlayerHealth {
+    pub fn new() -> InternalPlayerHealth {
+        InternalPlayerHealth {}
+    }
+}
+
+impl Script for InternalPlayerHealth {
+    fn handle_message(
+        &mut self,
+        _entity_id: EntityId,
+        _world: &World,
+        _physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        match msg {
+            MessagePayload::Damage {
+                amount,
+                damage_type,
+                ..
+            } => Effect::DamagePlayer {
+                amount: *amount,
+                damage_type: *damage_type,
+            },
+            _ => Effect::NoEffect,
+        }
+    }
+}
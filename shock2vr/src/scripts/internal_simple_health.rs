@@ -2,7 +2,7 @@ use shipyard::{EntityId, World};
 
 use crate::physics::PhysicsWorld;
 
-use super::{Effect, MessagePayload, Script};
+use super::{get_damage_modifier, Effect, MessagePayload, Script};
 
 // Script to handle simple health behavior
 pub struct InternalSimpleHealth {}
@@ -17,12 +17,24 @@ impl Script for InternalSimpleHealth {
     fn handle_message(
         &mut self,
         entity_id: EntityId,
-        _world: &World,
+        world: &World,
         _physics: &PhysicsWorld,
         msg: &MessagePayload,
     ) -> Effect {
         match msg {
-            MessagePayload::Damage { .. } => Effect::SlayEntity { entity_id },
+            MessagePayload::Damage {
+                amount,
+                damage_type,
+                ..
+            } => {
+                // Any damage at all is fatal - but damage the entity is immune to doesn't count
+                let modifier = get_damage_modifier(world, entity_id, *damage_type);
+                if (amount * modifier).round() as i32 > 0 {
+                    Effect::SlayEntity { entity_id }
+                } else {
+                    Effect::NoEffect
+                }
+            }
             _ => Effect::NoEffect,
         }
     }
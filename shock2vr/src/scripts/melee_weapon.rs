@@ -1,35 +1,231 @@
+use cgmath::{InnerSpace, Vector3};
+use engine::audio::AudioHandle;
+use shipyard::{EntityId, Get, View, World};
 
-use shipyard::{EntityId, World};
+use crate::{physics::PhysicsWorld, runtime_props::RuntimePropHeldVelocity, time::Time, util};
 
-use crate::physics::PhysicsWorld;
+use super::{
+    damage::get_stimulus_damage_type, script_util::play_environmental_sound, DamageType, Effect,
+    Message, MessagePayload, Script,
+};
 
-use super::{Effect, Message, MessagePayload, Script};
+// Hand speeds, in meters per second - swings slower than the minimum are just taps, and do nothing
+const MIN_SWING_SPEED: f32 = 1.0;
+const FULL_SWING_SPEED: f32 = 5.0;
 
-// Script to handle collision type
-pub struct MeleeWeapon {}
+// Damage done by a full swing - energy blades cut deeper than blunt weapons
+const MAX_DAMAGE: f32 = 12.0;
+const MAX_ENERGY_DAMAGE: f32 = 24.0;
+
+// Fraction of the full damage done by the slowest swing that counts
+const MIN_SWING_STRENGTH: f32 = 0.2;
+
+// Time after landing a hit before the weapon can hit again, so one swing only registers once
+const SWING_COOLDOWN_IN_SECONDS: f32 = 0.5;
+
+// Force applied, along the swing, to whatever a full swing hits
+const MAX_KNOCKBACK_FORCE: f32 = 400.0;
+
+// Impact sounds are never quieter than this, so even a light hit is audible
+const MIN_IMPACT_VOLUME: f32 = 0.3;
+
+// Script for melee weapons, like the wrench and laser rapier. Damage, knockback and the impact
+// sound all scale with how fast the weapon is swung.
+pub struct MeleeWeapon {
+    damage_type: DamageType,
+    max_damage: f32,
+    time_in_seconds: f32,
+    last_hit_in_seconds: Option<f32>,
+}
 
 impl MeleeWeapon {
     pub fn new() -> MeleeWeapon {
-        MeleeWeapon {}
+        MeleeWeapon {
+            damage_type: DamageType::Standard,
+            max_damage: MAX_DAMAGE,
+            time_in_seconds: 0.0,
+            last_hit_in_seconds: None,
+        }
+    }
+
+    fn is_cooling_down(&self) -> bool {
+        self.last_hit_in_seconds.map_or(false, |last_hit| {
+            self.time_in_seconds - last_hit < SWING_COOLDOWN_IN_SECONDS
+        })
     }
 }
 
 impl Script for MeleeWeapon {
-    fn handle_message(
+    fn initialize(&mut self, entity_id: EntityId, world: &World) -> Effect {
+        // The laser rapier shares the wrench script - the stimulus it gives off is what makes it
+        // cut with energy
+        if let Some(damage_type) = get_stimulus_damage_type(world, entity_id) {
+            self.damage_type = damage_type;
+        }
+
+        if self.damage_type == DamageType::Energy {
+            self.max_damage = MAX_ENERGY_DAMAGE;
+        }
+
+        Effect::NoEffect
+    }
+
+    fn update(
         &mut self,
         _entity_id: EntityId,
         _world: &World,
         _physics: &PhysicsWorld,
+        time: &Time,
+    ) -> Effect {
+        self.time_in_seconds = time.total.as_secs_f32();
+        Effect::NoEffect
+    }
+
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        _physics: &PhysicsWorld,
         msg: &MessagePayload,
     ) -> Effect {
         match msg {
-            MessagePayload::Collided { with } => Effect::Send {
-                msg: Message {
-                    to: *with,
-                    payload: MessagePayload::Damage { amount: 1.0 },
-                },
-            },
+            MessagePayload::Collided { with } => {
+                if self.is_cooling_down() {
+                    return Effect::NoEffect;
+                }
+
+                let velocity = {
+                    let v_held_velocity = world.borrow::<View<RuntimePropHeldVelocity>>().unwrap();
+                    v_held_velocity
+                        .get(entity_id)
+                        .map(|held_velocity| held_velocity.0)
+                        .unwrap_or(Vector3::new(0.0, 0.0, 0.0))
+                };
+
+                let strength = match swing_strength(velocity.magnitude()) {
+                    Some(strength) => strength,
+                    None => return Effect::NoEffect,
+                };
+                self.last_hit_in_seconds = Some(self.time_in_seconds);
+
+                let damage = Effect::Send {
+                    msg: Message {
+                        to: *with,
+                        payload: MessagePayload::Damage {
+                            amount: self.max_damage * strength,
+                            damage_type: self.damage_type,
+                            source: Some(entity_id),
+                            hit: None,
+                        },
+                    },
+                };
+
+                // Hitboxes are kinematic, so push the creature they belong to
+                let knockback = Effect::ApplyForce {
+                    entity_id: util::resolve_proxy_entity(world, *with),
+                    force: velocity.normalize() * MAX_KNOCKBACK_FORCE * strength,
+                };
+
+                let audio_handle = AudioHandle::new();
+                let impact_sound = play_environmental_sound(
+                    world,
+                    entity_id,
+                    "collision",
+                    vec![],
+                    audio_handle.clone(),
+                );
+                let impact_volume = Effect::SetSoundVolume {
+                    handle: audio_handle,
+                    volume: strength.max(MIN_IMPACT_VOLUME),
+                };
+
+                Effect::combine(vec![damage, knockback, impact_sound, impact_volume])
+            }
             _ => Effect::NoEffect,
         }
     }
 }
+
+///
+/// swing_strength
+///
+/// How hard a swing at the given speed hits, from the minimum strength up to 1.0 for a full swing.
+/// Returns None for swings too slow to count as a hit.
+fn swing_strength(speed: f32) -> Option<f32> {
+    if speed < MIN_SWING_SPEED {
+        return None;
+    }
+
+    let amount = ((speed - MIN_SWING_SPEED) / (FULL_SWING_SPEED - MIN_SWING_SPEED)).min(1.0);
+    Some(MIN_SWING_STRENGTH + (1.0 - MIN_SWING_STRENGTH) * amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dark::properties::{Link, Links, ToLink};
+
+    use crate::mission::{EntityMetadata, GlobalEntityMetadata};
+
+    use super::*;
+
+    const ENERGY_STIMULUS_ID: i32 = -50;
+
+    #[test]
+    fn test_swing_strength_scales_with_speed() {
+        // Then - taps don't count, and swings past full speed don't hit any harder
+        assert_eq!(swing_strength(0.5), None);
+        assert_eq!(swing_strength(MIN_SWING_SPEED), Some(MIN_SWING_STRENGTH));
+        assert!((swing_strength(3.0).unwrap() - 0.6).abs() < 0.001);
+        assert_eq!(swing_strength(FULL_SWING_SPEED), Some(1.0));
+        assert_eq!(swing_strength(20.0), Some(1.0));
+    }
+
+    #[test]
+    fn test_cooldown_limits_one_hit_per_swing() {
+        // Given - a hit that just landed
+        let mut weapon = MeleeWeapon::new();
+        weapon.time_in_seconds = 10.0;
+        weapon.last_hit_in_seconds = Some(10.0);
+
+        // When
+        let right_after = weapon.is_cooling_down();
+        weapon.time_in_seconds += SWING_COOLDOWN_IN_SECONDS;
+        let after_cooldown = weapon.is_cooling_down();
+
+        // Then
+        assert!(right_after);
+        assert!(!after_cooldown);
+    }
+
+    #[test]
+    fn test_damage_type_comes_from_the_stimulus_given_off() {
+        // Given - a weapon that gives off an energy stimulus
+        let mut world = World::new();
+        world.add_unique(GlobalEntityMetadata(HashMap::from([(
+            "energystim".to_owned(),
+            EntityMetadata {
+                template_id: ENERGY_STIMULUS_ID,
+                obj_icon: None,
+                obj_short_name: None,
+                obj_name: None,
+            },
+        )])));
+        let weapon = world.add_entity((Links {
+            to_links: vec![ToLink {
+                to_template_id: ENERGY_STIMULUS_ID,
+                to_entity_id: None,
+                link: Link::StimulusSource,
+            }],
+        },));
+        let mut melee_weapon = MeleeWeapon::new();
+
+        // When
+        melee_weapon.initialize(weapon, &world);
+
+        // Then
+        assert_eq!(melee_weapon.damage_type, DamageType::Energy);
+        assert_eq!(melee_weapon.max_damage, MAX_ENERGY_DAMAGE);
+    }
+}
@@ -1,8 +1,10 @@
 pub mod ai;
+pub mod damage;
 pub mod effect;
 
 mod base_button;
 mod base_elevator;
+mod base_light;
 mod base_monster;
 mod choose_service;
 mod core_room;
@@ -12,9 +14,13 @@ mod destroy_all_by_name;
 mod energy_station;
 mod frob_qb;
 mod gui;
+mod hazard_room;
 mod internal_collision_type;
 pub mod internal_fast_projectile;
+mod internal_impact_damage;
 mod internal_keycard_script;
+mod internal_nanites;
+mod internal_player_health;
 mod internal_simple_health;
 mod internal_switch_held_model;
 mod level_change_button;
@@ -23,6 +29,7 @@ mod melee_weapon;
 mod obj_consume_button;
 mod once_room;
 mod once_router;
+mod resurrect_machine;
 mod room_trigger;
 pub mod script_util;
 mod std_door;
@@ -56,7 +63,9 @@ mod weapon_script;
 use std::collections::{HashMap, HashSet};
 
 use cgmath::{Point2, Vector3};
+pub use damage::*;
 use dark::motion::MotionFlags;
+use dark::properties::TweqType;
 pub use effect::*;
 
 use shipyard::{EntityId, World};
@@ -72,17 +81,20 @@ use self::gui::{ContainerGui, ElevatorGui, GamePigGui, KeyPadGui, ReplicatorGui}
 use self::internal_switch_held_model::InternalSwitchHeldModelScript;
 use self::trap_signal::TrapSignal;
 use self::{
-    base_button::BaseButton, base_elevator::BaseElevator, base_monster::BaseMonster, core_room::*,
-    create_sound::*, dead_power_cell::DeadPowerCell, destroy_all_by_name::DestroyAllByName,
-    energy_station::EnergyStation, frob_qb::FrobQB, internal_collision_type::InternalCollisionType,
-    internal_keycard_script::KeyCardScript, internal_simple_health::InternalSimpleHealth,
-    level_change_button::LevelChangeButton, logdiscscript::LogDiscScript,
-    melee_weapon::MeleeWeapon, obj_consume_button::ObjConsumeButton, once_room::OnceRoom,
-    once_router::OnceRouter, room_trigger::RoomTrigger, std_door::StdDoor,
-    tool_consumable::ToolConsumable, trap_delay::TrapDelay, trap_destroyer::TrapDestroyer,
-    trap_email::TrapEmail, trap_exp_once::TrapEXPOnce, trap_inverter::TrapInverter,
-    trap_new_tripwire::TrapNewTripwire, trap_on_filter::TrapOffFilter,
-    trap_qb_filter::TrapQBFilter, trap_qb_neg_filter::TrapQBNegFilter, trap_qb_set::TrapQBSet,
+    base_button::BaseButton, base_elevator::BaseElevator, base_light::BaseLight,
+    base_monster::BaseMonster, core_room::*, create_sound::*, dead_power_cell::DeadPowerCell,
+    destroy_all_by_name::DestroyAllByName, energy_station::EnergyStation, frob_qb::FrobQB,
+    hazard_room::HazardRoom, internal_collision_type::InternalCollisionType,
+    internal_impact_damage::InternalImpactDamage, internal_keycard_script::KeyCardScript,
+    internal_nanites::NanitePickupScript, internal_player_health::InternalPlayerHealth,
+    internal_simple_health::InternalSimpleHealth, level_change_button::LevelChangeButton,
+    logdiscscript::LogDiscScript, melee_weapon::MeleeWeapon, obj_consume_button::ObjConsumeButton,
+    once_room::OnceRoom, once_router::OnceRouter, resurrect_machine::ResurrectMachine,
+    room_trigger::RoomTrigger, std_door::StdDoor, tool_consumable::ToolConsumable,
+    trap_delay::TrapDelay, trap_destroyer::TrapDestroyer, trap_email::TrapEmail,
+    trap_exp_once::TrapEXPOnce, trap_inverter::TrapInverter, trap_new_tripwire::TrapNewTripwire,
+    trap_on_filter::TrapOffFilter, trap_qb_filter::TrapQBFilter,
+    trap_qb_neg_filter::TrapQBNegFilter, trap_qb_set::TrapQBSet,
     trap_questbit_simple::TrapQuestbitSimple, trap_router::TrapRouter, trap_slayer::TrapSlayer,
     trap_sound::TrapSound, trap_teleport::TrapTeleport, trap_teleport_player::TrapTeleportPlayer,
     trap_trip_level::TrapTripLevel, trap_tweq::TrapTweq, trigger_collide::TriggerCollide,
@@ -111,6 +123,12 @@ pub enum MessagePayload {
     },
     AnimationCompleted,
 
+    // Tweq event - a tweq hit its limit, or finished its last frame
+    TweqComplete {
+        tweq_type: TweqType,
+        is_reverse: bool,
+    },
+
     // Gameplay events
     Recharge,
     ProvideForConsumption {
@@ -118,6 +136,9 @@ pub enum MessagePayload {
     }, // propose to consume this entity
     Damage {
         amount: f32,
+        damage_type: DamageType,
+        source: Option<EntityId>,
+        hit: Option<HitLocation>,
     }, // damage the entity
 
     // AI Signal
@@ -189,6 +210,31 @@ pub trait Script {
     ) -> Effect {
         Effect::NoEffect
     }
+
+    fn coverage(&self) -> ScriptCoverage {
+        ScriptCoverage::Implemented
+    }
+}
+
+///
+/// ScriptCoverage
+///
+/// How far along a script is in being ported - ordered from best to worst
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ScriptCoverage {
+    Implemented,
+    // Mapped to a placeholder that ignores messages (NoopScript or UnimplementedScript)
+    Noop,
+    // Not mapped at all, so it falls back to PanicOnLoadScript
+    Unknown,
+}
+
+///
+/// script_coverage
+///
+/// Classify a script name by how it would be handled, without creating an entity for it
+pub fn script_coverage(script_name: &str) -> ScriptCoverage {
+    ScriptWorld::create_script(script_name.to_ascii_lowercase()).coverage()
 }
 
 struct UnimplementedScript {
@@ -214,6 +260,10 @@ impl Script for UnimplementedScript {
             }
         }
     }
+
+    fn coverage(&self) -> ScriptCoverage {
+        ScriptCoverage::Noop
+    }
 }
 
 pub struct CompositeScript {
@@ -268,6 +318,15 @@ impl Script for CompositeScript {
 
         Effect::combine(effects)
     }
+
+    // Only as far along as the least-ported script
+    fn coverage(&self) -> ScriptCoverage {
+        self.scripts
+            .iter()
+            .map(|sc| sc.coverage())
+            .max()
+            .unwrap_or(ScriptCoverage::Implemented)
+    }
 }
 
 impl UnimplementedScript {
@@ -315,6 +374,10 @@ impl Script for PanicOnLoadScript {
     ) -> Effect {
         panic!("Unimplemented script: {}", self.name);
     }
+
+    fn coverage(&self) -> ScriptCoverage {
+        ScriptCoverage::Unknown
+    }
 }
 struct NoopScript {}
 impl NoopScript {
@@ -333,6 +396,10 @@ impl Script for NoopScript {
         warn!("message {:?} sent to NoopScript, unhandled", _msg);
         Effect::NoEffect
     }
+
+    fn coverage(&self) -> ScriptCoverage {
+        ScriptCoverage::Noop
+    }
 }
 
 pub struct ScriptWorld {
@@ -388,7 +455,7 @@ impl ScriptWorld {
             "changeinterface" => Box::new(NoopScript::new()),
             "reducehp" => Box::new(NoopScript::new()),
             "engineremoverad" => Box::new(NoopScript::new()),
-            "radroom" => Box::new(NoopScript::new()),
+            "radroom" => Box::new(HazardRoom::radiation()),
             "trapspawn" => Box::new(NoopScript::new()),
             // ops1 cutscene
             "transluceinoutholo" => Box::new(NoopScript::new()),
@@ -399,7 +466,8 @@ impl ScriptWorld {
             "cs9_shodanscreen" => Box::new(NoopScript::new()),
             "sitdownrightnowmp" => Box::new(NoopScript::new()),
             "trapdestroyteleport" => Box::new(NoopScript::new()),
-            "triggerdamage" => Box::new(NoopScript::new()),
+            // Damage volumes, like toxic areas
+            "triggerdamage" => Box::new(HazardRoom::toxic()),
             // many.micontain
             "brain" => Box::new(NoopScript::new()),
             "wormheartimplant" => Box::new(NoopScript::new()),
@@ -425,9 +493,12 @@ impl ScriptWorld {
 
             // Internal
             "internal_collision_type" => Box::new(InternalCollisionType::new()),
+            "internal_impact_damage" => Box::new(InternalImpactDamage::new()),
             "internal_inventory" => gui_script(Box::new(ContainerGui::inv_container())),
             // "internal_inventory" => Box::new(PanicOnLoadScript::new("internal_inventory")),
             "internal_keycard" => Box::new(KeyCardScript::new()),
+            "internal_nanites" => Box::new(NanitePickupScript::new()),
+            "internal_player_health" => Box::new(InternalPlayerHealth::new()),
             "internal_room_trigger" => Box::new(RoomTrigger::new()),
             "internal_simple_health" => Box::new(InternalSimpleHealth::new()),
             // Implemented
@@ -510,7 +581,7 @@ impl ScriptWorld {
             "keypadunhackable" => gui_script(Box::new(KeyPadGui)),
             "keypad" => gui_script(Box::new(KeyPadGui)),
             "securitycomputer" => Box::new(UnimplementedScript::new(&script_name)),
-            "resurrectmachine" => Box::new(BaseButton {}),
+            "resurrectmachine" => Box::new(ResurrectMachine::new()),
             "twostatebutton" => Box::new(BaseButton::new()),
 
             // weapons:
@@ -637,7 +708,7 @@ impl ScriptWorld {
             // TODO: Handle keypad code
             "ammoscript" => Box::new(NoopScript::new()),
             //"BaseElevator" => Box::new(UnimplementedScript::new(&name)),
-            "baselight" => Box::new(NoopScript {}),
+            "baselight" => Box::new(BaseLight::new()),
             //"baseai" => Box::new(PanicOnLoadScript::new(&script_name)),
             "baseai" => Box::new(NoopScript::new()),
             "basemonster" => Box::new(BaseMonster::new()),
@@ -0,0 +1,44 @@
+use shipyard::{EntityId, UniqueView, World};
+
+use crate::{mission::PlayerInfo, physics::PhysicsWorld};
+
+use super::{base_button::BaseButton, Effect, MessagePayload, Script};
+
+// Script for the Quantum Bio-Reconstruction machines. Activating one means a player who dies on
+// this deck is rebuilt in front of it, for a nanite fee, instead of the game ending.
+pub struct ResurrectMachine {
+    button: BaseButton,
+}
+
+impl ResurrectMachine {
+    pub fn new() -> ResurrectMachine {
+        ResurrectMachine {
+            button: BaseButton::new(),
+        }
+    }
+}
+
+impl Script for ResurrectMachine {
+    fn handle_message(
+        &mut self,
+        entity_id: EntityId,
+        world: &World,
+        physics: &PhysicsWorld,
+        msg: &MessagePayload,
+    ) -> Effect {
+        let button_effect = self.button.handle_message(entity_id, world, physics, msg);
+
+        match msg {
+            MessagePayload::Frob if !self.button.is_locked(entity_id, world) => {
+                // The player is standing at the machine to use it - that's where they come back
+                let player_info = world.borrow::<UniqueView<PlayerInfo>>().unwrap();
+                let activate_effect = Effect::ActivateResurrectionStation {
+                    position: player_info.pos,
+                    rotation: player_info.rotation,
+                };
+                Effect::combine(vec![button_effect, activate_effect])
+            }
+            _ => button_effect,
+        }
+    }
+}
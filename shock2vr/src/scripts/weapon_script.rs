@@ -1,10 +1,10 @@
 use cgmath::{point3, Deg, Matrix4, Quaternion, Rotation, Rotation3, Transform};
 use dark::properties::{GunFlashOptions, Link, ProjectileOptions};
 use engine::audio::AudioHandle;
-use shipyard::{EntityId, Get, View, World};
+use shipyard::{EntityId, Get, UniqueView, View, World};
 
 use crate::{
-    mission::entity_creator::CreateEntityOptions,
+    mission::{entity_creator::CreateEntityOptions, PlayerInfo},
     physics::PhysicsWorld,
     runtime_props::{RuntimePropTransform, RuntimePropVhots},
     vr_config,
@@ -140,6 +140,7 @@ fn create_projectile(
         .map(|v| v.point)
         .unwrap_or(point3(0.0, 0.0, 0.0));
 
+    // When the weapon is held in both hands, its transform already follows the two-handed aim
     let transform = v_transform.get(entity_id).unwrap();
 
     let adjustments = vr_config::get_vr_hand_model_adjustments_from_entity(
@@ -159,6 +160,9 @@ fn create_projectile(
     // Adjust the vhot position to be in the same coordinate space as the weapon
     let position = inv_rot_matrix.transform_point(vhot);
 
+    // Weapons are only fired from the player's hands
+    let player_entity_id = world.borrow::<UniqueView<PlayerInfo>>().unwrap().entity_id;
+
     Effect::CreateEntity {
         template_id: projectile_template_id,
         position,
@@ -168,6 +172,7 @@ fn create_projectile(
         root_transform: transform.0 * rot_matrix * projectile_rotation,
         options: CreateEntityOptions {
             force_visible: true,
+            launcher: Some(player_entity_id),
         },
     }
 }
@@ -0,0 +1,102 @@
+use dark::properties::{AnimLightMode, PropAnimLight};
+use rand::{thread_rng, Rng};
+use shipyard::{IntoIter, UniqueView, ViewMut};
+
+use crate::time::Time;
+
+///
+/// run_anim_light
+///
+/// Animates the brightness of animated lights, based on their mode
+///
+pub fn run_anim_light(u_time: UniqueView<Time>, mut v_anim_light: ViewMut<PropAnimLight>) {
+    let elapsed_in_milliseconds = u_time.elapsed.as_millis() as i32;
+
+    for light in (&mut v_anim_light).iter() {
+        if light.is_inactive {
+            continue;
+        }
+
+        update_brightness(light, elapsed_in_milliseconds);
+    }
+}
+
+fn update_brightness(light: &mut PropAnimLight, elapsed_in_milliseconds: i32) {
+    let min = light.min_brightness;
+    let max = light.max_brightness;
+
+    match light.mode {
+        AnimLightMode::Minimum => light.brightness = min,
+        AnimLightMode::Maximum => light.brightness = max,
+        AnimLightMode::Zero => light.brightness = 0.0,
+
+        // Modes that jump between brightness levels
+        AnimLightMode::Flip | AnimLightMode::Random | AnimLightMode::Flicker => {
+            light.countdown_in_milliseconds -= elapsed_in_milliseconds;
+            if light.countdown_in_milliseconds > 0 {
+                return;
+            }
+
+            light.is_rising = !light.is_rising;
+            let phase_time = if light.is_rising {
+                light.time_rise_in_milliseconds
+            } else {
+                light.time_fall_in_milliseconds
+            }
+            .max(1);
+
+            let mut rng = thread_rng();
+            match light.mode {
+                AnimLightMode::Random => {
+                    light.brightness = min + rng.gen::<f32>() * (max - min);
+                    light.countdown_in_milliseconds = phase_time;
+                }
+                AnimLightMode::Flicker => {
+                    light.brightness = if light.is_rising { max } else { min };
+                    light.countdown_in_milliseconds = rng.gen_range(1..=phase_time);
+                }
+                _ => {
+                    light.brightness = if light.is_rising { max } else { min };
+                    light.countdown_in_milliseconds = phase_time;
+                }
+            }
+        }
+
+        // Modes that slide between brightness levels
+        AnimLightMode::Smooth
+        | AnimLightMode::SmoothBrighten
+        | AnimLightMode::SmoothDim
+        | AnimLightMode::RandomCoherent => {
+            match light.mode {
+                AnimLightMode::SmoothBrighten => light.is_rising = true,
+                AnimLightMode::SmoothDim => light.is_rising = false,
+                _ => (),
+            }
+
+            let phase_time = if light.is_rising {
+                light.time_rise_in_milliseconds
+            } else {
+                light.time_fall_in_milliseconds
+            }
+            .max(1);
+
+            let step = (max - min) * elapsed_in_milliseconds as f32 / phase_time as f32;
+
+            let has_reached_limit = if light.is_rising {
+                light.brightness = (light.brightness + step).min(max);
+                light.brightness >= max
+            } else {
+                light.brightness = (light.brightness - step).max(min);
+                light.brightness <= min
+            };
+
+            if has_reached_limit {
+                match light.mode {
+                    AnimLightMode::Smooth => light.is_rising = !light.is_rising,
+                    AnimLightMode::RandomCoherent => light.is_rising = thread_rng().gen(),
+                    _ => (),
+                }
+            }
+        }
+    }
+}
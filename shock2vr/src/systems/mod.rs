@@ -1,7 +1,9 @@
+mod anim_light;
 mod bitmap_animation;
 mod tweq;
 mod update_teleported_state;
 
+pub use anim_light::*;
 pub use bitmap_animation::*;
 pub use tweq::*;
 pub use update_teleported_state::*;
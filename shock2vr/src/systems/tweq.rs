@@ -1,38 +1,39 @@
 use std::time::Duration;
 
-use cgmath::{Deg, Quaternion, Rotation3};
+use cgmath::{vec3, Deg, Matrix4, Quaternion, Rotation3};
 use dark::properties::{
-    PropPosition, PropTweqDeleteConfig, PropTweqDeleteState, PropTweqEmitterConfig,
-    PropTweqEmitterState, PropTweqRotateState, TweqAnimationState, TweqHalt,
+    PropPosition, PropScale, PropTweqBlinkConfig, PropTweqBlinkState, PropTweqDeleteConfig,
+    PropTweqDeleteState, PropTweqEmitterConfig, PropTweqEmitterState, PropTweqJointsConfig,
+    PropTweqJointsState, PropTweqLockConfig, PropTweqLockState, PropTweqRotateConfig,
+    PropTweqRotateState, PropTweqScaleConfig, PropTweqScaleState, TweqAnimationConfig,
+    TweqAnimationState, TweqHalt, TweqLimits, TweqType,
+};
+use shipyard::{
+    EntityId, Get, IntoIter, IntoWithId, UniqueView, UniqueViewMut, View, ViewMut, World,
 };
-use shipyard::{EntityId, Get, IntoIter, IntoWithId, UniqueView, UniqueViewMut, View, ViewMut};
 
-use crate::{mission::EffectQueue, scripts::Effect, time::Time, util::vec3_to_point3};
+use crate::{
+    mission::EffectQueue,
+    runtime_props::RuntimePropTransform,
+    scripts::{Effect, Message, MessagePayload},
+    time::Time,
+    util::vec3_to_point3,
+};
 
 ///
 /// run_tweq
 ///
-/// Runs all tweq components
+/// Runs the emitter and delete tweqs
 ///
 pub fn run_tweq(
     u_time: UniqueView<Time>,
     v_prop_position: View<PropPosition>,
-    v_tweq_rotate_state: View<PropTweqRotateState>,
     mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
     mut v_tweq_emit_config: ViewMut<PropTweqEmitterConfig>,
     mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
     mut v_tweq_delete_config: ViewMut<PropTweqDeleteConfig>,
     mut effects: UniqueViewMut<EffectQueue>,
 ) {
-    for (id, tweq) in v_tweq_rotate_state.iter().with_id() {
-        if tweq.animation_state.contains(TweqAnimationState::ON) {
-            effects.push(Effect::SetRotation {
-                entity_id: id,
-                rotation: Quaternion::from_angle_y(Deg(u_time.total.as_secs_f32() * 20.0)),
-            });
-        }
-    }
-
     // Run emit tweq
     for (_id, (tweq_state, tweq_config, position)) in (
         &mut v_tweq_emit_state,
@@ -58,7 +59,8 @@ pub fn run_tweq(
                 });
             }
 
-            // Did we finish emitting frames?
+            // Did we finish emitting frames? Emitters just switch off - their halt action isn't
+            // applied, so an emitter that's done stays around to be turned on again
             if tweq_state.num_iterations >= tweq_config.max_frames {
                 tweq_state.animation_state = tweq_state
                     .animation_state
@@ -86,32 +88,699 @@ pub fn run_tweq(
     }
 }
 
-pub fn turn_on_tweqs(
-    entity_id: EntityId,
-    mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
-    mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
+///
+/// run_rotate_tweq
+///
+/// Runs the rotate tweqs, rotating the object around its original orientation
+///
+pub fn run_rotate_tweq(
+    u_time: UniqueView<Time>,
+    v_prop_position: View<PropPosition>,
+    mut v_tweq_rotate_state: ViewMut<PropTweqRotateState>,
+    v_tweq_rotate_config: View<PropTweqRotateConfig>,
+    mut effects: UniqueViewMut<EffectQueue>,
+) {
+    let elapsed = u_time.elapsed.as_secs_f32();
+
+    for (id, tweq_state) in (&mut v_tweq_rotate_state).iter().with_id() {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON) {
+            continue;
+        }
+
+        let tweq_config = match v_tweq_rotate_config.get(id) {
+            Ok(config) => &config.0,
+            Err(_) => {
+                // No config to drive the tweq - just spin around the vertical axis
+                effects.push(Effect::SetRotation {
+                    entity_id: id,
+                    rotation: Quaternion::from_angle_y(Deg(u_time.total.as_secs_f32() * 20.0)),
+                });
+                continue;
+            }
+        };
+
+        let base_rotation = *tweq_state.base_rotation.get_or_insert_with(|| {
+            v_prop_position
+                .get(id)
+                .map(|p| p.rotation)
+                .unwrap_or(Quaternion::new(1.0, 0.0, 0.0, 0.0))
+        });
+
+        let mut angles: [f32; 3] = tweq_state.angles.into();
+        let mut axis_states = [
+            tweq_state.axis1_animation_state,
+            tweq_state.axis2_animation_state,
+            tweq_state.axis3_animation_state,
+        ];
+
+        let maybe_completed = step_tweq_values(
+            &mut angles,
+            &mut axis_states,
+            &tweq_config.limits,
+            &[tweq_config.animation_config; 3],
+            tweq_config.primary_axis,
+            elapsed,
+        );
+
+        [
+            tweq_state.axis1_animation_state,
+            tweq_state.axis2_animation_state,
+            tweq_state.axis3_animation_state,
+        ] = axis_states;
+        tweq_state.angles = angles.into();
+
+        // Angles are in the original axis order - z is the vertical axis
+        let offset = Quaternion::from_angle_y(Deg(angles[2]))
+            * Quaternion::from_angle_z(Deg(angles[1]))
+            * Quaternion::from_angle_x(Deg(-angles[0]));
+
+        effects.push(Effect::SetRotation {
+            entity_id: id,
+            rotation: base_rotation * offset,
+        });
+
+        if let Some(is_reverse) = maybe_completed {
+            complete_tweq(
+                id,
+                TweqType::Rotate,
+                tweq_config.halt,
+                is_reverse,
+                &mut tweq_state.animation_state,
+                &mut effects,
+            );
+        }
+    }
+}
+
+///
+/// run_scale_tweq
+///
+/// Runs the scale tweqs, updating the scale and transform of the object
+///
+pub fn run_scale_tweq(
+    u_time: UniqueView<Time>,
+    v_prop_position: View<PropPosition>,
+    mut v_tweq_scale_state: ViewMut<PropTweqScaleState>,
+    v_tweq_scale_config: View<PropTweqScaleConfig>,
+    mut v_prop_scale: ViewMut<PropScale>,
+    mut v_transform: ViewMut<RuntimePropTransform>,
+    mut effects: UniqueViewMut<EffectQueue>,
+) {
+    let elapsed = u_time.elapsed.as_secs_f32();
+
+    for (id, (tweq_state, tweq_config)) in (&mut v_tweq_scale_state, &v_tweq_scale_config)
+        .iter()
+        .with_id()
+    {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON) {
+            continue;
+        }
+
+        let tweq_config = &tweq_config.0;
+
+        // PropScale is in engine axis order - convert back to the original order the limits use
+        let initial_scale = v_prop_scale
+            .get(id)
+            .map(|p| vec3(p.0.x.abs(), p.0.z.abs(), p.0.y.abs()))
+            .unwrap_or(vec3(1.0, 1.0, 1.0));
+        let mut scale: [f32; 3] = tweq_state.scale.unwrap_or(initial_scale).into();
+
+        let maybe_completed = step_tweq_values(
+            &mut scale,
+            &mut tweq_state.axis_animation_states,
+            &tweq_config.limits,
+            &[tweq_config.animation_config; 3],
+            tweq_config.primary_axis,
+            elapsed,
+        );
+        tweq_state.scale = Some(scale.into());
+
+        // Same axis convention as reading P$Scale
+        let engine_scale = vec3(-scale[0], scale[2], scale[1]);
+        if let Ok(prop_scale) = (&mut v_prop_scale).get(id) {
+            prop_scale.0 = engine_scale;
+        }
+
+        if let (Ok(position), Ok(transform)) = (v_prop_position.get(id), (&mut v_transform).get(id))
+        {
+            transform.0 = Matrix4::from_translation(position.position)
+                * Matrix4::from(position.rotation)
+                * Matrix4::from_nonuniform_scale(engine_scale.x, engine_scale.y, engine_scale.z);
+        }
+
+        if let Some(is_reverse) = maybe_completed {
+            complete_tweq(
+                id,
+                TweqType::Scale,
+                tweq_config.halt,
+                is_reverse,
+                &mut tweq_state.animation_state,
+                &mut effects,
+            );
+        }
+    }
+}
+
+///
+/// run_joint_tweq
+///
+/// Runs the joints and lock tweqs, which animate the joints of the object's model
+///
+pub fn run_joint_tweq(
+    u_time: UniqueView<Time>,
+    mut v_tweq_joints_state: ViewMut<PropTweqJointsState>,
+    v_tweq_joints_config: View<PropTweqJointsConfig>,
+    mut v_tweq_lock_state: ViewMut<PropTweqLockState>,
+    v_tweq_lock_config: View<PropTweqLockConfig>,
+    mut effects: UniqueViewMut<EffectQueue>,
+) {
+    let elapsed = u_time.elapsed.as_secs_f32();
+
+    for (id, (tweq_state, tweq_config)) in (&mut v_tweq_joints_state, &v_tweq_joints_config)
+        .iter()
+        .with_id()
+    {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON) {
+            continue;
+        }
+
+        let limits: Vec<TweqLimits> = tweq_config.joints.iter().map(|j| j.limits).collect();
+        let animation_configs: Vec<TweqAnimationConfig> = tweq_config
+            .joints
+            .iter()
+            .map(|j| j.animation_config | tweq_config.animation_config)
+            .collect();
+
+        let maybe_completed = step_tweq_values(
+            &mut tweq_state.values,
+            &mut tweq_state.joint_animation_states,
+            &limits,
+            &animation_configs,
+            tweq_config.primary_joint,
+            elapsed,
+        );
+
+        for (idx, (value, joint_limits)) in tweq_state.values.iter().zip(limits.iter()).enumerate()
+        {
+            if joint_limits.rate == 0.0 {
+                continue;
+            }
+
+            effects.push(joint_effect(id, idx as u32 + 1, *value));
+        }
+
+        if let Some(is_reverse) = maybe_completed {
+            complete_tweq(
+                id,
+                TweqType::Joints,
+                tweq_config.halt,
+                is_reverse,
+                &mut tweq_state.animation_state,
+                &mut effects,
+            );
+        }
+    }
+
+    for (id, (tweq_state, tweq_config)) in (&mut v_tweq_lock_state, &v_tweq_lock_config)
+        .iter()
+        .with_id()
+    {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON)
+            || tweq_config.limits.rate == 0.0
+        {
+            continue;
+        }
+
+        let is_reverse = tweq_state
+            .animation_state
+            .contains(TweqAnimationState::REVERSE);
+
+        let is_completed = step_tweq_value(
+            &mut tweq_state.value,
+            &tweq_config.limits,
+            tweq_config.animation_config,
+            &mut tweq_state.animation_state,
+            elapsed,
+        );
+
+        effects.push(joint_effect(
+            id,
+            tweq_config.lock_joint.max(1) as u32,
+            tweq_state.value,
+        ));
+
+        if is_completed {
+            complete_tweq(
+                id,
+                TweqType::Lock,
+                tweq_config.halt,
+                is_reverse,
+                &mut tweq_state.animation_state,
+                &mut effects,
+            );
+        }
+    }
+}
+
+///
+/// run_blink_tweq
+///
+/// Runs the blink (flicker) tweqs, toggling the object between lit and unlit
+///
+pub fn run_blink_tweq(
+    u_time: UniqueView<Time>,
+    mut v_tweq_blink_state: ViewMut<PropTweqBlinkState>,
+    v_tweq_blink_config: View<PropTweqBlinkConfig>,
 ) {
-    if let Ok(tweq_state) = (&mut v_tweq_emit_state).get(entity_id) {
-        tweq_state.animation_state.insert(TweqAnimationState::ON);
-        tweq_state.time_since_last_event = Duration::from_secs(0);
+    for (tweq_state, tweq_config) in (&mut v_tweq_blink_state, &v_tweq_blink_config).iter() {
+        if !tweq_state.animation_state.contains(TweqAnimationState::ON)
+            || tweq_config.rate.is_zero()
+        {
+            continue;
+        }
+
+        tweq_state.time_since_last_event += u_time.elapsed;
+
+        if tweq_state.time_since_last_event > tweq_config.rate {
+            tweq_state.time_since_last_event = Duration::from_secs(0);
+            tweq_state.is_lit = !tweq_state.is_lit;
+        }
+    }
+}
+
+pub fn turn_on_tweqs(world: &World, entity_id: EntityId) {
+    set_tweqs_active(world, entity_id, true);
+}
+
+pub fn turn_off_tweqs(world: &World, entity_id: EntityId) {
+    set_tweqs_active(world, entity_id, false);
+}
+
+fn set_tweqs_active(world: &World, entity_id: EntityId, is_active: bool) {
+    world.run(
+        |mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
+         mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
+         mut v_tweq_rotate_state: ViewMut<PropTweqRotateState>,
+         mut v_tweq_scale_state: ViewMut<PropTweqScaleState>,
+         mut v_tweq_joints_state: ViewMut<PropTweqJointsState>,
+         mut v_tweq_lock_state: ViewMut<PropTweqLockState>,
+         mut v_tweq_blink_state: ViewMut<PropTweqBlinkState>| {
+            if let Ok(tweq_state) = (&mut v_tweq_emit_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+                tweq_state.time_since_last_event = Duration::from_secs(0);
+            }
+
+            if let Ok(tweq_state) = (&mut v_tweq_delete_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+                tweq_state.time_since_last_event = Duration::from_secs(0);
+            }
+
+            if let Ok(tweq_state) = (&mut v_tweq_rotate_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+            }
+
+            if let Ok(tweq_state) = (&mut v_tweq_scale_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+            }
+
+            if let Ok(tweq_state) = (&mut v_tweq_joints_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+            }
+
+            if let Ok(tweq_state) = (&mut v_tweq_lock_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+            }
+
+            if let Ok(tweq_state) = (&mut v_tweq_blink_state).get(entity_id) {
+                tweq_state
+                    .animation_state
+                    .set(TweqAnimationState::ON, is_active);
+                tweq_state.time_since_last_event = Duration::from_secs(0);
+            }
+        },
+    );
+}
+
+fn joint_effect(entity_id: EntityId, joint_id: u32, angle: f32) -> Effect {
+    Effect::SetJointTransform {
+        entity_id,
+        joint_id,
+        transform: Quaternion::from_angle_x(Deg(angle)).into(),
+    }
+}
+
+///
+/// step_tweq_values
+///
+/// Advances each value of a multi-axis (or multi-joint) tweq. Returns Some(is_reverse) if the
+/// primary value (or any value, if there is no primary) completed a leg of its animation.
+///
+fn step_tweq_values(
+    values: &mut [f32],
+    animation_states: &mut [TweqAnimationState],
+    limits: &[TweqLimits],
+    animation_configs: &[TweqAnimationConfig],
+    primary: u8,
+    elapsed: f32,
+) -> Option<bool> {
+    let mut maybe_completed = None;
+
+    for (idx, (((value, animation_state), limits), animation_config)) in values
+        .iter_mut()
+        .zip(animation_states.iter_mut())
+        .zip(limits.iter())
+        .zip(animation_configs.iter())
+        .enumerate()
+    {
+        if limits.rate == 0.0 {
+            continue;
+        }
+
+        let is_reverse = animation_state.contains(TweqAnimationState::REVERSE);
+        let is_primary = primary == 0 || primary as usize == idx + 1;
+
+        if step_tweq_value(value, limits, *animation_config, animation_state, elapsed) && is_primary
+        {
+            maybe_completed = Some(is_reverse);
+        }
+    }
+
+    maybe_completed
+}
+
+///
+/// step_tweq_value
+///
+/// Advances a single value by its rate, handling the limit, wrap and bounce flags.
+/// Returns true if the value completed a leg of its animation by hitting a limit.
+///
+fn step_tweq_value(
+    value: &mut f32,
+    limits: &TweqLimits,
+    animation_config: TweqAnimationConfig,
+    animation_state: &mut TweqAnimationState,
+    elapsed: f32,
+) -> bool {
+    let has_limits =
+        limits.high > limits.low && !animation_config.contains(TweqAnimationConfig::NOLIMT);
+
+    // Start from inside the limits, so the first step doesn't count as completing
+    if has_limits {
+        *value = value.clamp(limits.low, limits.high);
+    }
+
+    let direction = if animation_state.contains(TweqAnimationState::REVERSE) {
+        -1.0
+    } else {
+        1.0
+    };
+    *value += limits.rate * direction * elapsed;
+
+    if !has_limits || (*value >= limits.low && *value <= limits.high) {
+        return false;
+    }
+
+    if animation_config.contains(TweqAnimationConfig::WRAP) {
+        let range = limits.high - limits.low;
+        *value = limits.low + (*value - limits.low).rem_euclid(range);
+        animation_state.toggle(TweqAnimationState::LAPONE);
+        return false;
     }
 
-    if let Ok(tweq_state) = (&mut v_tweq_delete_state).get(entity_id) {
-        tweq_state.animation_state.insert(TweqAnimationState::ON);
-        tweq_state.time_since_last_event = Duration::from_secs(0);
+    *value = value.clamp(limits.low, limits.high);
+
+    // One bounce - turn around at the far limit, and only complete once back at the start
+    if animation_config.contains(TweqAnimationConfig::ONEBOUNCE)
+        && !animation_state.contains(TweqAnimationState::REVERSE)
+    {
+        animation_state.insert(TweqAnimationState::REVERSE);
+        return false;
     }
+
+    // Head back the other way - whether the tweq keeps going (ping-pong) is up to the halt action
+    animation_state.toggle(TweqAnimationState::REVERSE);
+    true
 }
 
-pub fn turn_off_tweqs(
+///
+/// complete_tweq
+///
+/// Applies the halt action of a tweq that finished, and lets the scripts on the entity know
+///
+fn complete_tweq(
     entity_id: EntityId,
-    mut v_tweq_emit_state: ViewMut<PropTweqEmitterState>,
-    mut v_tweq_delete_state: ViewMut<PropTweqDeleteState>,
+    tweq_type: TweqType,
+    halt: TweqHalt,
+    is_reverse: bool,
+    animation_state: &mut TweqAnimationState,
+    effects: &mut EffectQueue,
 ) {
-    if let Ok(tweq_state) = (&mut v_tweq_emit_state).get(entity_id) {
-        tweq_state.animation_state.remove(TweqAnimationState::ON);
+    match halt {
+        TweqHalt::CONTINUE => (),
+        // We don't remove properties at runtime, so stopping is the closest match
+        TweqHalt::STOP_TWEQ | TweqHalt::REMOVE_PROP => {
+            animation_state.remove(TweqAnimationState::ON)
+        }
+        TweqHalt::DESTROY_OBJECT => effects.push(Effect::DestroyEntity { entity_id }),
+        TweqHalt::SLAY_OBJ => effects.push(Effect::SlayEntity { entity_id }),
+    }
+
+    effects.push(Effect::Send {
+        msg: Message {
+            payload: MessagePayload::TweqComplete {
+                tweq_type,
+                is_reverse,
+            },
+            to: entity_id,
+        },
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(rate: f32, low: f32, high: f32) -> TweqLimits {
+        TweqLimits { rate, low, high }
+    }
+
+    #[test]
+    fn test_finished_emitter_turns_off_without_halting() {
+        // Given - an emitter with two frames left, and a halt action that would destroy it
+        let world = World::new();
+        world.add_unique(Time {
+            elapsed: Duration::from_secs(1),
+            total: Duration::from_secs(1),
+        });
+        world.add_unique(EffectQueue::new());
+        let entity_id = world.add_entity((
+            PropPosition {
+                position: vec3(0.0, 0.0, 0.0),
+                cell: 0,
+                rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            },
+            PropTweqEmitterState {
+                animation_state: TweqAnimationState::ON,
+                time_since_last_event: Duration::from_secs(0),
+                num_iterations: 0,
+            },
+            PropTweqEmitterConfig {
+                animation_config: TweqAnimationConfig::empty(),
+                halt: TweqHalt::DESTROY_OBJECT,
+                rate: Duration::from_millis(500),
+                max_frames: 2,
+                emit_what: "".to_string(),
+                velocity: vec3(0.0, 0.0, 0.0),
+                angle_random: vec3(0.0, 0.0, 0.0),
+            },
+        ));
+
+        // When
+        for _ in 0..3 {
+            world.run(run_tweq);
+        }
+
+        // Then - both frames were emitted, and the emitter is just off
+        let effects = world
+            .borrow::<UniqueViewMut<EffectQueue>>()
+            .unwrap()
+            .flush();
+        assert_eq!(effects.len(), 2);
+        assert!(effects
+            .iter()
+            .all(|effect| matches!(effect, Effect::CreateEntityByTemplateName { .. })));
+        let is_on = world.run(|v_tweq_emit_state: View<PropTweqEmitterState>| {
+            v_tweq_emit_state
+                .get(entity_id)
+                .unwrap()
+                .animation_state
+                .contains(TweqAnimationState::ON)
+        });
+        assert!(!is_on);
+    }
+
+    #[test]
+    fn test_step_tweq_value_bounces_wraps_and_completes_at_limit() {
+        // Given
+        let limits = limits(10.0, 0.0, 20.0);
+
+        // When - a plain tweq runs past the high limit
+        let mut plain_value = 15.0;
+        let mut plain_state = TweqAnimationState::ON;
+        let plain_completed = step_tweq_value(
+            &mut plain_value,
+            &limits,
+            TweqAnimationConfig::empty(),
+            &mut plain_state,
+            1.0,
+        );
+
+        // ...a one bounce tweq runs past the high limit
+        let mut bounce_value = 15.0;
+        let mut bounce_state = TweqAnimationState::ON;
+        let bounce_completed = step_tweq_value(
+            &mut bounce_value,
+            &limits,
+            TweqAnimationConfig::ONEBOUNCE,
+            &mut bounce_state,
+            1.0,
+        );
+
+        // ...and a wrapping tweq runs past the high limit
+        let mut wrap_value = 15.0;
+        let mut wrap_state = TweqAnimationState::ON;
+        let wrap_completed = step_tweq_value(
+            &mut wrap_value,
+            &limits,
+            TweqAnimationConfig::WRAP,
+            &mut wrap_state,
+            1.0,
+        );
+
+        // Then - the plain tweq stops at the limit and completes, turned around
+        assert!(plain_completed);
+        assert_eq!(plain_value, 20.0);
+        assert!(plain_state.contains(TweqAnimationState::REVERSE));
+
+        // ...the one bounce tweq turns around without completing
+        assert!(!bounce_completed);
+        assert_eq!(bounce_value, 20.0);
+        assert!(bounce_state.contains(TweqAnimationState::REVERSE));
+
+        // ...and the wrapping tweq comes back around from the low limit
+        assert!(!wrap_completed);
+        assert_eq!(wrap_value, 5.0);
+        assert!(!wrap_state.contains(TweqAnimationState::REVERSE));
     }
 
-    if let Ok(tweq_state) = (&mut v_tweq_delete_state).get(entity_id) {
-        tweq_state.animation_state.remove(TweqAnimationState::ON);
+    #[test]
+    fn test_one_bounce_completes_back_at_the_start() {
+        // Given - a one bounce tweq heading back from the far limit
+        let limits = limits(10.0, 0.0, 20.0);
+        let mut value = 5.0;
+        let mut state = TweqAnimationState::ON | TweqAnimationState::REVERSE;
+
+        // When
+        let completed = step_tweq_value(
+            &mut value,
+            &limits,
+            TweqAnimationConfig::ONEBOUNCE,
+            &mut state,
+            1.0,
+        );
+
+        // Then
+        assert!(completed);
+        assert_eq!(value, 0.0);
+        assert!(!state.contains(TweqAnimationState::REVERSE));
+    }
+
+    #[test]
+    fn test_step_tweq_values_completes_on_the_primary_axis() {
+        // Given - the second axis is primary, and the first axis is about to hit its limit
+        let mut values = [19.0, 0.0, 0.0];
+        let mut states = [TweqAnimationState::ON; 3];
+        let axis_limits = [
+            limits(10.0, 0.0, 20.0),
+            limits(10.0, 0.0, 20.0),
+            limits(0.0, 0.0, 0.0),
+        ];
+        let configs = [TweqAnimationConfig::empty(); 3];
+
+        // When - the first axis completes, and then the second one does
+        let first = step_tweq_values(&mut values, &mut states, &axis_limits, &configs, 2, 0.5);
+        let second = step_tweq_values(&mut values, &mut states, &axis_limits, &configs, 2, 2.0);
+
+        // Then - only the primary axis completes the tweq, and the axis without a rate stays put
+        assert_eq!(first, None);
+        assert_eq!(second, Some(false));
+        assert_eq!(values[2], 0.0);
+    }
+
+    #[test]
+    fn test_complete_tweq_applies_halt_action() {
+        // Given
+        let entity_id = World::new().add_entity(());
+        let mut effects = EffectQueue::new();
+        let mut stopped_state = TweqAnimationState::ON;
+        let mut continued_state = TweqAnimationState::ON;
+
+        // When
+        complete_tweq(
+            entity_id,
+            TweqType::Rotate,
+            TweqHalt::STOP_TWEQ,
+            false,
+            &mut stopped_state,
+            &mut effects,
+        );
+        complete_tweq(
+            entity_id,
+            TweqType::Rotate,
+            TweqHalt::CONTINUE,
+            false,
+            &mut continued_state,
+            &mut effects,
+        );
+        complete_tweq(
+            entity_id,
+            TweqType::Rotate,
+            TweqHalt::DESTROY_OBJECT,
+            true,
+            &mut TweqAnimationState::ON,
+            &mut effects,
+        );
+
+        // Then - stopping turns the tweq off, continuing leaves it on, and destroying removes the
+        // object - and the scripts hear about every one
+        assert!(!stopped_state.contains(TweqAnimationState::ON));
+        assert!(continued_state.contains(TweqAnimationState::ON));
+        let effects = effects.flush();
+        assert_eq!(effects.len(), 4);
+        assert!(matches!(effects[2], Effect::DestroyEntity { .. }));
+        assert!(matches!(
+            effects[3],
+            Effect::Send {
+                msg: Message {
+                    payload: MessagePayload::TweqComplete {
+                        is_reverse: true,
+                        ..
+                    },
+                    ..
+                }
+            }
+        ));
     }
 }
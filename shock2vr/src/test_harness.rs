@@ -0,0 +1,538 @@
+///
+/// test_harness.rs
+///
+/// Harness for testing gameplay logic - scripts, messages, and effects - without a renderer.
+///
+/// A harness can be booted from synthetic `EntitySaveData` fixtures (built with `EntityFixtures`),
+/// or from a real mission's entities. Tests send messages to entities, step time forward, and then
+/// assert on quest bits, entities, positions, and the effects that were emitted.
+///
+/// Only the effects that affect that state are applied (quest bits, key cards, destroying and
+/// moving entities) - everything else is recorded, so tests can assert on it.
+///
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader},
+    time::Duration,
+};
+
+use cgmath::{vec3, Quaternion, Vector3};
+use dark::{
+    properties::{
+        Link, Links, PropLocalPlayer, PropPosition, PropTemplateId, QuestBitValue, ToLink,
+        WrappedEntityId,
+    },
+    ss2_entity_info,
+};
+use shipyard::{
+    EntitiesView, EntityId, Get, IntoIter, IntoWithId, TupleAddComponent, UniqueView,
+    UniqueViewMut, View, ViewMut, World,
+};
+
+use crate::{
+    mission::{
+        entity_creator, entity_populator::populate_from_entity_info, EffectQueue,
+        GlobalEntityMetadata, GlobalTemplateIdMap, PlayerInfo,
+    },
+    physics::PhysicsWorld,
+    quest_info::QuestInfo,
+    runtime_props::RuntimePropDoNotSerialize,
+    save_load::EntitySaveData,
+    scripts::{Effect, Message, MessagePayload, ScriptWorld},
+    time::Time,
+};
+
+// Retail data, relative to the crate - only needed for tests that load a real mission
+const TEST_DATA_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../Data");
+
+pub struct MissionHarness {
+    pub world: World,
+    physics: PhysicsWorld,
+    script_world: ScriptWorld,
+    template_to_entity_id: HashMap<i32, WrappedEntityId>,
+    time: Time,
+    effects: Vec<Effect>,
+}
+
+impl MissionHarness {
+    ///
+    /// from_save_data
+    ///
+    /// Boot the harness from saved (or synthetic) entities. Every entity gets its scripts.
+    pub fn from_save_data(save_data: &EntitySaveData) -> MissionHarness {
+        let mut world = World::new();
+        let (template_to_entity_id, _) = save_data.instantiate(&mut world);
+
+        let entities = all_entities(&world);
+        Self::create(world, template_to_entity_id, entities)
+    }
+
+    ///
+    /// load
+    ///
+    /// Boot the harness from a real mission, ie 'medsci1.mis'
+    pub fn load(mission_name: &str) -> MissionHarness {
+        Self::from_readers(
+            &mut open_data_file("shock2.gam"),
+            &mut open_data_file(mission_name),
+        )
+    }
+
+    ///
+    /// from_readers
+    ///
+    /// Boot the harness from a gamesys and mission - either retail files, or synthetic ones from
+    /// `dark_fixtures`. Only the entities are loaded, so rooms (which come from the level geometry)
+    /// aren't available. Like in game, only entities with a position get scripts.
+    pub fn from_readers<R: io::Read + io::Seek + 'static>(
+        gamesys_reader: &mut R,
+        mission_reader: &mut R,
+    ) -> MissionHarness {
+        let (properties, links, links_with_data) = dark::properties::get::<R>();
+
+        let gamesys = dark::gamesys::read(gamesys_reader, &links, &links_with_data, &properties);
+        let (obj_map, mission_entity_info) =
+            dark::mission::read_entities(mission_reader, &links, &links_with_data, &properties);
+        let entity_info = ss2_entity_info::merge_with_gamesys(&mission_entity_info, &gamesys);
+
+        let mut world = World::new();
+        let template_to_entity_id =
+            populate_from_entity_info(&entity_info, &mission_entity_info, &obj_map, &mut world);
+
+        let entities = world.run(|v_position: View<PropPosition>| {
+            v_position
+                .iter()
+                .with_id()
+                .map(|(entity_id, _)| entity_id)
+                .collect::<Vec<EntityId>>()
+        });
+        Self::create(world, template_to_entity_id, entities)
+    }
+
+    fn create(
+        mut world: World,
+        template_to_entity_id: HashMap<i32, WrappedEntityId>,
+        entities_with_scripts: Vec<EntityId>,
+    ) -> MissionHarness {
+        let mut script_world = ScriptWorld::new();
+        for entity_id in entities_with_scripts {
+            for script in entity_creator::scripts_for_entity(&world, entity_id) {
+                script_world.add_entity(entity_id, &script);
+            }
+        }
+
+        let player_entity = world.add_entity((PropLocalPlayer {}, RuntimePropDoNotSerialize {}));
+        let inventory_entity = world.add_entity(RuntimePropDoNotSerialize {});
+
+        world.add_unique(Time::default());
+        world.add_unique(QuestInfo::new());
+        world.add_unique(GlobalEntityMetadata(HashMap::new()));
+        world.add_unique(GlobalTemplateIdMap(template_to_entity_id.clone()));
+        world.add_unique(EffectQueue::new());
+        world.add_unique(PlayerInfo {
+            pos: vec3(0.0, 0.0, 0.0),
+            rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            entity_id: player_entity,
+            left_hand_entity_id: None,
+            right_hand_entity_id: None,
+            holstered_entity_ids: HashMap::new(),
+            inventory_entity_id: inventory_entity,
+        });
+
+        MissionHarness {
+            world,
+            physics: PhysicsWorld::new(),
+            script_world,
+            template_to_entity_id,
+            time: Time::default(),
+            effects: Vec::new(),
+        }
+    }
+
+    ///
+    /// entity
+    ///
+    /// The entity created for the template id - panics if there isn't one, since that means the
+    /// fixture or mission isn't what the test expects
+    pub fn entity(&self, template_id: i32) -> EntityId {
+        self.template_to_entity_id
+            .get(&template_id)
+            .unwrap_or_else(|| panic!("no entity for template id {template_id}"))
+            .0
+    }
+
+    pub fn send(&mut self, to: EntityId, payload: MessagePayload) {
+        self.script_world.dispatch(Message { to, payload });
+    }
+
+    ///
+    /// step
+    ///
+    /// Advance time, running scripts once. Messages sent by scripts are handled on the next step,
+    /// same as in game.
+    pub fn step(&mut self, elapsed: Duration) {
+        self.time = Time {
+            elapsed,
+            total: self.time.total + elapsed,
+        };
+        self.world.add_unique(self.time.clone());
+
+        let effects = self
+            .script_world
+            .update(&self.world, &self.physics, &self.time);
+        for effect in effects {
+            self.apply_effect(&effect);
+            self.effects.push(effect);
+        }
+    }
+
+    ///
+    /// run_for
+    ///
+    /// Step repeatedly, in increments of `step`, until `duration` has passed
+    pub fn run_for(&mut self, duration: Duration, step: Duration) {
+        let mut remaining = duration;
+        while !remaining.is_zero() {
+            let elapsed = step.min(remaining);
+            self.step(elapsed);
+            remaining -= elapsed;
+        }
+    }
+
+    pub fn quest_bit(&self, quest_bit_name: &str) -> QuestBitValue {
+        let quest_info = self.world.borrow::<UniqueView<QuestInfo>>().unwrap();
+        quest_info.read_quest_bit_value(quest_bit_name)
+    }
+
+    pub fn entity_exists(&self, entity_id: EntityId) -> bool {
+        let v_entities = self.world.borrow::<EntitiesView>().unwrap();
+        v_entities.is_alive(entity_id)
+    }
+
+    pub fn position(&self, entity_id: EntityId) -> Option<Vector3<f32>> {
+        let v_position = self.world.borrow::<View<PropPosition>>().unwrap();
+        v_position.get(entity_id).ok().map(|p| p.position)
+    }
+
+    ///
+    /// effects
+    ///
+    /// Every effect emitted so far, flattened, in the order they were emitted. Messages between
+    /// entities are delivered rather than recorded.
+    pub fn effects(&self) -> &[Effect] {
+        &self.effects
+    }
+
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+    }
+
+    fn apply_effect(&mut self, effect: &Effect) {
+        match effect {
+            Effect::SetQuestBit {
+                quest_bit_name,
+                quest_bit_value,
+            } => {
+                let mut quest_info = self.world.borrow::<UniqueViewMut<QuestInfo>>().unwrap();
+                quest_info.set_quest_bit_value(quest_bit_name, *quest_bit_value);
+            }
+            Effect::AcquireKeyCard { key_card } => {
+                let mut quest_info = self.world.borrow::<UniqueViewMut<QuestInfo>>().unwrap();
+                quest_info.add_key_card(key_card.clone());
+            }
+            // Slaying in game can also leave a corpse behind, which needs assets - here it just
+            // removes the entity
+            Effect::DestroyEntity { entity_id } | Effect::SlayEntity { entity_id } => {
+                self.script_world.remove_entity(*entity_id);
+                self.world.delete_entity(*entity_id);
+            }
+            Effect::SetPosition {
+                entity_id,
+                position,
+            } => self.update_position(*entity_id, |p| p.position = *position),
+            Effect::SetRotation {
+                entity_id,
+                rotation,
+            } => self.update_position(*entity_id, |p| p.rotation = *rotation),
+            Effect::SetPositionRotation {
+                entity_id,
+                position,
+                rotation,
+            } => self.update_position(*entity_id, |p| {
+                p.position = *position;
+                p.rotation = *rotation;
+            }),
+            _ => (),
+        }
+    }
+
+    fn update_position(&mut self, entity_id: EntityId, f: impl FnOnce(&mut PropPosition)) {
+        let mut v_position = self.world.borrow::<ViewMut<PropPosition>>().unwrap();
+        if let Ok(position) = (&mut v_position).get(entity_id) {
+            f(position);
+        }
+    }
+}
+
+///
+/// EntityFixtures
+///
+/// Builder for synthetic `EntitySaveData` - entities are created from real components, and then
+/// serialized the same way a save file is
+pub struct EntityFixtures {
+    world: World,
+    template_to_entity_id: HashMap<i32, WrappedEntityId>,
+}
+
+impl EntityFixtures {
+    pub fn new() -> EntityFixtures {
+        EntityFixtures {
+            world: World::new(),
+            template_to_entity_id: HashMap::new(),
+        }
+    }
+
+    pub fn add_entity<C: TupleAddComponent>(&mut self, template_id: i32, components: C) -> i32 {
+        let entity_id = self.world.add_entity(PropTemplateId { template_id });
+        self.world.add_component(entity_id, components);
+        self.template_to_entity_id
+            .insert(template_id, WrappedEntityId(entity_id));
+        template_id
+    }
+
+    pub fn add_link(&mut self, from_template_id: i32, to_template_id: i32, link: Link) {
+        let from = self.template_to_entity_id[&from_template_id].0;
+        let to = self.template_to_entity_id[&to_template_id];
+
+        let mut v_links = self.world.borrow::<ViewMut<Links>>().unwrap();
+        let to_link = ToLink {
+            to_template_id,
+            to_entity_id: Some(to),
+            link,
+        };
+        match (&mut v_links).get(from) {
+            Ok(links) => links.to_links.push(to_link),
+            Err(_) => {
+                drop(v_links);
+                self.world.add_component(
+                    from,
+                    Links {
+                        to_links: vec![to_link],
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn build(self) -> EntitySaveData {
+        let (all_properties, _, _) = dark::properties::get::<File>();
+
+        let all_entities = all_entities(&self.world)
+            .iter()
+            .map(|entity_id| entity_id.inner())
+            .collect();
+
+        let properties = all_properties
+            .iter()
+            .map(|prop| (prop.name(), prop.serialize(&self.world)))
+            .collect();
+
+        let v_links = self.world.borrow::<View<Links>>().unwrap();
+        let links = v_links
+            .iter()
+            .with_id()
+            .map(|(entity_id, links)| (entity_id.inner(), serde_json::to_value(links).unwrap()))
+            .collect();
+
+        EntitySaveData {
+            all_entities,
+            template_id_to_entity_id: self.template_to_entity_id.clone(),
+            properties,
+            links,
+        }
+    }
+}
+
+fn all_entities(world: &World) -> Vec<EntityId> {
+    let v_entities = world.borrow::<EntitiesView>().unwrap();
+    v_entities.iter().collect()
+}
+
+fn open_data_file(name: &str) -> BufReader<File> {
+    let path = format!("{TEST_DATA_PATH}/{name}");
+    let file = File::open(&path).unwrap_or_else(|_| panic!("unable to open {path}"));
+    BufReader::new(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dark::properties::{PropDelayTime, PropQuestBitName, PropQuestBitValue, PropScripts};
+
+    fn scripts(names: &[&str]) -> PropScripts {
+        PropScripts {
+            scripts: names.iter().map(|name| name.to_string()).collect(),
+            inherits: false,
+        }
+    }
+
+    fn quest_bit_setter(fixtures: &mut EntityFixtures, template_id: i32, name: &str) {
+        fixtures.add_entity(
+            template_id,
+            (
+                scripts(&["TrapQBSet"]),
+                PropQuestBitName(name.to_owned()),
+                PropQuestBitValue(QuestBitValue::COMPLETE),
+            ),
+        );
+    }
+
+    #[test]
+    fn test_trap_qb_set_sets_quest_bit_and_destroys_itself() {
+        // Given a TrapQBSet
+        let mut fixtures = EntityFixtures::new();
+        quest_bit_setter(&mut fixtures, 1, "note_found");
+        let mut harness = MissionHarness::from_save_data(&fixtures.build());
+        let trap = harness.entity(1);
+
+        // When it is turned on
+        harness.send(trap, MessagePayload::TurnOn { from: trap });
+        harness.step(Duration::from_millis(16));
+
+        // Then the quest bit is set, and the trap is gone
+        assert_eq!(harness.quest_bit("note_found"), QuestBitValue::COMPLETE);
+        assert!(!harness.entity_exists(trap));
+        assert!(harness.effects().iter().any(
+            |effect| matches!(effect, Effect::DestroyEntity { entity_id } if *entity_id == trap)
+        ));
+    }
+
+    #[test]
+    fn test_trap_router_forwards_to_switch_links() {
+        // Given a TrapRouter, switch-linked to a TrapQBSet
+        let mut fixtures = EntityFixtures::new();
+        fixtures.add_entity(1, scripts(&["TrapRouter"]));
+        quest_bit_setter(&mut fixtures, 2, "door_unlocked");
+        fixtures.add_link(1, 2, Link::SwitchLink);
+        let mut harness = MissionHarness::from_save_data(&fixtures.build());
+        let router = harness.entity(1);
+
+        // When the router is turned on, the message takes a step to be routed
+        harness.send(router, MessagePayload::TurnOn { from: router });
+        harness.step(Duration::from_millis(16));
+        assert_eq!(harness.quest_bit("door_unlocked"), QuestBitValue::UNKNOWN);
+
+        // Then the linked trap gets it on the next step
+        harness.step(Duration::from_millis(16));
+        assert_eq!(harness.quest_bit("door_unlocked"), QuestBitValue::COMPLETE);
+    }
+
+    #[test]
+    fn test_trap_delay_waits_before_forwarding() {
+        // Given a TrapDelay of two seconds, switch-linked to a TrapQBSet
+        let mut fixtures = EntityFixtures::new();
+        fixtures.add_entity(
+            1,
+            (
+                scripts(&["TrapDelay"]),
+                PropDelayTime {
+                    delay: Duration::from_secs(2),
+                },
+            ),
+        );
+        quest_bit_setter(&mut fixtures, 2, "delayed");
+        fixtures.add_link(1, 2, Link::SwitchLink);
+        let mut harness = MissionHarness::from_save_data(&fixtures.build());
+        let delay = harness.entity(1);
+
+        // When it is turned on, nothing happens before the delay...
+        harness.send(delay, MessagePayload::TurnOn { from: delay });
+        harness.run_for(Duration::from_secs(1), Duration::from_millis(100));
+        assert_eq!(harness.quest_bit("delayed"), QuestBitValue::UNKNOWN);
+
+        // ...but the message is forwarded after it
+        harness.run_for(Duration::from_secs(2), Duration::from_millis(100));
+        assert_eq!(harness.quest_bit("delayed"), QuestBitValue::COMPLETE);
+    }
+
+    #[test]
+    fn test_trap_teleport_moves_linked_entity() {
+        // Given a TrapTeleport, switch-linked to a crate somewhere else
+        let at = |x: f32| PropPosition {
+            position: vec3(x, 0.0, 0.0),
+            cell: 0,
+            rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+        };
+        let mut fixtures = EntityFixtures::new();
+        fixtures.add_entity(1, (scripts(&["TrapTeleport"]), at(10.0)));
+        fixtures.add_entity(2, at(-5.0));
+        fixtures.add_link(1, 2, Link::SwitchLink);
+        let mut harness = MissionHarness::from_save_data(&fixtures.build());
+        let (teleport, target) = (harness.entity(1), harness.entity(2));
+
+        // When the teleport is turned on
+        harness.clear_effects();
+        harness.send(teleport, MessagePayload::TurnOn { from: teleport });
+        harness.step(Duration::from_millis(16));
+
+        // Then the crate is moved to the teleport
+        assert_eq!(harness.position(target), Some(vec3(10.0, 0.0, 0.0)));
+        assert_eq!(harness.effects().len(), 1);
+    }
+
+    #[test]
+    fn test_mission_fixture_scripts() {
+        // Given a synthetic mission with a TrapQBSet - which gets scripts, since it has a position
+        let mut mission = dark_fixtures::mission(
+            &dark_fixtures::WorldRepBuilder::new(),
+            &dark_fixtures::TextureListBuilder::new(),
+        );
+        mission
+            .add_chunk("OBJ_MAP", dark_fixtures::obj_map(&[(1, "trap")]))
+            .add_chunk(
+                "P$Position",
+                dark_fixtures::property_chunk(&[(
+                    1,
+                    dark_fixtures::position_property(vec3(0.0, 0.0, 0.0), 0),
+                )]),
+            )
+            .add_chunk(
+                "P$Scripts",
+                dark_fixtures::property_chunk(&[(
+                    1,
+                    dark_fixtures::scripts_property(&["TrapQBSet"], true),
+                )]),
+            )
+            .add_chunk(
+                "P$QBName",
+                dark_fixtures::property_chunk(&[(1, dark_fixtures::string_property("fixture"))]),
+            );
+        let mut harness = MissionHarness::from_readers(
+            &mut dark_fixtures::gamesys().build_reader(),
+            &mut mission.build_reader(),
+        );
+        let trap = harness.entity(1);
+
+        // When it is turned on
+        harness.send(trap, MessagePayload::TurnOn { from: trap });
+        harness.step(Duration::from_millis(16));
+
+        // Then the quest bit is set
+        assert_eq!(harness.quest_bit("fixture"), QuestBitValue::COMPLETE);
+    }
+
+    #[test]
+    #[ignore = "requires the retail game data in Data/"]
+    fn test_load_real_mission() {
+        let harness = MissionHarness::load("earth.mis");
+
+        let entity_count = harness
+            .world
+            .borrow::<EntitiesView>()
+            .unwrap()
+            .iter()
+            .count();
+        assert!(entity_count > 0);
+    }
+}
@@ -1,7 +1,10 @@
 // Helper to convert the input context to a form more useful for gameplay / interacting with the world
 
+use std::collections::VecDeque;
+
 use cgmath::{
-    point3, vec3, Angle, Euler, InnerSpace, Matrix4, Quaternion, Rad, Rotation, Vector3, Zero,
+    point3, vec3, Angle, Euler, InnerSpace, Matrix4, Quaternion, Rad, Rotation, Transform, Vector3,
+    Zero,
 };
 use dark::properties::{FrobFlag, PropFrobInfo, PropModelName};
 use engine::scene::SceneObject;
@@ -11,15 +14,36 @@ use shipyard::{EntityId, Get, View, World};
 use tracing::{self, trace};
 
 use crate::{
+    holster::{HolsterSlot, Holsters},
     input_context::Hand,
     physics::{InternalCollisionGroups, PhysicsWorld, RayCastResult},
+    runtime_props::RuntimePropTransform,
     scripts::{script_util, Message, MessagePayload},
+    time::Time,
     util::{self, point3_to_vec3},
     vr_config::{self, Handedness},
 };
 
 const HAND_OFFSET: Vector3<f32> = vec3(0.0, 0.0, 0.0);
 
+// Number of poses kept to estimate how fast the hand is moving
+const POSE_HISTORY_SIZE: usize = 12;
+
+// Velocity is averaged over this much of the most recent history, to smooth out tracking jitter
+const VELOCITY_WINDOW_IN_SECONDS: f32 = 0.1;
+
+// How close the off hand needs to be to the secondary grip of a two-handed weapon to take hold
+const SECONDARY_GRIP_REACH: f32 = 0.15;
+
+// A tracked hand position, at a point in time - in play space, so turning, teleporting or walking
+// the player around doesn't look like the hand moving
+#[derive(Clone, Copy, Debug)]
+pub struct HandPose {
+    pub position: Vector3<f32>,
+    pub rotation: Quaternion<f32>,
+    pub time_in_seconds: f32,
+}
+
 #[derive(Clone)]
 pub struct VirtualHand {
     position: Vector3<f32>,
@@ -34,6 +58,8 @@ pub struct VirtualHand {
     hand_state: HandState,
 
     handedness: Handedness,
+
+    pose_history: VecDeque<HandPose>,
 }
 
 pub enum VirtualHandEffect {
@@ -51,6 +77,11 @@ pub enum VirtualHandEffect {
         rotation: Quaternion<f32>,
         scale: Vector3<f32>,
     },
+    // How fast a held entity is being swung
+    SetHeldVelocity {
+        entity_id: EntityId,
+        velocity: Vector3<f32>,
+    },
     SpawnEntity {
         template_id: i32,
         position: Vector3<f32>,
@@ -61,8 +92,20 @@ pub enum VirtualHandEffect {
         position: Vector3<f32>,
         rotation: Quaternion<f32>,
     },
+    // Let go of a held entity - it keeps the hand's motion, so it can be thrown
     DropItem {
         entity_id: EntityId,
+        velocity: Vector3<f32>,
+        angular_velocity: Vector3<f32>,
+    },
+    // Let go of a held entity at an empty holster slot, to put it away
+    HolsterItem {
+        entity_id: EntityId,
+        slot: HolsterSlot,
+    },
+    // Empty a holster slot - the hand then takes hold of the entity that was in it, like any other
+    UnholsterItem {
+        slot: HolsterSlot,
     },
 }
 
@@ -76,6 +119,11 @@ pub enum HandState {
         entity_id: EntityId,
         // rigid_body_handle: RigidBodyHandle,
     },
+
+    // Steadying a two-handed weapon held by the other hand, by its secondary grip
+    Supporting {
+        entity_id: EntityId,
+    },
 }
 
 impl VirtualHand {
@@ -92,13 +140,14 @@ impl VirtualHand {
             last_frobbed_entity: None,
             hand_state: HandState::Empty,
             handedness,
+            pose_history: VecDeque::new(),
         }
     }
     pub fn destroy_entity(&self, entity_to_destroy_id: EntityId) -> VirtualHand {
         match self.hand_state {
             // Nothing to do here!
             HandState::Empty => self.clone(),
-            HandState::Grabbing { entity_id } => {
+            HandState::Grabbing { entity_id } | HandState::Supporting { entity_id } => {
                 if entity_id == entity_to_destroy_id {
                     VirtualHand {
                         hand_state: HandState::Empty,
@@ -113,7 +162,7 @@ impl VirtualHand {
 
     pub fn get_held_entity(&self) -> Option<EntityId> {
         match self.hand_state {
-            HandState::Empty => None,
+            HandState::Empty | HandState::Supporting { .. } => None,
             HandState::Grabbing { entity_id, .. } => Some(entity_id),
         }
     }
@@ -129,6 +178,30 @@ impl VirtualHand {
         self.get_held_entity() == Some(entity_id)
     }
 
+    pub fn position(&self) -> Vector3<f32> {
+        self.position
+    }
+
+    ///
+    /// transform
+    ///
+    /// Where the hand is, in world space
+    pub fn transform(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(self.position) * Matrix4::from(self.rotation)
+    }
+
+    ///
+    /// velocity
+    ///
+    /// How fast the hand is moving, in world space, from the recent pose history
+    pub fn velocity(&self, play_space_rotation: Quaternion<f32>) -> Vector3<f32> {
+        world_velocities(&self.pose_history, play_space_rotation).0
+    }
+
+    pub fn pose_history(&self) -> &VecDeque<HandPose> {
+        &self.pose_history
+    }
+
     pub fn grab_entity(
         &self,
         _world: &World,
@@ -166,9 +239,76 @@ impl VirtualHand {
                     self.clone()
                 }
             }
+            HandState::Supporting { entity_id } => {
+                if entity_id == old_entity_id {
+                    VirtualHand {
+                        hand_state: HandState::Supporting {
+                            entity_id: new_entity_id,
+                        },
+                        ..self.clone()
+                    }
+                } else {
+                    self.clone()
+                }
+            }
         }
     }
 
+    ///
+    /// secondary_grip_position
+    ///
+    /// If this hand holds a two-handed weapon, the weapon and where its secondary grip is, in
+    /// world space
+    pub fn secondary_grip_position(&self, world: &World) -> Option<(EntityId, Vector3<f32>)> {
+        let entity_id = self.get_held_entity()?;
+        let grip = vr_config::get_secondary_grip_from_entity(entity_id, world)?;
+
+        let v_transform = world.borrow::<View<RuntimePropTransform>>().unwrap();
+        let transform = v_transform.get(entity_id).ok()?;
+        let position = transform.0.transform_point(point3(grip.x, grip.y, grip.z));
+        Some((entity_id, point3_to_vec3(position)))
+    }
+
+    ///
+    /// two_handed_aim
+    ///
+    /// When one hand holds a two-handed weapon and the other is supporting it, the weapon is
+    /// aimed along the line between them. Returns where the weapon should be, overriding the
+    /// one-handed position from the hand holding it.
+    pub fn two_handed_aim(
+        world: &World,
+        left_hand: &VirtualHand,
+        right_hand: &VirtualHand,
+    ) -> Option<VirtualHandEffect> {
+        let (primary, support) = match (left_hand.hand_state, right_hand.hand_state) {
+            (_, HandState::Supporting { .. }) => (left_hand, right_hand),
+            (HandState::Supporting { .. }, _) => (right_hand, left_hand),
+            _ => return None,
+        };
+
+        let entity_id = match support.hand_state {
+            HandState::Supporting { entity_id } if primary.is_holding(entity_id) => entity_id,
+            _ => return None,
+        };
+
+        let grip = vr_config::get_secondary_grip_from_entity(entity_id, world)?;
+        let vr_offsets = get_held_position_orientation(entity_id, world, primary.handedness);
+        let position = primary.position + vr_offsets.offset;
+        let rotation = two_handed_rotation(
+            position,
+            primary.rotation * vr_offsets.rotation,
+            grip,
+            support.position,
+        );
+
+        Some(VirtualHandEffect::SetPositionRotation {
+            entity_id,
+            position,
+            rotation,
+            scale: vec3(1.0, 1.0, 1.0),
+        })
+    }
+
     pub fn update(
         prev: &VirtualHand,
         physics: &PhysicsWorld,
@@ -176,10 +316,22 @@ impl VirtualHand {
         pawn_pos: Vector3<f32>,
         pawn_rot: Quaternion<f32>,
         input_hand: &Hand,
+        other_hand: &VirtualHand,
+        holsters: &Holsters,
+        time: &Time,
     ) -> (VirtualHand, Vec<VirtualHandEffect>) {
         let handedness = prev.handedness;
         let hand_position = pawn_pos + HAND_OFFSET + pawn_rot.rotate_vector(input_hand.position);
         let hand_rotation = pawn_rot * input_hand.rotation;
+        let pose_history = record_pose(
+            &prev.pose_history,
+            HandPose {
+                position: input_hand.position,
+                rotation: input_hand.rotation,
+                time_in_seconds: time.total.as_secs_f32(),
+            },
+        );
+        let (velocity, angular_velocity) = world_velocities(&pose_history, pawn_rot);
 
         // Also do a raycast to provide the 'Hover' effect
         let ray_start = point3(hand_position.x, hand_position.y, hand_position.z);
@@ -207,9 +359,32 @@ impl VirtualHand {
                 // See what we're hitting
                 let mut msgs = Vec::new();
 
-                // If we're holding onto something, but not grabbing, we can drop it
-                if input_hand.squeeze_value < 0.5 {
-                    let mut msgs = vec![VirtualHandEffect::DropItem { entity_id }];
+                // Letting go at an empty holster slot puts the item away, instead of dropping it
+                let maybe_holster_slot = holsters
+                    .empty_slot_near(hand_position)
+                    .filter(|_| input_hand.squeeze_value < 0.5);
+
+                if let Some(slot) = maybe_holster_slot {
+                    let updated_hand = VirtualHand {
+                        position: hand_position,
+                        rotation: hand_rotation,
+                        trigger_value: input_hand.trigger_value,
+                        squeeze_value: input_hand.squeeze_value,
+                        raytrace_hit: None,
+                        last_frobbed_entity: None,
+                        hand_state: HandState::Empty,
+                        handedness,
+                        pose_history,
+                    };
+                    let msgs = vec![VirtualHandEffect::HolsterItem { entity_id, slot }];
+                    (updated_hand, msgs)
+                } else if input_hand.squeeze_value < 0.5 {
+                    // If we're holding onto something, but not grabbing, we can drop it
+                    let mut msgs = vec![VirtualHandEffect::DropItem {
+                        entity_id,
+                        velocity,
+                        angular_velocity,
+                    }];
 
                     let result_copy = result.clone();
                     if let Some(ray_cast_result) = result_copy {
@@ -234,6 +409,7 @@ impl VirtualHand {
                         last_frobbed_entity: None,
                         hand_state: HandState::Empty,
                         handedness,
+                        pose_history,
                     };
                     (updated_hand, msgs)
                 } else {
@@ -274,6 +450,11 @@ impl VirtualHand {
                         scale: vec3(1.0, 1.0, 1.0), //vr_offsets.scale,
                     });
 
+                    msgs.push(VirtualHandEffect::SetHeldVelocity {
+                        entity_id,
+                        velocity,
+                    });
+
                     let updated_hand = VirtualHand {
                         position: hand_position,
                         rotation: hand_rotation,
@@ -283,18 +464,44 @@ impl VirtualHand {
                         last_frobbed_entity: None,
                         hand_state: next_hand_state,
                         handedness,
+                        pose_history,
                     };
                     (updated_hand, msgs)
                 }
             }
+            HandState::Supporting { entity_id } => {
+                // Let go of the weapon when the squeeze is released, or the other hand drops it
+                let hand_state =
+                    if input_hand.squeeze_value < 0.5 || !other_hand.is_holding(entity_id) {
+                        HandState::Empty
+                    } else {
+                        prev.hand_state
+                    };
+
+                let updated_hand = VirtualHand {
+                    position: hand_position,
+                    rotation: hand_rotation,
+                    trigger_value: input_hand.trigger_value,
+                    squeeze_value: input_hand.squeeze_value,
+                    raytrace_hit: None,
+                    last_frobbed_entity: None,
+                    hand_state,
+                    handedness,
+                    pose_history,
+                };
+                (updated_hand, Vec::new())
+            }
             HandState::Empty => handle_empty_hand_state(
                 handedness,
                 hand_position,
                 hand_rotation,
+                pose_history,
                 prev.last_frobbed_entity,
                 world,
                 physics,
                 input_hand,
+                other_hand.secondary_grip_position(world),
+                holsters.holstered_item_near(hand_position),
             ),
         };
 
@@ -377,10 +584,13 @@ fn handle_empty_hand_state(
     handedness: Handedness,
     hand_position: Vector3<f32>,
     hand_rotation: Quaternion<f32>,
+    pose_history: VecDeque<HandPose>,
     frobbed_entity: Option<EntityId>,
     world: &World,
     physics: &PhysicsWorld,
     input_hand: &Hand,
+    secondary_grip: Option<(EntityId, Vector3<f32>)>,
+    holstered_item: Option<(HolsterSlot, EntityId)>,
 ) -> (VirtualHand, Vec<VirtualHandEffect>) {
     let ray_start = point3(hand_position.x, hand_position.y, hand_position.z);
     let forward = hand_rotation.rotate_vector(vec3(0.0, 0.0, -1.0));
@@ -435,8 +645,26 @@ fn handle_empty_hand_state(
         last_frobbed_entity = None
     }
 
+    // Taking hold of a two-handed weapon in the other hand comes first - otherwise, the raycast
+    // would grab the weapon right out of it
+    let supported_entity = secondary_grip
+        .filter(|(_, grip_position)| {
+            (grip_position - hand_position).magnitude() < SECONDARY_GRIP_REACH
+        })
+        .map(|(entity_id, _)| entity_id);
+
     if input_hand.squeeze_value > 0.5 {
-        if let Some(RayCastResult {
+        if let Some(entity_id) = supported_entity {
+            next_hand_state = HandState::Supporting { entity_id };
+        } else if let Some((slot, entity_id)) = holstered_item {
+            msgs.push(VirtualHandEffect::UnholsterItem { slot });
+            msgs.push(VirtualHandEffect::HoldItem {
+                entity_id,
+                position: hand_position,
+                rotation: hand_rotation,
+            });
+            next_hand_state = HandState::Grabbing { entity_id };
+        } else if let Some(RayCastResult {
             hit_point: _,
             hit_normal: _,
             maybe_entity_id: Some(entity_id),
@@ -467,10 +695,112 @@ fn handle_empty_hand_state(
         last_frobbed_entity,
         hand_state: next_hand_state,
         handedness,
+        pose_history,
     };
     (updated_hand, msgs)
 }
 
+///
+/// two_handed_rotation
+///
+/// Turn a weapon about where it's held, so its secondary grip lines up with the off hand. The
+/// weapon takes the shortest turn, so it keeps the roll from the hand holding it.
+fn two_handed_rotation(
+    position: Vector3<f32>,
+    rotation: Quaternion<f32>,
+    secondary_grip: Vector3<f32>,
+    off_hand_position: Vector3<f32>,
+) -> Quaternion<f32> {
+    let grip_direction = rotation.rotate_vector(secondary_grip);
+    let aim_direction = off_hand_position - position;
+    if grip_direction.magnitude() <= f32::EPSILON || aim_direction.magnitude() <= f32::EPSILON {
+        return rotation;
+    }
+
+    Quaternion::from_arc(grip_direction.normalize(), aim_direction.normalize(), None) * rotation
+}
+
+fn record_pose(history: &VecDeque<HandPose>, pose: HandPose) -> VecDeque<HandPose> {
+    let mut history = history.clone();
+    // Time went backwards - like a level transition resetting the clock - so the history is stale
+    if history
+        .back()
+        .map_or(false, |last| last.time_in_seconds > pose.time_in_seconds)
+    {
+        history.clear();
+    }
+    history.push_back(pose);
+    while history.len() > POSE_HISTORY_SIZE {
+        history.pop_front();
+    }
+    history
+}
+
+///
+/// estimate_velocity
+///
+/// Average velocity over the most recent poses, within the velocity window
+pub fn estimate_velocity(history: &VecDeque<HandPose>) -> Vector3<f32> {
+    match velocity_window(history) {
+        Some((oldest, newest, elapsed)) => (newest.position - oldest.position) / elapsed,
+        None => Vector3::zero(),
+    }
+}
+
+///
+/// estimate_angular_velocity
+///
+/// Average angular velocity over the most recent poses, as an axis scaled by radians per second
+pub fn estimate_angular_velocity(history: &VecDeque<HandPose>) -> Vector3<f32> {
+    let (oldest, newest, elapsed) = match velocity_window(history) {
+        Some(window) => window,
+        None => return Vector3::zero(),
+    };
+
+    let mut delta = newest.rotation * oldest.rotation.invert();
+    // Take the short way around
+    if delta.s < 0.0 {
+        delta = -delta;
+    }
+
+    let sin_half_angle = delta.v.magnitude();
+    if sin_half_angle <= f32::EPSILON {
+        return Vector3::zero();
+    }
+
+    let angle = 2.0 * f32::atan2(sin_half_angle, delta.s);
+    delta.v / sin_half_angle * angle / elapsed
+}
+
+// Linear and angular velocity of the hand, from its play space history, turned to world space
+fn world_velocities(
+    history: &VecDeque<HandPose>,
+    play_space_rotation: Quaternion<f32>,
+) -> (Vector3<f32>, Vector3<f32>) {
+    (
+        play_space_rotation.rotate_vector(estimate_velocity(history)),
+        play_space_rotation.rotate_vector(estimate_angular_velocity(history)),
+    )
+}
+
+// The oldest and newest poses to estimate velocity from, and the time between them. If the frame
+// took longer than the window, fall back to the previous pose.
+fn velocity_window(history: &VecDeque<HandPose>) -> Option<(&HandPose, &HandPose, f32)> {
+    let newest = history.back()?;
+    let oldest = history
+        .iter()
+        .find(|pose| newest.time_in_seconds - pose.time_in_seconds <= VELOCITY_WINDOW_IN_SECONDS)
+        .filter(|pose| pose.time_in_seconds < newest.time_in_seconds)
+        .or_else(|| history.iter().rev().nth(1))?;
+
+    let elapsed = newest.time_in_seconds - oldest.time_in_seconds;
+    if elapsed <= f32::EPSILON {
+        return None;
+    }
+
+    Some((oldest, newest, elapsed))
+}
+
 ///
 /// get_held_position_orientation
 ///
@@ -513,3 +843,103 @@ fn resolve_hit_proxy_entity(world: &World, ray_cast_result: RayCastResult) -> Ra
         ..ray_cast_result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Rotation3;
+
+    use super::*;
+
+    fn pose_at(x: f32, time_in_seconds: f32) -> HandPose {
+        HandPose {
+            position: vec3(x, 0.0, 0.0),
+            rotation: Quaternion::new(1.0, 0.0, 0.0, 0.0),
+            time_in_seconds,
+        }
+    }
+
+    #[test]
+    fn test_estimate_velocity_uses_recent_window() {
+        // Given - a hand that was still, and then moved at 2m/s
+        let mut history = VecDeque::new();
+        history = record_pose(&history, pose_at(0.0, 0.0));
+        history = record_pose(&history, pose_at(0.0, 0.5));
+        history = record_pose(&history, pose_at(0.1, 0.55));
+        history = record_pose(&history, pose_at(0.2, 0.6));
+
+        // When
+        let velocity = estimate_velocity(&history);
+
+        // Then
+        assert!((velocity.x - 2.0).abs() < 0.001);
+        assert_eq!(velocity.y, 0.0);
+    }
+
+    #[test]
+    fn test_velocity_is_turned_from_play_space_to_world_space() {
+        // Given - a hand moving along x in play space, with the player turned a quarter turn left
+        let mut history = VecDeque::new();
+        history = record_pose(&history, pose_at(0.0, 0.0));
+        history = record_pose(&history, pose_at(0.1, 0.05));
+        let play_space_rotation = Quaternion::from_angle_y(Rad(std::f32::consts::FRAC_PI_2));
+
+        // When
+        let (velocity, _) = world_velocities(&history, play_space_rotation);
+
+        // Then - the hand moves forward, along -z, in the world
+        assert!((velocity - vec3(0.0, 0.0, -2.0)).magnitude() < 0.001);
+    }
+
+    #[test]
+    fn test_record_pose_resets_when_time_goes_backwards() {
+        // Given
+        let mut history = VecDeque::new();
+        for i in 0..(POSE_HISTORY_SIZE * 2) {
+            history = record_pose(&history, pose_at(i as f32, i as f32));
+        }
+
+        // When
+        let reset = record_pose(&history, pose_at(0.0, 0.0));
+
+        // Then
+        assert_eq!(history.len(), POSE_HISTORY_SIZE);
+        assert_eq!(reset.len(), 1);
+        assert_eq!(estimate_velocity(&reset), Vector3::zero());
+    }
+
+    #[test]
+    fn test_estimate_angular_velocity_around_axis() {
+        // Given - a hand turning a quarter turn around y, over a sixteenth of a second
+        let mut history = VecDeque::new();
+        history = record_pose(&history, pose_at(0.0, 1.0));
+        let mut turned = pose_at(0.0, 1.0625);
+        turned.rotation = Quaternion::from_angle_y(Rad(std::f32::consts::FRAC_PI_2));
+        history = record_pose(&history, turned);
+
+        // When
+        let angular_velocity = estimate_angular_velocity(&history);
+
+        // Then
+        assert!(angular_velocity.x.abs() < 0.001);
+        assert!((angular_velocity.y - std::f32::consts::FRAC_PI_2 / 0.0625).abs() < 0.01);
+        assert!(angular_velocity.z.abs() < 0.001);
+    }
+
+    #[test]
+    fn test_two_handed_rotation_aims_grip_at_off_hand() {
+        // Given - a weapon pointing forward, with the off hand reaching out to the right
+        let position = vec3(0.0, 1.0, 0.0);
+        let rotation = Quaternion::new(1.0, 0.0, 0.0, 0.0);
+        let secondary_grip = vec3(0.0, 0.0, -0.5);
+        let off_hand_position = vec3(0.4, 1.0, 0.0);
+
+        // When
+        let aimed = two_handed_rotation(position, rotation, secondary_grip, off_hand_position);
+
+        // Then - the barrel points at the off hand, and the weapon stays upright
+        let forward = aimed.rotate_vector(vec3(0.0, 0.0, -1.0));
+        let up = aimed.rotate_vector(vec3(0.0, 1.0, 0.0));
+        assert!((forward - vec3(1.0, 0.0, 0.0)).magnitude() < 0.001);
+        assert!((up - vec3(0.0, 1.0, 0.0)).magnitude() < 0.001);
+    }
+}
@@ -5,6 +5,8 @@ use dark::properties::PropModelName;
 use once_cell::sync::Lazy;
 use shipyard::{EntityId, Get, View, World};
 
+use crate::runtime_props::RuntimePropVhots;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Handedness {
     Left,
@@ -54,6 +56,9 @@ struct VRHandModelAdjustments {
     left_hand: VRHandModelPerHandAdjustments,
     right_hand: VRHandModelPerHandAdjustments,
     projectile_rotation: Quaternion<f32>,
+    // For two-handed weapons, where the off hand can take hold of it - as a fraction of the way
+    // from the grip to the muzzle vhot
+    secondary_grip: Option<f32>,
 }
 
 impl VRHandModelAdjustments {
@@ -66,6 +71,7 @@ impl VRHandModelAdjustments {
             left_hand,
             right_hand,
             projectile_rotation,
+            secondary_grip: None,
         }
     }
 
@@ -78,6 +84,13 @@ impl VRHandModelAdjustments {
             ..self
         }
     }
+
+    pub fn with_secondary_grip(self, secondary_grip: f32) -> VRHandModelAdjustments {
+        VRHandModelAdjustments {
+            secondary_grip: Some(secondary_grip),
+            ..self
+        }
+    }
 }
 
 static HAND_MODEL_POSITIONING: Lazy<HashMap<&str, VRHandModelAdjustments>> = Lazy::new(|| {
@@ -109,7 +122,7 @@ static HAND_MODEL_POSITIONING: Lazy<HashMap<&str, VRHandModelAdjustments>> = Laz
     // relative ot the virtual hand
     let items = vec![
         // Weapons
-        ("atek_h", held_weapon.clone()),
+        ("atek_h", held_weapon.clone().with_secondary_grip(0.6)),
         ("amp_h", held_weapon.clone()),
         (
             "lasehand",
@@ -117,9 +130,9 @@ static HAND_MODEL_POSITIONING: Lazy<HashMap<&str, VRHandModelAdjustments>> = Laz
                 .clone()
                 .with_projectile_rotation(Quaternion::from_angle_y(Deg(12.))),
         ),
-        ("empgun", held_weapon.clone()),
+        ("empgun", held_weapon.clone().with_secondary_grip(0.5)),
         ("wrench_h", default.clone()),
-        ("sg_w", held_weapon.clone()),
+        ("sg_w", held_weapon.clone().with_secondary_grip(0.6)),
         // World items
         ("battery", held_item.clone()),
         ("batteryb", held_item.clone()),
@@ -169,6 +182,26 @@ pub fn get_projectile_rotation_from_entity(entity_id: EntityId, world: &World) -
     }
 }
 
+///
+/// get_secondary_grip_from_entity
+///
+/// For two-handed weapons, where the off hand holds it, in model space
+pub fn get_secondary_grip_from_entity(entity_id: EntityId, world: &World) -> Option<Vector3<f32>> {
+    let v_model_name = world.borrow::<View<PropModelName>>().unwrap();
+    let model_name = v_model_name
+        .get(entity_id)
+        .map(|sz| sz.0.to_ascii_lowercase())
+        .ok()?;
+
+    let amount = HAND_MODEL_POSITIONING
+        .get(model_name.as_str())?
+        .secondary_grip?;
+
+    let v_vhots = world.borrow::<View<RuntimePropVhots>>().unwrap();
+    let muzzle = v_vhots.get(entity_id).ok()?.0.first()?.point;
+    Some(vec3(muzzle.x, muzzle.y, muzzle.z) * amount)
+}
+
 pub fn get_vr_hand_model_adjustments_from_model(
     model_name: &str,
     handedness: Handedness,
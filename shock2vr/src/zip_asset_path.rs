@@ -6,10 +6,11 @@ use std::{
     sync::Mutex,
 };
 
-use engine::assets::asset_paths::{AbstractAssetPath, ReadableAndSeekable};
+use engine::assets::asset_paths::{AbstractAssetPath, AssetListing, ReadableAndSeekable};
 use zip::ZipArchive;
 
 pub struct ZipAssetPath {
+    zip_path: String,
     archive: Mutex<ZipArchive<BufReader<File>>>,
     asset_to_path: HashMap<String, String>,
 }
@@ -20,7 +21,7 @@ impl ZipAssetPath {
     }
 
     pub fn new2(zip_path: String, collapse_paths: bool) -> Box<ZipAssetPath> {
-        let file = File::open(zip_path).unwrap();
+        let file = File::open(&zip_path).unwrap();
         let reader = BufReader::new(file);
 
         let mut archive = zip::ZipArchive::new(reader).unwrap();
@@ -53,6 +54,7 @@ impl ZipAssetPath {
             }
         }
         Box::new(ZipAssetPath {
+            zip_path,
             archive: Mutex::new(archive),
             asset_to_path,
         })
@@ -77,4 +79,28 @@ impl AbstractAssetPath for ZipAssetPath {
         file.read_to_end(&mut file_contents).unwrap();
         Some(RefCell::new(Box::new(Cursor::new(file_contents))))
     }
+
+    fn describe(&self) -> String {
+        self.zip_path.to_owned()
+    }
+
+    // Lists the full path of each file in the archive - these can always be used as asset names,
+    // even when paths are collapsed.
+    fn list_assets(&self, _base_path: String) -> Vec<AssetListing> {
+        let mut full_paths = self
+            .asset_to_path
+            .values()
+            .map(|path| path.to_ascii_lowercase())
+            .collect::<Vec<String>>();
+        full_paths.sort();
+        full_paths.dedup();
+
+        full_paths
+            .into_iter()
+            .map(|asset_name| AssetListing {
+                source: self.describe(),
+                asset_name,
+            })
+            .collect()
+    }
 }